@@ -0,0 +1,56 @@
+use std::time::Instant;
+
+use crate::{
+    logic::{
+        evaluation::MaterialEvaluator,
+        nnue::Network,
+        search::{Search, SearchLimits},
+        transposition::TranspositionTable,
+    },
+    utils::fen::Fen,
+};
+
+/// middlegame and endgame positions the bench searches
+pub const BENCH_POSITIONS: [&str; 4] = [
+    "r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3",
+    "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+    "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+    "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+];
+
+/// Searches every bench position to the depth with a fresh table, once per
+/// thread count. The speedup is the time to reach the depth compared to a
+/// single thread.
+pub fn bench(depth: usize, network: Option<&Network>) {
+    let boards: Vec<_> = BENCH_POSITIONS
+        .iter()
+        .map(|fen| Fen::decode(fen).expect("the bench positions are valid"))
+        .collect();
+    println!("threads      nodes   time (s)      nodes/s  speedup");
+    let mut single_thread_time = None;
+    for threads in [1, 2, 4, 8] {
+        let start = Instant::now();
+        let mut nodes = 0;
+        for board in &boards {
+            let limits = SearchLimits {
+                depth,
+                threads,
+                ..Default::default()
+            };
+            let table = TranspositionTable::default();
+            let mut search =
+                Search::new(&MaterialEvaluator, limits).with_transposition_table(&table);
+            if let Some(network) = network {
+                search = search.with_network(network);
+            }
+            nodes += search.run(board, |_| {}).nodes;
+        }
+        let elapsed = start.elapsed().as_secs_f64().max(1e-9);
+        let single_thread_time = *single_thread_time.get_or_insert(elapsed);
+        println!(
+            "{threads:>7} {nodes:>10} {elapsed:>10.3} {:>12.0} {:>7.2}x",
+            nodes as f64 / elapsed,
+            single_thread_time / elapsed
+        );
+    }
+}
@@ -1,22 +1,51 @@
-use crate::models::game_state::GameState;
-use std::io;
+use crate::models::{
+    clock::format_duration, error::GameError, game_state::GameState, piece::PieceColor,
+};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
 
-use super::game::Game;
+use super::game::{Game, AUTOSAVE_FILE};
 
 pub struct CliDriver {}
 
 impl CliDriver {
     pub fn run_game() {
-        let mut game = Game::new();
+        Self::run(Game::new())
+    }
+
+    /// plays the given game, e.g. one resumed from a file
+    pub fn run(mut game: Game) {
+        println!("Enter moves like e2e4, or one of: resign, draw, accept, decline, abort, quit");
+        println!("Use 'save <file>' and 'load <file>' to store and continue games");
         loop {
             println!("It's {:?}'s turn", game.board.turn_color());
             println!("{}", game.board.to_pretty_string());
+            if let Some(clock) = &game.clock {
+                println!(
+                    "White {} | Black {}",
+                    format_duration(clock.remaining(PieceColor::White)),
+                    format_duration(clock.remaining(PieceColor::Black))
+                );
+            }
 
             let mut input = String::new();
-            io::stdin()
-                .read_line(&mut input)
-                .expect("Failed to read line");
-            let outcome = game.apply_input(input.trim());
+            let thinking = Instant::now();
+            let read = match io::stdin().read_line(&mut input) {
+                Ok(read) => read,
+                Err(e) => {
+                    println!("Reading the input failed: {e}");
+                    Self::autosave(&game);
+                    break;
+                }
+            };
+            // end of input or quitting, don't lose the game
+            if read == 0 || input.trim() == "quit" {
+                Self::autosave(&game);
+                break;
+            }
+            let outcome = Self::play(&mut game, input.trim(), thinking.elapsed());
             match outcome {
                 Err(s) => println!("Move failed with error: {}", s),
                 Ok(GameState::Ongoing) => println!("Move executed"),
@@ -27,7 +56,47 @@ impl CliDriver {
                 Ok(GameState::Stalemate) => {
                     println!("Game ended in stalemate!!!");
                 }
+                Ok(state) => {
+                    println!("{}", state.message());
+                    break;
+                }
             }
         }
     }
+
+    /// Handles a line of input of the player to move, who thought about it
+    /// for the given time. The time counts first, so a move comes too late
+    /// once the flag has fallen.
+    pub fn play(game: &mut Game, input: &str, elapsed: Duration) -> Result<GameState, GameError> {
+        if game.tick(elapsed).is_over() {
+            return Ok(game.state);
+        }
+        let color = game.board.turn_color();
+        match input {
+            command if command.starts_with("save ") => {
+                let path = command["save ".len()..].trim();
+                game.save(path).map(|_| game.state)
+            }
+            command if command.starts_with("load ") => {
+                let path = command["load ".len()..].trim();
+                Game::load(path).map(|loaded| {
+                    *game = loaded;
+                    game.state
+                })
+            }
+            "resign" => game.resign(color),
+            "draw" => game.offer_draw(color).map(|_| game.state),
+            "accept" => game.accept_draw(color),
+            "decline" => game.decline_draw(color).map(|_| game.state),
+            "abort" => game.abort(),
+            input => Ok(game.apply_input(input)?),
+        }
+    }
+
+    fn autosave(game: &Game) {
+        match game.save(AUTOSAVE_FILE) {
+            Ok(()) => println!("Game saved to {AUTOSAVE_FILE}"),
+            Err(e) => println!("Saving the game failed: {e}"),
+        }
+    }
 }
@@ -0,0 +1,40 @@
+use std::{error::Error, time::Duration};
+
+use super::{cli_driver::CliDriver, game::Game};
+use crate::models::{clock::TimeControl, game_state::GameState, piece::PieceColor};
+
+fn blitz() -> Game {
+    Game::with_time_control(TimeControl::fischer(
+        Duration::from_secs(60),
+        Duration::ZERO,
+    ))
+}
+
+#[test]
+fn thinking_time_runs_down_the_clock() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = blitz();
+    // action
+    let state = CliDriver::play(&mut game, "e2e4", Duration::from_secs(15))?;
+    // assert
+    assert_eq!(state, GameState::Ongoing);
+    assert_eq!(game.history.len(), 1);
+    assert_eq!(
+        game.remaining_time(PieceColor::White),
+        Some(Duration::from_secs(45))
+    );
+    Ok(())
+}
+
+#[test]
+fn flag_falls_before_the_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = blitz();
+    CliDriver::play(&mut game, "e2e4", Duration::from_secs(1))?;
+    // action
+    let state = CliDriver::play(&mut game, "e7e5", Duration::from_secs(61))?;
+    // assert
+    assert_eq!(state, GameState::WonOnTime(PieceColor::White));
+    assert_eq!(game.history.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,492 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    time::{Duration, Instant},
+};
+
+use log::{debug, info};
+
+use crate::{
+    logic::{
+        evaluation::EvalParams,
+        nnue::Network,
+        search::{Search, SearchLimits},
+        transposition::TranspositionTable,
+    },
+    models::{
+        board::Board,
+        error::{DataError, EngineError, FileError, ParseError},
+        game_state::GameState,
+        piece::PieceColor,
+        step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::{
+        elo::{MatchScore, SprtVerdict},
+        fen::Fen,
+        pgn::Pgn,
+    },
+};
+
+use super::{game::Game, uci::UciDriver};
+
+// depth searched when neither a depth nor a time is configured
+const DEFAULT_DEPTH: usize = 3;
+const MAX_DEPTH: usize = 64;
+
+/// How an engine of a match plays, decoded from comma separated settings,
+/// e.g. `depth=4,params=eval.params,name=new` or `cmd=/usr/bin/stockfish,movetime=100`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EngineConfig {
+    /// shown in the PGN and the results
+    pub name: Option<String>,
+    pub depth: Option<usize>,
+    /// milliseconds to think per move
+    pub movetime: Option<u64>,
+    /// network file the built-in engine evaluates with
+    pub network: Option<PathBuf>,
+    /// evaluation parameters of the built-in engine, if it has no network
+    pub params: Option<PathBuf>,
+    /// a UCI engine played instead of the built-in one, the program and
+    /// its arguments separated by spaces
+    pub command: Option<String>,
+}
+
+impl EngineConfig {
+    pub fn decode(encoded: &str) -> Result<Self, DataError> {
+        let mut res = Self::default();
+        for setting in encoded
+            .split(',')
+            .filter(|setting| !setting.trim().is_empty())
+        {
+            let (key, value) = setting
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(setting, 0, "a 'key=value' setting"))?;
+            let value = value.trim();
+            match key.trim() {
+                "name" => res.name = Some(value.to_string()),
+                "depth" => {
+                    res.depth = Some(
+                        value
+                            .parse()
+                            .map_err(|_| ParseError::new(value, 0, "a depth"))?,
+                    )
+                }
+                "movetime" => {
+                    res.movetime = Some(
+                        value
+                            .parse()
+                            .map_err(|_| ParseError::new(value, 0, "milliseconds"))?,
+                    )
+                }
+                "nnue" => res.network = Some(PathBuf::from(value)),
+                "params" => res.params = Some(PathBuf::from(value)),
+                "cmd" => res.command = Some(value.to_string()),
+                key => return Err(DataError::UnknownKey(key.to_string())),
+            }
+        }
+        Ok(res)
+    }
+
+    /// the name if configured, else the program or the depth
+    pub fn display_name(&self) -> String {
+        if let Some(name) = &self.name {
+            return name.clone();
+        }
+        let program = self.command.as_deref().and_then(|command| {
+            let program = Path::new(command.split_whitespace().next()?);
+            Some(program.file_name()?.to_string_lossy().to_string())
+        });
+        program.unwrap_or(format!("rustychess depth {}", self.depth()))
+    }
+
+    /// starts the engine the config describes
+    pub fn player(&self) -> Result<Box<dyn Player>, EngineError> {
+        Ok(match &self.command {
+            Some(_) => Box::new(UciPlayer::start(self.clone())?),
+            None => Box::new(BuiltinPlayer::new(self.clone())?),
+        })
+    }
+
+    fn depth(&self) -> usize {
+        match (self.depth, self.movetime) {
+            (Some(depth), _) => depth,
+            (None, Some(_)) => MAX_DEPTH,
+            (None, None) => DEFAULT_DEPTH,
+        }
+    }
+}
+
+/// An engine taking part in a match
+pub trait Player {
+    fn name(&self) -> String;
+
+    /// called before every game
+    fn new_game(&mut self) -> Result<(), EngineError>;
+
+    /// the step to play in the current position of the game
+    fn choose_step(&mut self, game: &Game) -> Result<StepKind, EngineError>;
+}
+
+/// The engine of this crate, searching with the material evaluation or a
+/// network
+pub struct BuiltinPlayer {
+    config: EngineConfig,
+    params: EvalParams,
+    network: Option<Network>,
+    table: TranspositionTable,
+}
+
+impl BuiltinPlayer {
+    pub fn new(config: EngineConfig) -> Result<Self, EngineError> {
+        let network = config.network.as_ref().map(Network::load).transpose()?;
+        let params = config.params.as_ref().map(EvalParams::load).transpose()?;
+        Ok(Self {
+            config,
+            params: params.unwrap_or_default(),
+            network,
+            table: TranspositionTable::default(),
+        })
+    }
+}
+
+impl Player for BuiltinPlayer {
+    fn name(&self) -> String {
+        self.config.display_name()
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.table.clear();
+        Ok(())
+    }
+
+    fn choose_step(&mut self, game: &Game) -> Result<StepKind, EngineError> {
+        let limits = SearchLimits {
+            depth: self.config.depth(),
+            deadline: self
+                .config
+                .movetime
+                .map(|ms| Instant::now() + Duration::from_millis(ms)),
+            ..Default::default()
+        };
+        let mut search = Search::new(&self.params, limits).with_transposition_table(&self.table);
+        if let Some(network) = &self.network {
+            search = search.with_network(network);
+        }
+        let info = search.run(&game.board, |_| {});
+        info.best_step()
+            .cloned()
+            .ok_or_else(|| EngineError::NoMove(self.name()))
+    }
+}
+
+/// An engine in another process, spoken to with the universal chess interface
+pub struct UciPlayer {
+    config: EngineConfig,
+    /// as the engine introduced itself
+    name: String,
+    process: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+}
+
+impl UciPlayer {
+    pub fn start(config: EngineConfig) -> Result<Self, EngineError> {
+        let command = config.command.clone().unwrap_or_default();
+        let mut args = command.split_whitespace();
+        let io_error = |error| EngineError::Io {
+            engine: command.clone(),
+            error,
+        };
+        let program = args
+            .next()
+            .ok_or_else(|| io_error(io::Error::from(io::ErrorKind::NotFound)))?;
+        let mut process = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(io_error)?;
+        let input = process.stdin.take().expect("stdin is piped");
+        let output = BufReader::new(process.stdout.take().expect("stdout is piped"));
+        let mut res = Self {
+            name: config.display_name(),
+            config,
+            process,
+            input,
+            output,
+        };
+        res.send("uci")?;
+        let id = res.wait_for("uciok")?;
+        if let Some(name) = id
+            .iter()
+            .find_map(|line| line.strip_prefix("id name "))
+            .filter(|_| res.config.name.is_none())
+        {
+            res.name = name.trim().to_string();
+        }
+        Ok(res)
+    }
+
+    fn send(&mut self, line: &str) -> Result<(), EngineError> {
+        debug!("{} <- {line}", self.name);
+        writeln!(self.input, "{line}")
+            .and_then(|_| self.input.flush())
+            .map_err(|error| self.io_error(error))
+    }
+
+    fn io_error(&self, error: io::Error) -> EngineError {
+        EngineError::Io {
+            engine: self.name.clone(),
+            error,
+        }
+    }
+
+    /// the lines the engine writes until one starting with the prefix
+    fn wait_for(&mut self, prefix: &str) -> Result<Vec<String>, EngineError> {
+        let mut lines = vec![];
+        loop {
+            let mut line = String::new();
+            let read = self
+                .output
+                .read_line(&mut line)
+                .map_err(|error| self.io_error(error))?;
+            if read == 0 {
+                return Err(EngineError::Quit(self.name.clone()));
+            }
+            debug!("{} -> {}", self.name, line.trim_end());
+            let done = line.starts_with(prefix);
+            lines.push(line);
+            if done {
+                return Ok(lines);
+            }
+        }
+    }
+}
+
+impl Player for UciPlayer {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.send("ucinewgame")?;
+        self.send("isready")?;
+        self.wait_for("readyok").map(|_| ())
+    }
+
+    fn choose_step(&mut self, game: &Game) -> Result<StepKind, EngineError> {
+        let chess960 = game.start.is_chess960();
+        let variant = game.start.variant();
+        if variant != VariantKind::Standard {
+            self.send(&format!(
+                "setoption name UCI_Variant value {}",
+                variant.encode_uci()
+            ))?;
+        }
+        self.send(&format!("setoption name UCI_Chess960 value {chess960}"))?;
+        let moves: Vec<String> = game
+            .history
+            .iter()
+            .map(|step| UciDriver::encode_step(step, chess960))
+            .collect();
+        let mut position = format!("position fen {}", Fen::encode(&game.start));
+        if !moves.is_empty() {
+            position += &format!(" moves {}", moves.join(" "));
+        }
+        self.send(&position)?;
+        match self.config.movetime {
+            Some(movetime) => self.send(&format!("go movetime {movetime}"))?,
+            None => self.send(&format!("go depth {}", self.config.depth()))?,
+        }
+        let lines = self.wait_for("bestmove")?;
+        let best = lines
+            .last()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .ok_or_else(|| EngineError::NoMove(self.name.clone()))?;
+        Ok(UciDriver::decode_step(&game.board, best)?)
+    }
+}
+
+impl Drop for UciPlayer {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.process.wait();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchOptions {
+    /// every opening is played twice, once with each color
+    pub openings: Vec<Board>,
+    /// games still going on after this many plies are drawn
+    pub max_plies: usize,
+}
+
+impl Default for MatchOptions {
+    fn default() -> Self {
+        Self {
+            openings: vec![Board::standard_board()],
+            max_plies: 400,
+        }
+    }
+}
+
+/// Games between two engines, keeping the score of the first one
+pub struct EngineMatch {
+    first: Box<dyn Player>,
+    second: Box<dyn Player>,
+    options: MatchOptions,
+    score: MatchScore,
+}
+
+impl EngineMatch {
+    pub fn new(first: Box<dyn Player>, second: Box<dyn Player>, options: MatchOptions) -> Self {
+        Self {
+            first,
+            second,
+            options,
+            score: MatchScore::default(),
+        }
+    }
+
+    /// Plays the game with the given number, counting from 0. Pairs of games
+    /// share an opening, the first engine has White in the even ones. An
+    /// engine that fails or plays an illegal move forfeits the game. The ply
+    /// limit ends it in a draw.
+    pub fn play_game(&mut self, number: usize) -> Result<Game, EngineError> {
+        let openings = &self.options.openings;
+        let opening = openings
+            .get(number / 2 % openings.len().max(1))
+            .ok_or(EngineError::NoOpenings)?;
+        let first_color = if number.is_multiple_of(2) {
+            PieceColor::White
+        } else {
+            PieceColor::Black
+        };
+        let mut game = Game::from_board(opening.clone());
+        self.first.new_game()?;
+        self.second.new_game()?;
+        while game.state == GameState::Ongoing {
+            if game.history.len() >= self.options.max_plies {
+                game.state = GameState::AdjudicatedDraw;
+                break;
+            }
+            let color = game.board.turn_color();
+            let player = if color == first_color {
+                &mut self.first
+            } else {
+                &mut self.second
+            };
+            // an engine that fails to move or plays an illegal move loses
+            let played = player
+                .choose_step(&game)
+                .and_then(|step| game.apply_stepkind(step).map_err(EngineError::from));
+            if let Err(e) = played {
+                info!("game {number}: {e}, {color:?} forfeits");
+                game.forfeit(color, e.to_string())
+                    .expect("the game is still going on");
+            }
+        }
+        match game.state.winner() {
+            Some(color) if color == first_color => self.score.wins += 1,
+            Some(_) => self.score.losses += 1,
+            None => self.score.draws += 1,
+        }
+        Ok(game)
+    }
+
+    /// Plays the games, appends them to the PGN file and prints the score
+    /// after every game, then the Elo difference and the SPRT of the elo0
+    /// and elo1 hypotheses
+    pub fn run(
+        &mut self,
+        games: usize,
+        pgn: impl AsRef<Path>,
+        elo0: f64,
+        elo1: f64,
+    ) -> Result<MatchScore, EngineError> {
+        let pgn = pgn.as_ref();
+        let mut file = File::options()
+            .create(true)
+            .append(true)
+            .open(pgn)
+            .map_err(|e| FileError::new(pgn, e))?;
+        for number in 0..games {
+            let game = self.play_game(number)?;
+            let (white, black) = self.players(number);
+            let tags = [
+                ("Event", "rustychess match".to_string()),
+                ("Round", (number + 1).to_string()),
+                ("White", white.clone()),
+                ("Black", black.clone()),
+            ];
+            writeln!(file, "{}", Pgn::encode_with_tags(&game, &tags))
+                .map_err(|e| FileError::new(pgn, e))?;
+            let score = self.score();
+            println!(
+                "game {}/{games}: {white} - {black} {}, score {}-{}-{}",
+                number + 1,
+                game.state.result(),
+                score.wins,
+                score.losses,
+                score.draws
+            );
+        }
+
+        let score = self.score();
+        let (first, second) = self.players(0);
+        println!();
+        println!(
+            "{first} vs {second}: {} wins, {} draws, {} losses ({:.1}%)",
+            score.wins,
+            score.draws,
+            score.losses,
+            score.ratio() * 100.0
+        );
+        let (elo, error) = score.elo();
+        println!("Elo difference: {elo:.1} +/- {error:.1}");
+        let sprt = score.sprt(elo0, elo1, 0.05, 0.05);
+        let verdict = match sprt.verdict {
+            SprtVerdict::H0 => "H0 accepted",
+            SprtVerdict::H1 => "H1 accepted",
+            SprtVerdict::Continue => "no decision yet",
+        };
+        println!(
+            "SPRT elo0 {elo0} elo1 {elo1}: LLR {:.2} ({:.2}, {:.2}), {verdict}",
+            sprt.llr, sprt.lower, sprt.upper
+        );
+        Ok(score)
+    }
+
+    /// one FEN per line, empty lines and lines starting with '#' are skipped
+    pub fn load_openings(
+        path: impl AsRef<Path>,
+        variant: VariantKind,
+    ) -> Result<Vec<Board>, DataError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|fen| Ok(Fen::decode_valid(fen, variant)?))
+            .collect()
+    }
+
+    /// the names of White and Black in the game with the given number
+    pub fn players(&self, number: usize) -> (String, String) {
+        let (first, second) = (self.first.name(), self.second.name());
+        if number.is_multiple_of(2) {
+            (first, second)
+        } else {
+            (second, first)
+        }
+    }
+
+    /// wins, draws and losses of the first engine so far
+    pub fn score(&self) -> MatchScore {
+        self.score
+    }
+}
@@ -0,0 +1,213 @@
+use std::{error::Error, fs, path::PathBuf};
+
+use super::{
+    engine_match::{EngineConfig, EngineMatch, MatchOptions, Player},
+    game::Game,
+    uci::UciDriver,
+};
+use crate::{
+    models::{
+        error::EngineError, game_state::GameState, piece::PieceColor, step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::{
+        fen::{Fen, STANDARD_FEN},
+        pgn::Pgn,
+    },
+};
+
+#[test]
+fn decodes_engine_configs() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let builtin = "depth=2,nnue=net.bin,params=eval.params,name=new";
+    let uci = "cmd=/usr/local/bin/rustychess uci, movetime=100";
+    // action
+    let builtin = EngineConfig::decode(builtin)?;
+    let uci = EngineConfig::decode(uci)?;
+    // assert
+    assert_eq!(builtin.depth, Some(2));
+    assert_eq!(builtin.network, Some(PathBuf::from("net.bin")));
+    assert_eq!(builtin.params, Some(PathBuf::from("eval.params")));
+    assert_eq!(builtin.display_name(), "new");
+    assert_eq!(uci.movetime, Some(100));
+    assert_eq!(
+        uci.command.as_deref(),
+        Some("/usr/local/bin/rustychess uci")
+    );
+    assert_eq!(uci.display_name(), "rustychess");
+    assert!(EngineConfig::decode("speed=fast").is_err());
+    assert!(EngineConfig::decode("depth=deep").is_err());
+    Ok(())
+}
+
+#[test]
+fn alternates_colors_and_keeps_score() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let deeper = EngineConfig::decode("depth=2,name=deeper")?.player()?;
+    let shallow = EngineConfig::decode("depth=1,name=shallow")?.player()?;
+    let options = MatchOptions {
+        // White mates in one, a depth of one is enough to see it
+        openings: vec![Fen::decode("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")?],
+        max_plies: 6,
+    };
+    let mut engine_match = EngineMatch::new(deeper, shallow, options);
+    // action
+    let first = engine_match.play_game(0)?;
+    let second = engine_match.play_game(1)?;
+    // assert
+    assert_eq!(first.state.result(), "1-0");
+    assert_eq!(second.state.result(), "1-0");
+    assert_eq!(engine_match.players(0), ("deeper".into(), "shallow".into()));
+    assert_eq!(engine_match.players(1), ("shallow".into(), "deeper".into()));
+    let score = engine_match.score();
+    assert_eq!((score.wins, score.draws, score.losses), (1, 0, 1));
+    Ok(())
+}
+
+#[test]
+fn long_games_are_adjudicated() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let first = EngineConfig::decode("depth=1")?.player()?;
+    let second = EngineConfig::decode("depth=1")?.player()?;
+    let options = MatchOptions {
+        max_plies: 4,
+        ..Default::default()
+    };
+    let mut engine_match = EngineMatch::new(first, second, options);
+    // action
+    let game = engine_match.play_game(0)?;
+    // assert
+    assert_eq!(game.state, GameState::AdjudicatedDraw);
+    assert_eq!(game.history.len(), 4);
+    assert_eq!(engine_match.score().draws, 1);
+    Ok(())
+}
+
+/// plays the given moves in UCI notation over and over
+struct ScriptedPlayer {
+    moves: Vec<&'static str>,
+    played: usize,
+}
+
+impl ScriptedPlayer {
+    fn boxed(moves: Vec<&'static str>) -> Box<dyn Player> {
+        Box::new(Self { moves, played: 0 })
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn name(&self) -> String {
+        "scripted".to_string()
+    }
+
+    fn new_game(&mut self) -> Result<(), EngineError> {
+        self.played = 0;
+        Ok(())
+    }
+
+    fn choose_step(&mut self, game: &Game) -> Result<StepKind, EngineError> {
+        let input = self.moves[self.played % self.moves.len()];
+        self.played += 1;
+        Ok(UciDriver::decode_step(&game.board, input)?)
+    }
+}
+
+#[test]
+fn repetitions_are_a_draw() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let white = ScriptedPlayer::boxed(vec!["g1f3", "f3g1"]);
+    let black = ScriptedPlayer::boxed(vec!["g8f6", "f6g8"]);
+    let mut engine_match = EngineMatch::new(white, black, MatchOptions::default());
+    // action
+    let game = engine_match.play_game(0)?;
+    // assert
+    assert_eq!(game.state, GameState::Repetition);
+    // the start position is back for the third time after two rounds
+    assert_eq!(game.history.len(), 8);
+    Ok(())
+}
+
+#[test]
+fn fifty_moves_are_a_draw() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let white = ScriptedPlayer::boxed(vec!["a1a2"]);
+    let black = ScriptedPlayer::boxed(vec!["e8d8"]);
+    let options = MatchOptions {
+        openings: vec![Fen::decode("4k3/8/8/8/8/8/8/R3K3 w - - 98 70")?],
+        ..Default::default()
+    };
+    let mut engine_match = EngineMatch::new(white, black, options);
+    // action
+    let game = engine_match.play_game(0)?;
+    // assert
+    assert_eq!(game.state, GameState::FiftyMoves);
+    assert_eq!(game.history.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn illegal_moves_forfeit_the_game() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let white = ScriptedPlayer::boxed(vec!["e2e5"]);
+    let black = ScriptedPlayer::boxed(vec!["e7e5"]);
+    let mut engine_match = EngineMatch::new(white, black, MatchOptions::default());
+    // action
+    let game = engine_match.play_game(0)?;
+    // assert
+    assert_eq!(game.state, GameState::Forfeit(PieceColor::White));
+    assert!(game.history.is_empty());
+    assert!(game.forfeit_reason.is_some());
+    assert_eq!(engine_match.score().losses, 1);
+    let pgn = Pgn::encode(&game);
+    assert!(pgn.contains("[Termination \"rules infraction\"]"));
+    assert!(pgn.contains("{ White forfeits: "));
+    let resumed = Pgn::decode(&pgn)?;
+    assert_eq!(resumed.state, game.state);
+    assert_eq!(resumed.forfeit_reason, game.forfeit_reason);
+    Ok(())
+}
+
+#[test]
+fn run_writes_every_game() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let pgn = std::env::temp_dir().join("rustychess_match_test.pgn");
+    let _ = fs::remove_file(&pgn);
+    let first = EngineConfig::decode("depth=1,name=first")?.player()?;
+    let second = EngineConfig::decode("depth=1,name=second")?.player()?;
+    let options = MatchOptions {
+        max_plies: 2,
+        ..Default::default()
+    };
+    let mut engine_match = EngineMatch::new(first, second, options);
+    // action
+    let score = engine_match.run(2, &pgn, 0.0, 10.0)?;
+    let written = fs::read_to_string(&pgn).map_err(|e| e.to_string())?;
+    fs::remove_file(&pgn).map_err(|e| e.to_string())?;
+    // assert
+    assert_eq!(score.draws, 2);
+    let games = Pgn::split_games(&written);
+    assert_eq!(games.len(), 2);
+    assert!(games[1].contains("[White \"second\"]"));
+    Ok(())
+}
+
+#[test]
+fn load_openings_skips_comments() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let valid = std::env::temp_dir().join("rustychess_openings_test.txt");
+    let invalid = std::env::temp_dir().join("rustychess_invalid_openings_test.txt");
+    fs::write(&valid, format!("# start position\n\n{STANDARD_FEN}\n"))
+        .map_err(|e| e.to_string())?;
+    fs::write(&invalid, "8/8/8/8/8/8/8/8 w - - 0 1\n").map_err(|e| e.to_string())?;
+    // action
+    let openings = EngineMatch::load_openings(&valid, VariantKind::Standard);
+    let without_kings = EngineMatch::load_openings(&invalid, VariantKind::Standard);
+    fs::remove_file(&valid).map_err(|e| e.to_string())?;
+    fs::remove_file(&invalid).map_err(|e| e.to_string())?;
+    // assert
+    let openings = openings?;
+    assert_eq!(openings.len(), 1);
+    assert_eq!(Fen::encode(&openings[0]), STANDARD_FEN);
+    assert!(without_kings.is_err());
+    Ok(())
+}
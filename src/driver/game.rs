@@ -1,60 +1,296 @@
+use std::{path::Path, sync::Arc, time::Duration};
+
 use crate::{
-    logic::move_computer::StepComputer,
-    models::{board::Board, game_state::GameState, step::StepKind},
+    logic::{move_computer::StepComputer, tablebase::Tablebase},
+    models::{
+        board::Board,
+        clock::{Clock, TimeControl},
+        error::{FileError, GameError, MoveError},
+        game_state::GameState,
+        piece::PieceColor,
+        step::StepKind,
+    },
+    utils::{pgn::Pgn, san::San, zobrist::Zobrist},
 };
 
-#[derive(Debug, Default)]
+/// unfinished games are written here when quitting
+pub const AUTOSAVE_FILE: &str = "autosave.pgn";
+
+#[derive(Debug, Clone)]
 pub struct Game {
     pub board: Board,
+    /// the position the game started from
+    pub start: Board,
+    /// all steps played so far
+    pub history: Vec<StepKind>,
+    pub clock: Option<Clock>,
+    pub state: GameState,
+    /// the color that offered a draw which has not been answered yet
+    pub draw_offer: Option<PieceColor>,
+    /// ends the game as soon as the tablebases know its result
+    pub tablebase: Option<Arc<Tablebase>>,
+    /// the hashes of all positions so far, to find repetitions
+    pub positions: Vec<u64>,
+    /// what the player that forfeited the game did wrong
+    pub forfeit_reason: Option<String>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
+
 impl Game {
     pub fn new() -> Self {
-        let board = Board::standard_board();
-        Self { board }
+        Self::from_board(Board::standard_board())
+    }
+
+    /// start a game from a custom position
+    pub fn from_board(board: Board) -> Self {
+        Self {
+            start: board.clone(),
+            positions: vec![Zobrist::hash(&board)],
+            board,
+            history: vec![],
+            clock: None,
+            state: GameState::Ongoing,
+            draw_offer: None,
+            tablebase: None,
+            forfeit_reason: None,
+        }
     }
 
-    pub fn apply_input(&mut self, input: &str) -> Result<GameState, String> {
+    pub fn with_time_control(time_control: TimeControl) -> Self {
+        let mut game = Self::new();
+        game.set_time_control(time_control);
+        game
+    }
+
+    /// starts the clocks from the current position, the side to move first
+    pub fn set_time_control(&mut self, time_control: TimeControl) {
+        self.clock = Some(Clock::new(time_control, self.board.turn_color()));
+    }
+
+    pub fn apply_input(&mut self, input: &str) -> Result<GameState, MoveError> {
+        self.check_ongoing()?;
         // 1. decode:
         let color = self.board.turn_color();
-        let step_kind = StepKind::decode(input, color)?;
+        let step_kind = San::decode(&self.board, input)?;
         let eval = self.board.evaluate_basic();
 
         // 2. check step is legal
         if eval.possible_moves.contains(&step_kind) {
-            self.board.apply_step_kind(&step_kind)?
+            self.board.apply_step_kind(&step_kind)?;
+            self.history.push(step_kind);
+            self.complete_move(color);
         } else {
-            return Err(format!(
-                "illegal move! {:?}, possible moves {:?}",
-                step_kind, eval.possible_moves
-            ));
+            return Err(self.board.illegal_step_error(&step_kind));
         }
 
-        let new_eval = self.board.evaluate_basic();
-
-        if new_eval.has_stalemate {
-            Ok(GameState::Stalemate)
-        } else if new_eval.has_checkmate {
-            Ok(GameState::Won(color))
-        } else {
-            Ok(GameState::Ongoing)
-        }
+        Ok(self.state_after_move())
     }
 
-    pub fn apply_stepkind(&mut self, step_kind: StepKind) -> Result<GameState, String> {
+    pub fn apply_stepkind(&mut self, step_kind: StepKind) -> Result<GameState, MoveError> {
+        self.check_ongoing()?;
         // 1. evaluate position beforehand:
         let color = self.board.turn_color();
         let eval = self.board.evaluate_basic();
+        let step_kind = Self::resolve_castle(step_kind, &eval.possible_moves);
 
-        if eval.possible_moves.contains(&step_kind) {
-            self.board.apply_step_kind(&step_kind)?;
+        if !eval.possible_moves.contains(&step_kind) {
+            return Err(self.board.illegal_step_error(&step_kind));
+        }
+        self.board.apply_step_kind(&step_kind)?;
+        self.history.push(step_kind);
+        self.complete_move(color);
+        Ok(self.state_after_move())
+    }
+
+    /// A king moved onto its castling square or onto its own rook, e.g. by
+    /// clicking in the TUI, means castling
+    fn resolve_castle(step_kind: StepKind, legal_steps: &[StepKind]) -> StepKind {
+        let StepKind::GoTo(step) = step_kind else {
+            return step_kind;
+        };
+        legal_steps
+            .iter()
+            .find(|legal| {
+                matches!(legal, StepKind::Castle { king_step, rook_step }
+                    if king_step.from == step.from
+                        && (king_step.to == step.to || rook_step.from == step.to))
+            })
+            .cloned()
+            .unwrap_or(step_kind)
+    }
+
+    /// writes the game, including its history and clocks, as PGN
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), GameError> {
+        Ok(FileError::write(path.as_ref(), Pgn::encode(self))?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        Ok(Pgn::decode(&content)?)
+    }
+
+    /// the position after the given number of plies from the start
+    pub fn board_at(&self, ply: usize) -> Result<Board, GameError> {
+        let steps = self.history.get(..ply).ok_or(GameError::PlyOutOfRange {
+            ply,
+            plies: self.history.len(),
+        })?;
+        let mut board = self.start.clone();
+        for step in steps {
+            board.apply_step_kind(step)?;
+        }
+        Ok(board)
+    }
+
+    /// let time pass on the clock of the player to move, returns the resulting state
+    pub fn tick(&mut self, elapsed: Duration) -> GameState {
+        if self.state.is_over() {
+            return self.state;
+        }
+        if let Some(clock) = &mut self.clock {
+            clock.tick(elapsed);
+        }
+        if let Some(state) = self.timeout_state() {
+            self.state = state;
+        }
+        self.state
+    }
+
+    /// the given color gives up the game
+    pub fn resign(&mut self, color: PieceColor) -> Result<GameState, GameError> {
+        self.check_ongoing()?;
+        self.state = GameState::Resigned(color);
+        Ok(self.state)
+    }
+
+    /// the given color loses for breaking the rules, e.g. an engine that
+    /// failed to move
+    pub fn forfeit(
+        &mut self,
+        color: PieceColor,
+        reason: impl Into<String>,
+    ) -> Result<GameState, GameError> {
+        self.check_ongoing()?;
+        self.state = GameState::Forfeit(color);
+        self.forfeit_reason = Some(reason.into());
+        Ok(self.state)
+    }
+
+    /// the offer stands until the opponent answers it or makes a move
+    pub fn offer_draw(&mut self, color: PieceColor) -> Result<(), GameError> {
+        self.check_ongoing()?;
+        match self.draw_offer {
+            Some(offering) if offering == color => Err(GameError::AlreadyOffered(color)),
+            // both sides want a draw, the second offer counts as acceptance
+            Some(_) => self.accept_draw(color).map(|_| ()),
+            None => {
+                self.draw_offer = Some(color);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn accept_draw(&mut self, color: PieceColor) -> Result<GameState, GameError> {
+        self.check_ongoing()?;
+        if self.draw_offer != Some(color.invert()) {
+            return Err(GameError::NoDrawOffer(color));
+        }
+        self.draw_offer = None;
+        self.state = GameState::DrawAgreed;
+        Ok(self.state)
+    }
+
+    pub fn decline_draw(&mut self, color: PieceColor) -> Result<(), GameError> {
+        self.check_ongoing()?;
+        if self.draw_offer != Some(color.invert()) {
+            return Err(GameError::NoDrawOffer(color));
+        }
+        self.draw_offer = None;
+        Ok(())
+    }
+
+    /// a game can only be aborted as long as not both players made their first move
+    pub fn abort(&mut self) -> Result<GameState, GameError> {
+        self.check_ongoing()?;
+        if !self.can_abort() {
+            return Err(GameError::CannotAbort);
         }
-        let new_eval = self.board.evaluate_basic();
-        if new_eval.has_stalemate {
-            Ok(GameState::Stalemate)
-        } else if new_eval.has_checkmate {
-            Ok(GameState::Won(color))
+        self.state = GameState::Aborted;
+        Ok(self.state)
+    }
+
+    pub fn can_abort(&self) -> bool {
+        self.history.len() < 2
+    }
+
+    /// the outcome of the game if a flag has fallen
+    pub fn timeout_state(&self) -> Option<GameState> {
+        let flagged = self.clock.as_ref()?.flagged()?;
+        let opponent = flagged.invert();
+        if self.board.has_mating_material(opponent) {
+            Some(GameState::WonOnTime(opponent))
         } else {
-            Ok(GameState::Ongoing)
+            Some(GameState::TimeoutDraw)
+        }
+    }
+
+    pub fn remaining_time(&self, color: PieceColor) -> Option<Duration> {
+        self.clock.as_ref().map(|clock| clock.remaining(color))
+    }
+
+    /// updates the state in case a flag fell, fails if the game is over
+    fn check_ongoing(&mut self) -> Result<(), MoveError> {
+        if let Some(state) = self.timeout_state() {
+            self.state = state;
+        }
+        if self.state.is_over() {
+            Err(MoveError::GameOver(self.state))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn complete_move(&mut self, color: PieceColor) {
+        self.positions.push(Zobrist::hash(&self.board));
+        if let Some(clock) = &mut self.clock {
+            clock.press();
+        }
+        // making a move declines a pending offer of the opponent
+        if self.draw_offer == Some(color.invert()) {
+            self.draw_offer = None;
+        }
+    }
+
+    /// whether the current position occurred for the third time
+    fn is_repetition(&self) -> bool {
+        self.positions.last().is_some_and(|current| {
+            self.positions
+                .iter()
+                .filter(|position| *position == current)
+                .count()
+                >= 3
+        })
+    }
+
+    fn state_after_move(&mut self) -> GameState {
+        self.state = self.board.game_state();
+        if self.state == GameState::Ongoing && self.is_repetition() {
+            self.state = GameState::Repetition;
+        }
+        if self.state == GameState::Ongoing {
+            if let Some(state) = self
+                .tablebase
+                .as_ref()
+                .and_then(|tablebase| tablebase.adjudicate(&self.board))
+            {
+                self.state = state;
+            }
         }
+        self.state
     }
 }
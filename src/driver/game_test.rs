@@ -0,0 +1,262 @@
+use std::error::Error;
+
+use super::game::Game;
+use crate::{
+    models::{
+        error::{GameError, IllegalReason, MoveError},
+        game_state::GameState,
+        location::Location,
+        piece::PieceColor,
+        step::StepKind,
+    },
+    utils::{fen::Fen, pgn::Pgn},
+};
+
+#[test]
+fn resign() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e2e4")?;
+    // action
+    let state = game.resign(PieceColor::Black)?;
+    // assert
+    assert_eq!(state, GameState::Resigned(PieceColor::Black));
+    assert_eq!(
+        game.apply_input("e7e5"),
+        Err(MoveError::GameOver(GameState::Resigned(PieceColor::Black)))
+    );
+    Ok(())
+}
+
+#[test]
+fn draw_offer_accepted() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.offer_draw(PieceColor::White)?;
+    // action
+    let state = game.accept_draw(PieceColor::Black)?;
+    // assert
+    assert_eq!(state, GameState::DrawAgreed);
+    Ok(())
+}
+
+#[test]
+fn draw_offer_declined_by_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e2e4")?;
+    game.offer_draw(PieceColor::White)?;
+    // action
+    game.apply_input("e7e5")?;
+    // assert
+    assert_eq!(game.draw_offer, None);
+    assert!(matches!(
+        game.accept_draw(PieceColor::Black),
+        Err(GameError::NoDrawOffer(PieceColor::Black))
+    ));
+    Ok(())
+}
+
+#[test]
+fn own_offer_cannot_be_accepted() {
+    // arrange
+    let mut game = Game::new();
+    game.offer_draw(PieceColor::White).unwrap();
+    // action
+    let res = game.accept_draw(PieceColor::White);
+    let again = game.offer_draw(PieceColor::White);
+    // assert
+    assert!(matches!(
+        res,
+        Err(GameError::NoDrawOffer(PieceColor::White))
+    ));
+    assert!(matches!(
+        again,
+        Err(GameError::AlreadyOffered(PieceColor::White))
+    ));
+}
+
+#[test]
+fn abort() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e2e4")?;
+    // action
+    let state = game.abort()?;
+    // assert
+    assert_eq!(state, GameState::Aborted);
+    Ok(())
+}
+
+#[test]
+fn abort_after_second_move_fails() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e2e4")?;
+    game.apply_input("e7e5")?;
+    // action
+    let res = game.abort();
+    // assert
+    assert!(matches!(res, Err(GameError::CannotAbort)));
+    assert_eq!(game.state, GameState::Ongoing);
+    Ok(())
+}
+
+#[test]
+fn abort_from_a_later_position() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/8/8/8/4P3/4K3 w - - 12 40")?;
+    let mut game = Game::from_board(board);
+    game.apply_input("e2e4")?;
+    // action
+    let state = game.abort()?;
+    // assert
+    assert_eq!(state, GameState::Aborted);
+    Ok(())
+}
+
+#[test]
+fn abort_a_reloaded_game() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/8/8/8/4P3/4K3 b - - 0 25")?;
+    let mut game = Game::from_board(board);
+    game.apply_input("e8d8")?;
+    let mut reloaded = Pgn::decode(&Pgn::encode(&game))?;
+    let mut played_on = reloaded.clone();
+    played_on.apply_input("e2e4")?;
+    // action
+    let state = reloaded.abort()?;
+    let res = played_on.abort();
+    // assert
+    assert_eq!(state, GameState::Aborted);
+    assert!(matches!(res, Err(GameError::CannotAbort)));
+    Ok(())
+}
+
+#[test]
+fn board_at_replays_the_history() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    for input in ["e4", "e5", "Nf3"] {
+        game.apply_input(input)?;
+    }
+    // action
+    let board = game.board_at(2)?;
+    // assert
+    assert_eq!(
+        Fen::encode(&board),
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+    );
+    assert_eq!(Fen::encode(&game.board_at(3)?), Fen::encode(&game.board));
+    assert!(matches!(
+        game.board_at(4),
+        Err(GameError::PlyOutOfRange { ply: 4, plies: 3 })
+    ));
+    Ok(())
+}
+
+#[test]
+fn illegal_moves_tell_the_reason() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/4r3/4K2R w K - 0 1")?);
+    // action
+    let opponents_piece = game.apply_input("e2e1");
+    let empty = game.apply_input("a1a2");
+    let unreachable = game.apply_input("h1g2");
+    let into_check = game.apply_input("h1h2");
+    let outside = game.apply_stepkind(StepKind::decode("h1i1")?);
+    let unparsable = game.apply_input("e1e");
+    game.resign(PieceColor::White)?;
+    let over = game.apply_input("e1d1");
+    // assert
+    let reason = |res: Result<GameState, MoveError>| match res {
+        Err(MoveError::Illegal { reason, .. }) => Some(reason),
+        _ => None,
+    };
+    assert_eq!(
+        reason(opponents_piece),
+        Some(IllegalReason::OpponentsPiece(Location::decode("e2")?))
+    );
+    assert_eq!(
+        reason(empty),
+        Some(IllegalReason::NoPiece(Location::decode("a1")?))
+    );
+    assert_eq!(reason(unreachable), Some(IllegalReason::Unreachable));
+    assert_eq!(reason(into_check), Some(IllegalReason::LeavesKingInCheck));
+    assert_eq!(outside, Err(MoveError::OutOfBounds(Location::new(8, 0))));
+    assert_eq!(unparsable, Err(MoveError::NoMatch("e1e".to_string())));
+    assert_eq!(
+        over,
+        Err(MoveError::GameOver(GameState::Resigned(PieceColor::White)))
+    );
+    Ok(())
+}
+
+#[test]
+fn threefold_repetition_is_a_draw() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    let mut states = vec![];
+    // action
+    for input in ["Nf3", "Nf6", "Ng1", "Ng8", "Nf3", "Nf6", "Ng1", "Ng8"] {
+        states.push(game.apply_input(input)?);
+    }
+    // assert
+    // the start position is back for the third time after the last move
+    assert!(states[..7].iter().all(|state| *state == GameState::Ongoing));
+    assert_eq!(states[7], GameState::Repetition);
+    assert_eq!(game.state.result(), "1/2-1/2");
+    Ok(())
+}
+
+#[test]
+fn fifty_moves_without_progress_are_a_draw() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/8/R3K3 w - - 98 70")?);
+    // action
+    let first = game.apply_input("Ra2")?;
+    let second = game.apply_input("Kd8")?;
+    // assert
+    assert_eq!(first, GameState::Ongoing);
+    assert_eq!(second, GameState::FiftyMoves);
+    Ok(())
+}
+
+#[test]
+fn mate_on_the_fiftieth_move_counts() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("k7/8/1K6/8/8/8/8/7R w - - 99 70")?);
+    // action
+    let state = game.apply_input("Rh8#")?;
+    // assert
+    assert_eq!(state, GameState::Won(PieceColor::White));
+    Ok(())
+}
+
+#[test]
+fn insufficient_material_is_a_draw() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/3r4/2N1K3 w - - 0 1")?);
+    // action
+    let state = game.apply_input("Kxd2")?;
+    // assert
+    assert_eq!(state, GameState::InsufficientMaterial);
+    assert!(game.apply_input("Kd3").is_err());
+    Ok(())
+}
+
+#[test]
+fn forfeit_keeps_the_reason() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e4")?;
+    // action
+    let state = game.forfeit(PieceColor::Black, "illegal move e7e4")?;
+    let resumed = Pgn::decode(&Pgn::encode(&game))?;
+    // assert
+    assert_eq!(state, GameState::Forfeit(PieceColor::Black));
+    assert_eq!(state.result(), "1-0");
+    assert_eq!(resumed.state, state);
+    assert_eq!(resumed.forfeit_reason.as_deref(), Some("illegal move e7e4"));
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use std::{path::Path, time::Instant};
+
+use crate::{
+    logic::{
+        analysis::{analyze, AnalysisOptions},
+        book::OpeningBook,
+        move_computer::StepComputer,
+    },
+    models::{board::Board, error::FileError},
+    utils::{fen::Fen, pgn::Pgn, san::San},
+};
+
+use super::uci::UciDriver;
+
+/// Prints the leaf nodes below every move to the depth, and the speed.
+/// Returns the total number of nodes.
+pub fn perft(board: &Board, depth: usize) -> u64 {
+    let start = Instant::now();
+    let divide = board.perft_divide(depth);
+    for (step, nodes) in divide.iter() {
+        println!(
+            "{}: {nodes}",
+            UciDriver::encode_step(step, board.is_chess960())
+        );
+    }
+    let total: u64 = divide.iter().map(|(_, nodes)| nodes).sum();
+    let elapsed = start.elapsed();
+    println!();
+    println!("Nodes searched: {total}");
+    println!(
+        "Time: {:.3}s ({:.0} nodes/s)",
+        elapsed.as_secs_f64(),
+        total as f64 / elapsed.as_secs_f64().max(1e-9)
+    );
+    total
+}
+
+/// prints the board, what the tablebases know about it and the best lines
+/// at every depth of the search
+pub fn analyze_position(board: &Board, options: &AnalysisOptions) {
+    println!("{}", board.to_pretty_string());
+    println!("{}", Fen::encode(board));
+    let eval = board.evaluate_basic();
+    println!(
+        "{:?} to move, {} legal moves{}",
+        board.turn_color(),
+        eval.possible_moves.len(),
+        if eval.has_check { ", in check" } else { "" }
+    );
+    let tablebase = options.tablebase.as_deref();
+    if let Some(wdl) = tablebase.and_then(|tablebase| tablebase.probe_wdl(board)) {
+        let dtz = tablebase
+            .and_then(|tablebase| tablebase.probe_dtz(board))
+            .map_or(String::new(), |dtz| format!(", dtz {dtz}"));
+        println!("tablebase: {wdl:?}{dtz}");
+    }
+    analyze(board, options, None, |analysis| {
+        println!(
+            "\ndepth {}  nodes {}  time {:.2}s",
+            analysis.depth,
+            analysis.nodes,
+            analysis.elapsed.as_secs_f64()
+        );
+        for (index, line) in analysis.lines.iter().enumerate() {
+            println!(
+                "{:>3}. {:>7}  {}",
+                index + 1,
+                line.score_text(),
+                line.numbered(board)
+            );
+        }
+    });
+}
+
+/// prints the moves of the book for the board with their share of the weights
+pub fn book_moves(book: &OpeningBook, board: &Board) {
+    let steps = book.steps(board);
+    if steps.is_empty() {
+        println!("no book moves for {}", Fen::encode(board));
+        return;
+    }
+    let total: u32 = steps.iter().map(|(_, weight)| *weight as u32).sum();
+    for (step, weight) in steps {
+        println!(
+            "{:<8} weight {weight:>5}  {:>5.1}%",
+            San::encode(board, &step),
+            weight as f64 * 100.0 / total as f64
+        );
+    }
+}
+
+/// Replays every game of a PGN file and prints whether it worked. Returns
+/// the number of games that failed.
+pub fn validate_pgn(path: impl AsRef<Path>) -> Result<usize, FileError> {
+    let content = FileError::read_to_string(path.as_ref())?;
+    let mut failures = 0;
+    for (index, game) in Pgn::split_games(&content).iter().enumerate() {
+        match Pgn::decode(game) {
+            Ok(game) => println!(
+                "game {}: ok, {} plies, result {}",
+                index + 1,
+                game.history.len(),
+                game.state.result()
+            ),
+            Err(e) => {
+                failures += 1;
+                println!("game {}: {e}", index + 1);
+            }
+        }
+    }
+    Ok(failures)
+}
@@ -0,0 +1,27 @@
+use std::{error::Error, fs};
+
+use super::inspect::{perft, validate_pgn};
+use crate::models::board::Board;
+
+#[test]
+fn perft_counts_the_leaves() {
+    // action
+    let nodes = perft(&Board::standard_board(), 2);
+    // assert
+    assert_eq!(nodes, 400);
+}
+
+#[test]
+fn validate_pgn_counts_the_failures() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let path = std::env::temp_dir().join("rustychess_validate_test.pgn");
+    let content = "[Event \"ok\"]\n\n1. e4 e5 *\n\n[Event \"illegal\"]\n\n1. e5 *\n";
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    // action
+    let failures = validate_pgn(&path)?;
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+    // assert
+    assert_eq!(failures, 1);
+    assert!(validate_pgn("does/not/exist.pgn").is_err());
+    Ok(())
+}
@@ -1,2 +1,30 @@
+pub mod bench;
 pub mod cli_driver;
+pub mod engine_match;
 pub mod game;
+pub mod inspect;
+pub mod puzzle;
+pub mod replay;
+pub mod review;
+pub mod selfplay;
+pub mod tune;
+pub mod uci;
+
+#[cfg(test)]
+mod cli_driver_test;
+#[cfg(test)]
+mod engine_match_test;
+#[cfg(test)]
+mod game_test;
+#[cfg(test)]
+mod inspect_test;
+#[cfg(test)]
+mod puzzle_test;
+#[cfg(test)]
+mod replay_test;
+#[cfg(test)]
+mod review_test;
+#[cfg(test)]
+mod selfplay_test;
+#[cfg(test)]
+mod uci_test;
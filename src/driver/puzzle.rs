@@ -0,0 +1,237 @@
+use std::{collections::HashSet, path::Path};
+
+use crate::{
+    models::{
+        board::Board,
+        error::{DataError, FileError, MoveError, ParseError, PuzzleError},
+        game_state::GameState,
+        piece::PieceColor,
+        step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::{elo::expected_score, fen::Fen},
+};
+
+use super::{game::Game, uci::UciDriver};
+
+/// where the puzzle rating is kept between sessions
+pub const RATING_FILE: &str = "puzzle_rating.conf";
+// how fast the rating follows the results
+const RATING_K: f64 = 32.0;
+
+/// A tactics puzzle, one line of the Lichess puzzle database:
+/// `PuzzleId,FEN,Moves,Rating,RatingDeviation,Popularity,NbPlays,Themes,GameUrl,OpeningTags`
+#[derive(Debug, Clone)]
+pub struct Puzzle {
+    pub id: String,
+    /// the position before the opponent's move that sets up the puzzle
+    pub board: Board,
+    /// in UCI notation, the opponent's move first, then the solution
+    /// alternating with the replies
+    pub moves: Vec<String>,
+    pub rating: u32,
+    pub themes: Vec<String>,
+}
+
+impl Puzzle {
+    pub fn decode(line: &str) -> Result<Self, DataError> {
+        let line = line.trim();
+        let columns: Vec<&str> = line.split(',').collect();
+        let [id, fen, encoded_moves, rating, ..] = columns[..] else {
+            return Err(ParseError::new(line, line.len(), "at least 4 columns").into());
+        };
+        let moves: Vec<String> = encoded_moves
+            .split_whitespace()
+            .map(str::to_string)
+            .collect();
+        if moves.len() < 2 {
+            return Err(ParseError::new(encoded_moves, 0, "a move and its solution").into());
+        }
+        Ok(Self {
+            id: id.to_string(),
+            board: Fen::decode_valid(fen, VariantKind::Standard)?,
+            moves,
+            rating: rating
+                .parse()
+                .map_err(|_| ParseError::new(rating, 0, "a rating"))?,
+            themes: columns
+                .get(7)
+                .map(|themes| themes.split_whitespace().map(str::to_string).collect())
+                .unwrap_or_default(),
+        })
+    }
+
+    /// reads a puzzle CSV file, the header line is skipped
+    pub fn load_all(path: impl AsRef<Path>) -> Result<Vec<Self>, DataError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with("PuzzleId"))
+            .map(Self::decode)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PuzzleStatus {
+    Playing,
+    Solved,
+    Failed,
+}
+
+/// A puzzle being solved: the user plays the solution moves, the replies
+/// are played automatically
+#[derive(Debug)]
+pub struct PuzzleAttempt {
+    pub puzzle: Puzzle,
+    pub game: Game,
+    /// the color the user plays
+    pub color: PieceColor,
+    pub status: PuzzleStatus,
+    /// index of the next move in `puzzle.moves`
+    next: usize,
+}
+
+impl PuzzleAttempt {
+    /// plays the opponent's move that sets up the puzzle
+    pub fn start(puzzle: Puzzle) -> Result<Self, PuzzleError> {
+        let mut game = Game::from_board(puzzle.board.clone());
+        let setup = UciDriver::decode_step(&game.board, &puzzle.moves[0])?;
+        game.apply_stepkind(setup)?;
+        Ok(Self {
+            color: game.board.turn_color(),
+            puzzle,
+            game,
+            status: PuzzleStatus::Playing,
+            next: 1,
+        })
+    }
+
+    /// Checks the user's step against the solution. A correct one is played
+    /// together with the reply, a wrong one fails the puzzle and is not
+    /// played. Any mate counts as correct, even if it is not the one of the
+    /// solution.
+    pub fn play(&mut self, step: StepKind) -> Result<PuzzleStatus, PuzzleError> {
+        if self.status != PuzzleStatus::Playing {
+            return Err(PuzzleError::Over);
+        }
+        let expected = self.solution_step()?;
+        let mut game = self.game.clone();
+        let state = game.apply_stepkind(step)?;
+        let played = &game.history[self.game.history.len()];
+        let mates = matches!(state, GameState::Won(color) if color == self.color);
+        if *played != expected && !mates {
+            self.status = PuzzleStatus::Failed;
+            return Ok(self.status);
+        }
+        self.game = game;
+        self.next += 1;
+        if mates || self.next >= self.puzzle.moves.len() {
+            self.status = PuzzleStatus::Solved;
+            return Ok(self.status);
+        }
+        let reply = self.solution_step()?;
+        self.game.apply_stepkind(reply)?;
+        self.next += 1;
+        if self.next >= self.puzzle.moves.len() {
+            self.status = PuzzleStatus::Solved;
+        }
+        Ok(self.status)
+    }
+
+    /// the next move of the solution in UCI notation
+    pub fn solution(&self) -> Option<&str> {
+        self.puzzle.moves.get(self.next).map(String::as_str)
+    }
+
+    /// only called while the puzzle is played, so before the solution ends
+    fn solution_step(&self) -> Result<StepKind, MoveError> {
+        let solution = self.solution().expect("the solution goes on");
+        UciDriver::decode_step(&self.game.board, solution)
+    }
+}
+
+/// The user's puzzle rating, an Elo rating against the ratings of the
+/// puzzles, kept in a file of `key = value` lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct PuzzleRating {
+    pub rating: f64,
+    pub solved: usize,
+    pub failed: usize,
+}
+
+impl Default for PuzzleRating {
+    fn default() -> Self {
+        Self {
+            rating: 1500.0,
+            solved: 0,
+            failed: 0,
+        }
+    }
+}
+
+impl PuzzleRating {
+    /// the rating of the file, the start rating if there is none yet
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = FileError::read_to_string(path)?;
+        Self::decode(&content)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FileError> {
+        FileError::write(path.as_ref(), self.encode())
+    }
+
+    pub fn decode(input: &str) -> Result<Self, DataError> {
+        let mut res = Self::default();
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(line, 0, "a 'key = value' line"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let invalid = || ParseError::new(value, 0, "a number");
+            match key {
+                "rating" => res.rating = value.parse().map_err(|_| invalid())?,
+                "solved" => res.solved = value.parse().map_err(|_| invalid())?,
+                "failed" => res.failed = value.parse().map_err(|_| invalid())?,
+                key => return Err(DataError::UnknownKey(key.to_string())),
+            }
+        }
+        Ok(res)
+    }
+
+    pub fn encode(&self) -> String {
+        format!(
+            "rating = {:.1}\nsolved = {}\nfailed = {}\n",
+            self.rating, self.solved, self.failed
+        )
+    }
+
+    /// moves the rating towards the puzzle's if it failed, and away from it
+    /// if it was solved
+    pub fn update(&mut self, puzzle: &Puzzle, solved: bool) {
+        let expected = expected_score(self.rating - puzzle.rating as f64);
+        let result = if solved { 1.0 } else { 0.0 };
+        self.rating += RATING_K * (result - expected);
+        if solved {
+            self.solved += 1;
+        } else {
+            self.failed += 1;
+        }
+    }
+
+    /// the puzzle closest to the rating that is not done yet
+    pub fn pick<'a>(&self, puzzles: &'a [Puzzle], done: &HashSet<String>) -> Option<&'a Puzzle> {
+        puzzles
+            .iter()
+            .filter(|puzzle| !done.contains(&puzzle.id))
+            .min_by_key(|puzzle| (puzzle.rating as f64 - self.rating).abs() as u32)
+    }
+}
@@ -0,0 +1,90 @@
+use std::{collections::HashSet, error::Error};
+
+use super::puzzle::{Puzzle, PuzzleAttempt, PuzzleRating, PuzzleStatus};
+use crate::{
+    driver::uci::UciDriver,
+    models::{
+        error::{DataError, PuzzleError},
+        piece::PieceColor,
+    },
+};
+
+const MATE_IN_TWO: &str = "00sHx,q3k1nr/1pp1nQpp/3p4/1P2p3/4P3/B1PP1b2/B5PP/5K2 b k - 0 17,\
+    e8d7 a2e6 d7d8 f7f8,1760,80,83,72,mate mateIn2 middlegame short,\
+    https://lichess.org/yyznGmXs/black#34,Italian_Game Italian_Game_Classical_Variation";
+
+#[test]
+fn decode_lichess_puzzle() -> Result<(), Box<dyn Error>> {
+    // action
+    let puzzle = Puzzle::decode(MATE_IN_TWO)?;
+    // assert
+    assert_eq!(puzzle.id, "00sHx");
+    assert_eq!(puzzle.moves, vec!["e8d7", "a2e6", "d7d8", "f7f8"]);
+    assert_eq!(puzzle.rating, 1760);
+    assert_eq!(
+        puzzle.themes,
+        vec!["mate", "mateIn2", "middlegame", "short"]
+    );
+    assert!(matches!(
+        Puzzle::decode("00sHx,8/8/8/8/8/8/8/K6k w - - 0 1,a1a2,1500"),
+        Err(DataError::Parse(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn solving_plays_the_replies() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut attempt = PuzzleAttempt::start(Puzzle::decode(MATE_IN_TWO)?)?;
+    assert_eq!(attempt.color, PieceColor::White);
+    // action
+    let check = UciDriver::decode_step(&attempt.game.board, "a2e6")?;
+    let after_check = attempt.play(check)?;
+    let mate = UciDriver::decode_step(&attempt.game.board, "f7f8")?;
+    let after_mate = attempt.play(mate)?;
+    // assert
+    assert_eq!(after_check, PuzzleStatus::Playing);
+    assert_eq!(after_mate, PuzzleStatus::Solved);
+    assert_eq!(attempt.game.history.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn wrong_move_fails_without_being_played() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut attempt = PuzzleAttempt::start(Puzzle::decode(MATE_IN_TWO)?)?;
+    let wrong = UciDriver::decode_step(&attempt.game.board, "a3b2")?;
+    // action
+    let status = attempt.play(wrong)?;
+    // assert
+    assert_eq!(status, PuzzleStatus::Failed);
+    assert_eq!(attempt.game.history.len(), 1);
+    assert_eq!(attempt.solution(), Some("a2e6"));
+    assert_eq!(
+        attempt.play(UciDriver::decode_step(&attempt.game.board, "a2e6")?),
+        Err(PuzzleError::Over)
+    );
+    Ok(())
+}
+
+#[test]
+fn rating_follows_the_results() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let puzzle = Puzzle::decode(MATE_IN_TWO)?;
+    let easy = Puzzle::decode("easy,8/8/8/8/8/8/8/K6k w - - 0 1,a1a2 h1h2,1200")?;
+    let mut rating = PuzzleRating::default();
+    // action
+    rating.update(&puzzle, true);
+    let decoded = PuzzleRating::decode(&rating.encode())?;
+    // assert
+    assert!((rating.rating - 1526.2).abs() < 0.1, "{}", rating.rating);
+    assert_eq!(decoded.solved, 1);
+    assert!((decoded.rating - rating.rating).abs() < 0.1);
+    let puzzles = [puzzle, easy];
+    let next = rating.pick(&puzzles, &HashSet::new());
+    assert_eq!(next.map(|puzzle| puzzle.id.as_str()), Some("00sHx"));
+    let done = HashSet::from(["00sHx".to_string()]);
+    let next = rating.pick(&puzzles, &done);
+    assert_eq!(next.map(|puzzle| puzzle.id.as_str()), Some("easy"));
+    Ok(())
+}
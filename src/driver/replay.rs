@@ -0,0 +1,177 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    models::{
+        board::Board,
+        error::{FileError, GameError, PgnError},
+    },
+    utils::{
+        pgn::{MoveNotes, Pgn},
+        san::San,
+    },
+};
+
+use super::game::Game;
+
+/// how long a position is shown while playing automatically, from slow to fast
+pub const AUTOPLAY_SPEEDS: [Duration; 5] = [
+    Duration::from_secs(5),
+    Duration::from_secs(2),
+    Duration::from_secs(1),
+    Duration::from_millis(500),
+    Duration::from_millis(250),
+];
+
+/// A finished game being stepped through, forwards, backwards or
+/// automatically, together with the comments and variations of its PGN
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub game: Game,
+    pub tags: Vec<(String, String)>,
+    /// the moves of the main line in SAN
+    pub moves: Vec<String>,
+    /// see `PgnGame::notes`
+    pub notes: Vec<MoveNotes>,
+    /// the position after `ply` moves
+    pub board: Board,
+    ply: usize,
+    /// index of `AUTOPLAY_SPEEDS`
+    speed: usize,
+    playing: bool,
+    /// time passed since the last automatic move
+    waited: Duration,
+}
+
+impl Replay {
+    /// reads the first game of a PGN, starting at its first position
+    pub fn decode(input: &str) -> Result<Self, PgnError> {
+        let input = Pgn::split_games(input)
+            .into_iter()
+            .next()
+            .ok_or(PgnError::NoGame)?;
+        let pgn_game = Pgn::parse(&input)?;
+        let game = Pgn::decode(&input)?;
+        let mut board = game.start.clone();
+        let mut moves = vec![];
+        for (ply, step) in game.history.iter().enumerate() {
+            moves.push(San::encode(&board, step));
+            board
+                .apply_step_kind(step)
+                .map_err(|error| PgnError::Move { ply, error })?;
+        }
+        Ok(Self {
+            board: game.start.clone(),
+            game,
+            tags: pgn_game.tags,
+            moves,
+            notes: pgn_game.notes,
+            ply: 0,
+            speed: 2,
+            playing: false,
+            waited: Duration::ZERO,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, GameError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        Ok(Self::decode(&content)?)
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// the number of moves played on the shown board
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// the comments and variations of the last move played, the ones
+    /// before the first move at the start
+    pub fn notes(&self) -> Option<&MoveNotes> {
+        self.notes.get(self.ply)
+    }
+
+    /// shows the position after the given number of moves, at most all
+    pub fn jump(&mut self, ply: usize) {
+        self.ply = ply.min(self.moves.len());
+        self.board = self
+            .game
+            .board_at(self.ply)
+            .expect("the moves of the game have been played before");
+        self.waited = Duration::ZERO;
+    }
+
+    /// shows the position after White's move of the given number, or after
+    /// Black's first move if the game starts with it
+    pub fn jump_to_move(&mut self, number: usize) {
+        let ply = (number * 2).saturating_sub(1 + self.game.start.turn());
+        self.jump(ply.max(usize::from(number > 0)));
+    }
+
+    pub fn first(&mut self) {
+        self.jump(0);
+    }
+
+    pub fn previous(&mut self) {
+        self.jump(self.ply.saturating_sub(1));
+    }
+
+    pub fn next(&mut self) {
+        self.jump(self.ply + 1);
+    }
+
+    pub fn last(&mut self) {
+        self.jump(self.moves.len());
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.ply == self.moves.len()
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// starts or stops playing automatically, at the end it starts over
+    pub fn toggle_autoplay(&mut self) {
+        self.playing = !self.playing;
+        if self.playing && self.is_at_end() {
+            self.first();
+        }
+        self.waited = Duration::ZERO;
+    }
+
+    pub fn interval(&self) -> Duration {
+        AUTOPLAY_SPEEDS[self.speed]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(AUTOPLAY_SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Lets time pass while playing automatically and plays the next move
+    /// once the interval is over. Stops at the end of the game, returns
+    /// whether a move was played.
+    pub fn tick(&mut self, elapsed: Duration) -> bool {
+        if !self.playing {
+            return false;
+        }
+        self.waited += elapsed;
+        if self.waited < self.interval() {
+            return false;
+        }
+        self.next();
+        if self.is_at_end() {
+            self.playing = false;
+        }
+        true
+    }
+}
@@ -0,0 +1,126 @@
+use std::{error::Error, time::Duration};
+
+use super::replay::{Replay, AUTOPLAY_SPEEDS};
+use crate::utils::fen::{Fen, STANDARD_FEN};
+
+const PGN: &str = r#"[Event "test"]
+[White "Alice"]
+[Result "1-0"]
+
+{An old trap} 1. e4 e5 2. Bc4 Nc6 (2... Nf6 3. d3) 3. Qh5 Nf6 $4 {Defends the wrong square}
+4. Qxf7# 1-0
+"#;
+
+#[test]
+fn step_through_the_game() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut replay = Replay::decode(PGN)?;
+    // action
+    let start = Fen::encode(&replay.board);
+    replay.next();
+    replay.next();
+    let after_two = replay.ply();
+    replay.previous();
+    let after_one = Fen::encode(&replay.board);
+    replay.last();
+    let at_end = replay.ply();
+    replay.next();
+    // assert
+    assert_eq!(start, STANDARD_FEN);
+    assert_eq!(after_two, 2);
+    assert_eq!(
+        after_one,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+    );
+    assert_eq!(at_end, 7);
+    assert_eq!(replay.ply(), 7);
+    assert!(replay.is_at_end());
+    assert_eq!(replay.tag("White"), Some("Alice"));
+    assert_eq!(replay.moves.last().map(String::as_str), Some("Qxf7#"));
+    Ok(())
+}
+
+#[test]
+fn jump_to_a_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut replay = Replay::decode(PGN)?;
+    let mut from_black = Replay::decode(
+        r#"[FEN "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"]
+
+1... e5 2. Nf3 *
+"#,
+    )?;
+    // action
+    replay.jump_to_move(3);
+    let third = replay.ply();
+    replay.jump_to_move(0);
+    let start = replay.ply();
+    replay.jump_to_move(99);
+    from_black.jump_to_move(2);
+    // assert
+    assert_eq!(third, 5);
+    assert_eq!(start, 0);
+    assert_eq!(replay.ply(), 7);
+    assert_eq!(from_black.ply(), 2);
+    Ok(())
+}
+
+#[test]
+fn notes_follow_the_moves() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut replay = Replay::decode(PGN)?;
+    // action
+    let intro = replay.notes().cloned().unwrap_or_default();
+    replay.jump(4);
+    let alternatives = replay.notes().cloned().unwrap_or_default();
+    replay.jump(6);
+    let blunder = replay.notes().cloned().unwrap_or_default();
+    // assert
+    assert_eq!(intro.comments, vec!["An old trap"]);
+    assert_eq!(alternatives.variations, vec!["2... Nf6 3. d3"]);
+    assert_eq!(blunder.comments, vec!["Defends the wrong square"]);
+    Ok(())
+}
+
+#[test]
+fn autoplay_until_the_end() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut replay = Replay::decode(PGN)?;
+    replay.jump(5);
+    replay.faster();
+    let interval = replay.interval();
+    // action
+    let paused = replay.tick(Duration::from_secs(60));
+    replay.toggle_autoplay();
+    let too_early = replay.tick(interval / 2);
+    let played = replay.tick(interval / 2);
+    let last = replay.tick(interval);
+    let after_end = replay.tick(interval);
+    // assert
+    assert_eq!(interval, AUTOPLAY_SPEEDS[3]);
+    assert!(!paused);
+    assert!(!too_early);
+    assert!(played);
+    assert!(last);
+    assert!(!after_end);
+    assert!(!replay.is_playing());
+    assert!(replay.is_at_end());
+    Ok(())
+}
+
+#[test]
+fn autoplay_starts_over_at_the_end() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut replay = Replay::decode(PGN)?;
+    replay.last();
+    for _ in 0..10 {
+        replay.slower();
+    }
+    // action
+    replay.toggle_autoplay();
+    // assert
+    assert_eq!(replay.ply(), 0);
+    assert!(replay.is_playing());
+    assert_eq!(replay.interval(), AUTOPLAY_SPEEDS[0]);
+    Ok(())
+}
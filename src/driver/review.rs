@@ -0,0 +1,312 @@
+use std::path::Path;
+
+use crate::{
+    logic::analysis::{analyze, AnalysisLine, AnalysisOptions},
+    models::{
+        error::{FileError, GameError, MoveError, PgnError},
+        piece::PieceColor,
+    },
+    utils::{
+        pgn::{MoveAnnotation, Pgn},
+        san::San,
+    },
+};
+
+use super::game::Game;
+
+// scores are capped here, so that a missed mate counts like losing a lot of
+// material instead of outweighing all other moves
+const MAX_CENTIPAWNS: i32 = 1000;
+
+/// How bad a move is, judged by how much it lowered the score of the player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Judgement {
+    Inaccuracy,
+    Mistake,
+    Blunder,
+}
+
+impl Judgement {
+    /// by the centipawns the move lost compared to the best one
+    pub fn from_loss(loss: i32) -> Option<Self> {
+        match loss {
+            300.. => Some(Self::Blunder),
+            100.. => Some(Self::Mistake),
+            50.. => Some(Self::Inaccuracy),
+            _ => None,
+        }
+    }
+
+    /// the annotation glyph of PGN
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            Self::Inaccuracy => "?!",
+            Self::Mistake => "?",
+            Self::Blunder => "??",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Inaccuracy => "Inaccuracy",
+            Self::Mistake => "Mistake",
+            Self::Blunder => "Blunder",
+        }
+    }
+}
+
+/// What the review found about a single move
+#[derive(Debug, Clone, PartialEq)]
+pub struct MoveReview {
+    pub color: PieceColor,
+    /// the move as played
+    pub san: String,
+    /// the best line of the position after the move
+    pub after: AnalysisLine,
+    /// centipawns the move lost compared to the best one, from the view of
+    /// the player who made it
+    pub loss: i32,
+    /// how much the player's chance to win dropped, in percent
+    pub win_chance_loss: f64,
+    pub judgement: Option<Judgement>,
+    /// the best move in SAN, if it is not the one played
+    pub best: Option<String>,
+}
+
+impl MoveReview {
+    /// the evaluation after the move for White, e.g. `-1.25` or `#3`, as it
+    /// is written in `[%eval]` comments. Nothing once the game is over.
+    pub fn eval(&self) -> Option<String> {
+        let sign = match self.color {
+            // the score after the move is the opponent's
+            PieceColor::White => -1,
+            PieceColor::Black => 1,
+        };
+        match self.after.mate_in {
+            Some(0) => None,
+            Some(moves) => Some(format!("#{}", sign * moves)),
+            None if self.after.steps.is_empty() => None,
+            None => Some(format!("{:.2}", (sign * self.after.score) as f64 / 100.0)),
+        }
+    }
+
+    pub fn annotation(&self) -> MoveAnnotation {
+        let mut comment = self
+            .eval()
+            .map(|eval| format!("[%eval {eval}]"))
+            .unwrap_or_default();
+        if let Some(judgement) = self.judgement {
+            comment += &format!(" {}.", judgement.name());
+            if let Some(best) = &self.best {
+                comment += &format!(" {best} was best.");
+            }
+        }
+        let comment = comment.trim().to_string();
+        MoveAnnotation {
+            glyph: self.judgement.map(|judgement| judgement.glyph()),
+            comment: (!comment.is_empty()).then_some(comment),
+        }
+    }
+}
+
+/// The moves of one player added up
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerSummary {
+    pub moves: usize,
+    pub inaccuracies: usize,
+    pub mistakes: usize,
+    pub blunders: usize,
+    /// average centipawn loss
+    pub average_loss: f64,
+    /// 0 to 100, the mean accuracy of the moves, computed from the drop of
+    /// the chance to win like Lichess does
+    pub accuracy: f64,
+}
+
+impl PlayerSummary {
+    fn new(moves: &[&MoveReview]) -> Self {
+        let count = |judgement| {
+            moves
+                .iter()
+                .filter(|review| review.judgement == Some(judgement))
+                .count()
+        };
+        let mean = |value: &dyn Fn(&MoveReview) -> f64| {
+            if moves.is_empty() {
+                0.0
+            } else {
+                moves.iter().map(|review| value(review)).sum::<f64>() / moves.len() as f64
+            }
+        };
+        Self {
+            moves: moves.len(),
+            inaccuracies: count(Judgement::Inaccuracy),
+            mistakes: count(Judgement::Mistake),
+            blunders: count(Judgement::Blunder),
+            average_loss: mean(&|review| review.loss as f64),
+            accuracy: mean(&|review| move_accuracy(review.win_chance_loss)),
+        }
+    }
+}
+
+/// An automatic review of a game: every position is searched to a fixed
+/// depth, and every move is compared to the best one
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameReview {
+    /// by ply
+    pub moves: Vec<MoveReview>,
+    pub white: PlayerSummary,
+    pub black: PlayerSummary,
+}
+
+impl GameReview {
+    /// Reviews the moves of the game, reporting the number of positions
+    /// searched so far
+    pub fn new(
+        game: &Game,
+        options: &AnalysisOptions,
+        mut on_progress: impl FnMut(usize, usize),
+    ) -> Result<Self, MoveError> {
+        let options = AnalysisOptions {
+            lines: 1,
+            ..options.clone()
+        };
+        let positions = game.history.len() + 1;
+        let mut lines = vec![];
+        let mut board = game.start.clone();
+        for ply in 0..positions {
+            let analysis = analyze(&board, &options, None, |_| {});
+            // a search that is not stopped always reports a line
+            lines.push(analysis.lines.into_iter().next().expect("a searched line"));
+            on_progress(ply + 1, positions);
+            if let Some(step) = game.history.get(ply) {
+                board.apply_step_kind(step)?;
+            }
+        }
+
+        let mut moves = vec![];
+        let mut board = game.start.clone();
+        for (ply, step) in game.history.iter().enumerate() {
+            let (before, after) = (&lines[ply], &lines[ply + 1]);
+            let loss = (centipawns(before.score) + centipawns(after.score)).max(0);
+            let best = before
+                .steps
+                .first()
+                .filter(|best| *best != step)
+                .and_then(|_| before.san.first().cloned());
+            let win_chance_loss = (win_chance(before.score) - win_chance(-after.score)).max(0.0);
+            moves.push(MoveReview {
+                color: board.turn_color(),
+                san: San::encode(&board, step),
+                after: after.clone(),
+                loss,
+                win_chance_loss,
+                judgement: best.as_ref().and_then(|_| Judgement::from_loss(loss)),
+                best,
+            });
+            board.apply_step_kind(step)?;
+        }
+        let of_color = |color| {
+            moves
+                .iter()
+                .filter(|review| review.color == color)
+                .collect::<Vec<_>>()
+        };
+        Ok(Self {
+            white: PlayerSummary::new(&of_color(PieceColor::White)),
+            black: PlayerSummary::new(&of_color(PieceColor::Black)),
+            moves,
+        })
+    }
+
+    /// Reviews the first game of the PGN file, writes it annotated to `out`
+    /// and prints the judged moves and how accurately both sides played
+    pub fn review_file(
+        pgn: impl AsRef<Path>,
+        options: &AnalysisOptions,
+        out: impl AsRef<Path>,
+    ) -> Result<Self, GameError> {
+        let out = out.as_ref();
+        let content = FileError::read_to_string(pgn.as_ref())?;
+        let first = Pgn::split_games(&content)
+            .into_iter()
+            .next()
+            .ok_or(PgnError::NoGame)?;
+        let tags = Pgn::parse(&first)?.tags;
+        let game = Pgn::decode(&first)?;
+        let review = Self::new(&game, options, |done, total| {
+            eprint!("\rreviewing position {done}/{total}");
+        })?;
+        eprintln!();
+
+        let mut board = game.start.clone();
+        for (step, reviewed) in game.history.iter().zip(&review.moves) {
+            if let Some(judgement) = reviewed.judgement {
+                let number = board.turn() / 2 + 1;
+                let dots = if board.turn().is_multiple_of(2) {
+                    "."
+                } else {
+                    "..."
+                };
+                let best = reviewed.best.clone().unwrap_or_default();
+                println!(
+                    "{number}{dots} {}{} {} (-{:.2}), {best} was best",
+                    reviewed.san,
+                    judgement.glyph(),
+                    judgement.name().to_lowercase(),
+                    reviewed.loss as f64 / 100.0
+                );
+            }
+            board.apply_step_kind(step)?;
+        }
+        let tag = |name: &str| {
+            tags.iter()
+                .find(|(tag, _)| tag == name)
+                .map_or("?".to_string(), |(_, value)| value.clone())
+        };
+        for (color, summary) in [("White", &review.white), ("Black", &review.black)] {
+            println!(
+                "{color} ({}): accuracy {:.1}%, {} inaccuracies, {} mistakes, {} blunders, \
+                 average loss {:.0} cp",
+                tag(color),
+                summary.accuracy,
+                summary.inaccuracies,
+                summary.mistakes,
+                summary.blunders,
+                summary.average_loss
+            );
+        }
+
+        let mut extra_tags: Vec<(&str, String)> = tags
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.clone()))
+            .collect();
+        extra_tags.push(("Annotator", format!("rustychess depth {}", options.depth)));
+        FileError::write(
+            out,
+            Pgn::encode_annotated(&game, &extra_tags, &review.annotations()),
+        )?;
+        println!("annotated game written to {}", out.display());
+        Ok(review)
+    }
+
+    /// the annotations of the moves for `Pgn::encode_annotated`
+    pub fn annotations(&self) -> Vec<MoveAnnotation> {
+        self.moves.iter().map(MoveReview::annotation).collect()
+    }
+}
+
+fn centipawns(score: i32) -> i32 {
+    score.clamp(-MAX_CENTIPAWNS, MAX_CENTIPAWNS)
+}
+
+/// the chance to win in percent of the side with the score
+fn win_chance(score: i32) -> f64 {
+    let score = centipawns(score) as f64;
+    50.0 + 50.0 * (2.0 / (1.0 + (-0.00368208 * score).exp()) - 1.0)
+}
+
+/// 100 for a move that keeps the chance to win, less the more it drops
+fn move_accuracy(win_chance_loss: f64) -> f64 {
+    (103.1668 * (-0.04354 * win_chance_loss).exp() - 3.1669).clamp(0.0, 100.0)
+}
@@ -0,0 +1,60 @@
+use std::error::Error;
+
+use super::{
+    game::Game,
+    review::{GameReview, Judgement},
+};
+use crate::{logic::analysis::AnalysisOptions, models::error::MoveError, utils::pgn::Pgn};
+
+fn scholars_mate() -> Result<Game, MoveError> {
+    let mut game = Game::new();
+    for input in ["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"] {
+        game.apply_input(input)?;
+    }
+    Ok(game)
+}
+
+#[test]
+fn review_finds_the_blunder() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let game = scholars_mate()?;
+    let options = AnalysisOptions {
+        depth: 2,
+        ..Default::default()
+    };
+    let mut progress = vec![];
+    // action
+    let review = GameReview::new(&game, &options, |done, total| progress.push((done, total)))?;
+    // assert
+    assert_eq!(progress.last(), Some(&(8, 8)));
+    let blunder = &review.moves[5];
+    assert_eq!(blunder.san, "Nf6");
+    assert_eq!(blunder.judgement, Some(Judgement::Blunder));
+    assert!(blunder.best.as_ref().is_some_and(|best| best != "Nf6"));
+    assert_eq!(blunder.eval(), Some("#1".to_string()));
+    assert_eq!(review.moves[6].judgement, None);
+    assert_eq!(review.moves[6].eval(), None);
+    assert_eq!(review.black.blunders, 1);
+    assert!(review.white.accuracy > review.black.accuracy);
+    Ok(())
+}
+
+#[test]
+fn annotated_pgn_can_be_read_again() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let game = scholars_mate()?;
+    let options = AnalysisOptions {
+        depth: 1,
+        ..Default::default()
+    };
+    let review = GameReview::new(&game, &options, |_, _| {})?;
+    // action
+    let pgn = Pgn::encode_annotated(&game, &[], &review.annotations());
+    let decoded = Pgn::decode(&pgn)?;
+    // assert
+    assert!(pgn.contains("Nf6??"), "{pgn}");
+    assert!(pgn.contains("{ [%eval #1] Blunder."), "{pgn}");
+    assert!(pgn.contains("4. Qxf7#"), "{pgn}");
+    assert_eq!(decoded.history, game.history);
+    Ok(())
+}
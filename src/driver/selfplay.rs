@@ -0,0 +1,168 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    logic::{
+        evaluation::MaterialEvaluator,
+        move_computer::StepComputer,
+        nnue::Network,
+        search::{Search, SearchLimits},
+        transposition::TranspositionTable,
+    },
+    models::{
+        board::Board,
+        error::{FileError, GameError, MoveError},
+        game_state::GameState,
+        piece::PieceColor,
+    },
+    utils::fen::Fen,
+};
+
+use super::game::Game;
+
+/// A position of a self-play game, one line of the text format network
+/// trainers read: `<fen> | <score> | <result>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrainingPosition {
+    pub fen: String,
+    /// the search score in centipawns for White
+    pub score: i32,
+    /// 1 if White won the game, 0.5 for a draw and 0 if Black won
+    pub result: f32,
+}
+
+impl TrainingPosition {
+    pub fn encode(&self) -> String {
+        format!("{} | {} | {:.1}", self.fen, self.score, self.result)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SelfPlayOptions {
+    /// depth every move is searched to
+    pub depth: usize,
+    /// moves played at random at the start, so that the games differ
+    pub random_plies: usize,
+    /// games still going on after this many plies count as draws
+    pub max_plies: usize,
+}
+
+impl Default for SelfPlayOptions {
+    fn default() -> Self {
+        Self {
+            depth: 3,
+            random_plies: 8,
+            max_plies: 300,
+        }
+    }
+}
+
+/// Plays the engine against itself to produce training positions for the
+/// network, evaluating with the network if one is given
+pub struct SelfPlay<'a> {
+    pub options: SelfPlayOptions,
+    pub network: Option<&'a Network>,
+}
+
+impl SelfPlay<'_> {
+    /// Plays the games and appends their positions to the file, seeded by
+    /// the time so that runs differ. Returns the number of positions.
+    pub fn play_games(
+        &self,
+        start: &Board,
+        games: usize,
+        out: impl AsRef<Path>,
+    ) -> Result<usize, GameError> {
+        let out = out.as_ref();
+        let write_error = |e| FileError::new(out, e);
+        let mut file = File::options()
+            .create(true)
+            .append(true)
+            .open(out)
+            .map_err(write_error)?;
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+        let mut count = 0;
+        for game in 0..games {
+            let positions = self.play(start, seed.wrapping_add(game as u64))?;
+            for position in &positions {
+                writeln!(file, "{}", position.encode()).map_err(write_error)?;
+            }
+            count += positions.len();
+            let result = positions.first().map_or(0.5, |position| position.result);
+            println!(
+                "game {}/{games}: result {result:.1}, {} positions",
+                game + 1,
+                positions.len()
+            );
+        }
+        Ok(count)
+    }
+
+    /// Plays one game from the position, the seed picks the random moves.
+    /// Positions in check or with a mate found are left out, their score
+    /// says little about the evaluation.
+    pub fn play(&self, start: &Board, seed: u64) -> Result<Vec<TrainingPosition>, MoveError> {
+        let mut game = Game::from_board(start.clone());
+        let table = TranspositionTable::default();
+        let mut random = seed.max(1);
+        let mut positions = vec![];
+        while game.state == GameState::Ongoing && game.history.len() < self.options.max_plies {
+            let board = &game.board;
+            let no_material = !board.has_mating_material(PieceColor::White)
+                && !board.has_mating_material(PieceColor::Black);
+            if no_material {
+                break;
+            }
+            let steps = board.compute_steps();
+            let step = if game.history.len() < self.options.random_plies {
+                // xorshift, good enough to vary the openings
+                random ^= random << 13;
+                random ^= random >> 7;
+                random ^= random << 17;
+                steps[(random % steps.len() as u64) as usize].clone()
+            } else {
+                let limits = SearchLimits {
+                    depth: self.options.depth,
+                    ..Default::default()
+                };
+                let mut search =
+                    Search::new(&MaterialEvaluator, limits).with_transposition_table(&table);
+                if let Some(network) = self.network {
+                    search = search.with_network(network);
+                }
+                let info = search.run(board, |_| {});
+                if info.mate_in().is_none() && !board.evaluate_basic().has_check {
+                    let score = match board.turn_color() {
+                        PieceColor::White => info.score,
+                        PieceColor::Black => -info.score,
+                    };
+                    positions.push(TrainingPosition {
+                        fen: Fen::encode(board),
+                        score,
+                        result: 0.5,
+                    });
+                }
+                match info.best_step() {
+                    Some(step) => step.clone(),
+                    None => break,
+                }
+            };
+            game.apply_stepkind(step)?;
+        }
+        let result = match game.state.winner() {
+            Some(PieceColor::White) => 1.0,
+            Some(PieceColor::Black) => 0.0,
+            None => 0.5,
+        };
+        for position in &mut positions {
+            position.result = result;
+        }
+        Ok(positions)
+    }
+}
@@ -0,0 +1,33 @@
+use std::error::Error;
+
+use super::selfplay::{SelfPlay, SelfPlayOptions};
+use crate::{models::board::Board, utils::fen::Fen};
+
+#[test]
+fn plays_reproducible_games() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let self_play = SelfPlay {
+        options: SelfPlayOptions {
+            depth: 1,
+            random_plies: 4,
+            max_plies: 16,
+        },
+        network: None,
+    };
+    let start = Board::standard_board();
+    // action
+    let positions = self_play.play(&start, 7)?;
+    let again = self_play.play(&start, 7)?;
+    // assert
+    assert_eq!(positions, again);
+    assert!(!positions.is_empty() && positions.len() <= 12);
+    for position in &positions {
+        Fen::decode(&position.fen)?;
+        assert_eq!(position.result, 0.5);
+        assert_eq!(
+            position.encode(),
+            format!("{} | {} | 0.5", position.fen, position.score)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use crate::{
+    logic::{
+        evaluation::EvalParams,
+        tuning::{Tuner, TuningPosition},
+    },
+    models::error::{DataError, FileError},
+};
+
+/// Tunes the evaluation parameters to the positions of the file, resuming
+/// from `out` if it exists and writing the parameters there after every
+/// iteration. Stops early once no parameter changes the error anymore.
+pub fn tune(
+    positions: impl AsRef<Path>,
+    out: impl AsRef<Path>,
+    iterations: usize,
+) -> Result<EvalParams, DataError> {
+    let out = out.as_ref();
+    let content = FileError::read_to_string(positions.as_ref())?;
+    let positions = content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(TuningPosition::decode)
+        .collect::<Result<Vec<_>, _>>()?;
+    println!("tuning on {} positions", positions.len());
+    let mut tuner = Tuner::resume(positions, out)?;
+    println!(
+        "starting at iteration {} with k {:.3}, error {:.6}",
+        tuner.iteration,
+        tuner.k,
+        tuner.error(&tuner.params)
+    );
+    for _ in 0..iterations {
+        let params = tuner.params;
+        let error = tuner.step();
+        tuner.save(out)?;
+        println!("iteration {}: error {error:.6}", tuner.iteration);
+        if tuner.params == params {
+            println!("no parameter changes the error anymore");
+            break;
+        }
+    }
+    print!("{}", tuner.params.encode());
+    Ok(tuner.params)
+}
@@ -0,0 +1,443 @@
+use std::{
+    fmt,
+    io::{self, BufRead},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use log::{debug, info};
+
+use crate::{
+    logic::{
+        book::{BookOptions, OpeningBook},
+        evaluation::EvalParams,
+        move_computer::StepComputer,
+        nnue::Network,
+        search::{Search, SearchInfo, SearchLimits},
+        tablebase::Tablebase,
+        transposition::{TranspositionTable, DEFAULT_HASH_MB, MAX_HASH_MB},
+    },
+    models::{
+        board::Board,
+        error::{BoardError, MoveError, ParseError},
+        piece::PieceColor,
+        step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::fen::Fen,
+};
+
+// depth used when `go` comes without any limit
+const DEFAULT_DEPTH: usize = 4;
+const MAX_DEPTH: usize = 64;
+const MAX_THREADS: usize = 256;
+const MAX_MULTI_PV: usize = 256;
+
+/// Speaks the universal chess interface on stdin/stdout, so that the engine
+/// can be used from chess GUIs
+#[derive(Default)]
+pub struct UciDriver {
+    board: Board,
+    stop: Arc<AtomicBool>,
+    /// set while an infinite search has to keep its best move until `stop`
+    /// or `ponderhit`, even when it ends on its own
+    hold: Arc<AtomicBool>,
+    search_thread: Option<JoinHandle<()>>,
+    /// the `UCI_Chess960` option: castling is sent as king takes rook
+    chess960: bool,
+    /// the `UCI_Variant` option, the rules positions are played with
+    variant: VariantKind,
+    /// loaded from the `BookFile` option
+    book: Option<OpeningBook>,
+    /// the `OwnBook` option: answer from the book while it has moves
+    own_book: bool,
+    book_options: BookOptions,
+    /// loaded from the `SyzygyPath` option
+    tablebase: Option<Arc<Tablebase>>,
+    /// sized by the `Hash` option, kept from one search to the next
+    table: Arc<TranspositionTable>,
+    /// loaded from the `EvalFile` option, evaluates instead of the material
+    network: Option<Arc<Network>>,
+    /// weights of the evaluation when there is no network
+    params: EvalParams,
+    /// the `Threads` option, a single thread searches while it is unset
+    threads: usize,
+    /// the `MultiPV` option, how many best lines the search reports
+    multi_pv: usize,
+}
+
+impl UciDriver {
+    /// plays from the given opening book and tablebases and evaluates with
+    /// the network, if any, or else with the parameters
+    pub fn run(
+        book: Option<OpeningBook>,
+        tablebase: Option<Tablebase>,
+        network: Option<Network>,
+        params: EvalParams,
+    ) -> io::Result<()> {
+        let mut driver = Self {
+            params,
+            own_book: book.is_some(),
+            book,
+            tablebase: tablebase.map(Arc::new),
+            network: network.map(Arc::new),
+            ..Default::default()
+        };
+        for line in io::stdin().lock().lines() {
+            let line = line?;
+            debug!("uci <- {line}");
+            if !driver.handle_command(line.trim()) {
+                break;
+            }
+        }
+        driver.stop_search();
+        Ok(())
+    }
+
+    /// returns false once the engine should quit
+    pub fn handle_command(&mut self, line: &str) -> bool {
+        let mut parts = line.split_whitespace();
+        match parts.next() {
+            Some("uci") => {
+                println!("id name rustychess");
+                println!("id author the rustychess authors");
+                println!(
+                    "option name Hash type spin default {DEFAULT_HASH_MB} min 1 max {MAX_HASH_MB}"
+                );
+                println!("option name Threads type spin default 1 min 1 max {MAX_THREADS}");
+                println!("option name MultiPV type spin default 1 min 1 max {MAX_MULTI_PV}");
+                println!("option name UCI_Chess960 type check default false");
+                let variants: Vec<String> = VariantKind::ALL
+                    .iter()
+                    .map(|variant| format!("var {}", variant.encode_uci()))
+                    .collect();
+                println!(
+                    "option name UCI_Variant type combo default chess {}",
+                    variants.join(" ")
+                );
+                let book_options = BookOptions::default();
+                println!("option name OwnBook type check default {}", self.own_book);
+                println!("option name BookFile type string default <empty>");
+                println!(
+                    "option name BookDepth type spin default {} min 1 max 100",
+                    book_options.depth
+                );
+                println!(
+                    "option name BookVariety type spin default {} min 0 max 100",
+                    book_options.variety
+                );
+                println!("option name SyzygyPath type string default <empty>");
+                println!("option name EvalFile type string default <empty>");
+                println!("uciok");
+            }
+            Some("isready") => println!("readyok"),
+            Some("ucinewgame") => {
+                self.stop_search();
+                self.table.clear();
+                self.board = self.variant.rules().start_position();
+            }
+            Some("setoption") => {
+                let args: Vec<&str> = parts.collect();
+                self.set_option(&args);
+            }
+            Some("position") => {
+                let args: Vec<&str> = parts.collect();
+                match Self::parse_position(&args, self.variant) {
+                    Ok(mut board) => {
+                        board.set_chess960(board.is_chess960() || self.chess960);
+                        self.board = board;
+                    }
+                    Err(e) => info!("invalid position command '{line}': {e}"),
+                }
+            }
+            Some("go") => {
+                let args: Vec<&str> = parts.collect();
+                self.go(&args);
+            }
+            Some("stop") => self.stop_search(),
+            Some("ponderhit") => self.hold.store(false, Ordering::Relaxed),
+            Some("quit") => return false,
+            _ => info!("ignoring unknown uci command '{line}'"),
+        }
+        true
+    }
+
+    /// finds the legal step with the given long algebraic notation, castling
+    /// may be given as king step or as king takes rook
+    pub fn decode_step(board: &Board, input: &str) -> Result<StepKind, MoveError> {
+        board
+            .compute_steps()
+            .into_iter()
+            .find(|step| step.encode_uci() == input || step.encode_uci_chess960() == input)
+            .ok_or_else(|| MoveError::NoMatch(input.to_string()))
+    }
+
+    pub fn encode_step(step: &StepKind, chess960: bool) -> String {
+        if chess960 {
+            step.encode_uci_chess960()
+        } else {
+            step.encode_uci()
+        }
+    }
+
+    /// formats a search result as uci info lines, one per principal
+    /// variation, numbered with `multipv` if there are several
+    pub fn info_lines(info: &SearchInfo, chess960: bool) -> Vec<String> {
+        info.lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let multi_pv = if info.lines.len() > 1 {
+                    format!(" multipv {}", index + 1)
+                } else {
+                    String::new()
+                };
+                let score = match line.mate_in() {
+                    Some(moves) => format!("mate {moves}"),
+                    None => format!("cp {}", line.score),
+                };
+                let pv: Vec<String> = line
+                    .pv
+                    .iter()
+                    .map(|step| Self::encode_step(step, chess960))
+                    .collect();
+                format!(
+                    "info depth {}{multi_pv} score {score} nodes {} tbhits {} hashfull {} time {} pv {}",
+                    info.depth,
+                    info.nodes,
+                    info.tb_hits,
+                    info.hashfull,
+                    info.elapsed.as_millis(),
+                    pv.join(" ")
+                )
+            })
+            .collect()
+    }
+
+    /// `setoption name <name> [value <value>]`
+    fn set_option(&mut self, args: &[&str]) {
+        let value_index = args.iter().position(|arg| *arg == "value");
+        let name = args
+            .get(1..value_index.unwrap_or(args.len()))
+            .unwrap_or_default()
+            .join(" ");
+        let value = value_index.map(|index| args[index + 1..].join(" "));
+        match (name.to_lowercase().as_str(), value.as_deref()) {
+            ("hash", Some(value)) => match value.parse() {
+                Ok(megabytes) => {
+                    self.stop_search();
+                    self.table = Arc::new(TranspositionTable::new(megabytes));
+                }
+                Err(_) => info!("invalid hash size {value}"),
+            },
+            ("threads", Some(value)) => match value.parse::<usize>() {
+                Ok(threads) => self.threads = threads.clamp(1, MAX_THREADS),
+                Err(_) => info!("invalid thread count {value}"),
+            },
+            ("multipv", Some(value)) => match value.parse::<usize>() {
+                Ok(lines) => self.multi_pv = lines.clamp(1, MAX_MULTI_PV),
+                Err(_) => info!("invalid line count {value}"),
+            },
+            ("uci_chess960", Some(value)) => {
+                self.chess960 = value == "true";
+                self.board.set_chess960(self.chess960);
+            }
+            ("uci_variant", Some(value)) => match VariantKind::decode(value) {
+                Ok(variant) => {
+                    self.variant = variant;
+                    self.board = variant.rules().start_position();
+                }
+                Err(e) => info!("{e}"),
+            },
+            ("ownbook", Some(value)) => self.own_book = value == "true",
+            ("bookfile", Some(value)) => match OpeningBook::load(value) {
+                Ok(book) => {
+                    info!("loaded {} book entries from {value}", book.len());
+                    self.book = Some(book);
+                }
+                Err(e) => info!("{e}"),
+            },
+            ("syzygypath", Some(value)) => match Tablebase::open(value) {
+                Ok(tablebase) => {
+                    info!(
+                        "loaded tablebases with up to {} pieces from {value}",
+                        tablebase.max_pieces()
+                    );
+                    self.tablebase = Some(Arc::new(tablebase));
+                }
+                Err(e) => info!("{e}"),
+            },
+            ("evalfile", Some(value)) => match Network::load(value) {
+                Ok(network) => {
+                    info!(
+                        "loaded a network with {} hidden neurons from {value}",
+                        network.hidden_size()
+                    );
+                    self.network = Some(Arc::new(network));
+                }
+                Err(e) => info!("{e}"),
+            },
+            ("bookdepth", Some(value)) => match value.parse() {
+                Ok(depth) => self.book_options.depth = depth,
+                Err(_) => info!("invalid book depth {value}"),
+            },
+            ("bookvariety", Some(value)) => match value.parse::<u8>() {
+                Ok(variety) => self.book_options.variety = variety.min(100),
+                Err(_) => info!("invalid book variety {value}"),
+            },
+            _ => info!("ignoring unknown option '{name}'"),
+        }
+    }
+
+    fn parse_position(args: &[&str], variant: VariantKind) -> Result<Board, PositionCommandError> {
+        let moves_index = args.iter().position(|arg| *arg == "moves");
+        let (position, moves) = match moves_index {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => (args, &[][..]),
+        };
+        let mut board = match position {
+            ["startpos"] => variant.rules().start_position(),
+            ["fen", fen @ ..] => Fen::decode_valid(&fen.join(" "), variant)?,
+            _ => {
+                let error = ParseError::new(&args.join(" "), 0, "'startpos' or 'fen'");
+                return Err(BoardError::from(error).into());
+            }
+        };
+        board.set_variant(variant);
+        for input in moves {
+            let step = Self::decode_step(&board, input)?;
+            board.apply_step_kind(&step)?;
+        }
+        Ok(board)
+    }
+
+    fn go(&mut self, args: &[&str]) {
+        self.stop_search();
+        // an infinite search has to wait for `stop` before answering
+        if !args.contains(&"infinite") {
+            if let Some(step) = self.book_step() {
+                println!("info string book move");
+                println!(
+                    "bestmove {}",
+                    Self::encode_step(&step, self.board.is_chess960())
+                );
+                return;
+            }
+        }
+        let value = |name: &str| {
+            args.iter()
+                .position(|arg| *arg == name)
+                .and_then(|index| args.get(index + 1))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let (time, increment) = match self.board.turn_color() {
+            PieceColor::White => (value("wtime"), value("winc")),
+            PieceColor::Black => (value("btime"), value("binc")),
+        };
+        let budget = value("movetime").or(time.map(|time| time / 30 + increment.unwrap_or(0) / 2));
+        let infinite = args.contains(&"infinite");
+        let depth = match (value("depth"), budget, infinite) {
+            (Some(depth), _, _) => depth as usize,
+            (None, None, false) => DEFAULT_DEPTH,
+            _ => MAX_DEPTH,
+        };
+
+        self.stop.store(false, Ordering::Relaxed);
+        self.hold.store(infinite, Ordering::Relaxed);
+        let stop = self.stop.clone();
+        let hold = self.hold.clone();
+        let limits = SearchLimits {
+            depth,
+            deadline: budget.map(|ms| Instant::now() + Duration::from_millis(ms)),
+            stop: Some(self.stop.clone()),
+            threads: self.threads,
+            multi_pv: self.multi_pv,
+        };
+        let board = self.board.clone();
+        let chess960 = board.is_chess960();
+        let tablebase = self.tablebase.clone();
+        let table = self.table.clone();
+        let network = self.network.clone();
+        let params = self.params;
+        self.search_thread = Some(thread::spawn(move || {
+            let mut search = Search::new(&params, limits).with_transposition_table(&table);
+            if let Some(tablebase) = &tablebase {
+                search = search.with_tablebase(tablebase);
+            }
+            if let Some(network) = &network {
+                search = search.with_network(network);
+            }
+            let info = search.run(&board, |info| {
+                for line in Self::info_lines(info, chess960) {
+                    println!("{line}");
+                }
+            });
+            // the GUI decides when an infinite search is over
+            while hold.load(Ordering::Relaxed) && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(5));
+            }
+            let best = info
+                .best_step()
+                .map_or("0000".to_string(), |step| Self::encode_step(step, chess960));
+            println!("bestmove {best}");
+        }));
+    }
+
+    /// whether a search is running or holding its best move
+    pub fn is_searching(&self) -> bool {
+        self.search_thread
+            .as_ref()
+            .is_some_and(|handle| !handle.is_finished())
+    }
+
+    fn book_step(&self) -> Option<StepKind> {
+        if !self.own_book {
+            return None;
+        }
+        let random = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.subsec_nanos() as u64);
+        self.book
+            .as_ref()?
+            .pick(&self.board, &self.book_options, random)
+    }
+
+    fn stop_search(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.search_thread.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Why the board of a `position` command could not be set up
+#[derive(Debug)]
+enum PositionCommandError {
+    Board(BoardError),
+    Move(MoveError),
+}
+
+impl fmt::Display for PositionCommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Board(e) => write!(f, "{e}"),
+            Self::Move(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<BoardError> for PositionCommandError {
+    fn from(e: BoardError) -> Self {
+        Self::Board(e)
+    }
+}
+
+impl From<MoveError> for PositionCommandError {
+    fn from(e: MoveError) -> Self {
+        Self::Move(e)
+    }
+}
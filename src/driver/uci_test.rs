@@ -0,0 +1,57 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use super::uci::UciDriver;
+
+/// waits up to a second for the search to answer
+fn wait_for_bestmove(driver: &UciDriver) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while driver.is_searching() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(5));
+    }
+    !driver.is_searching()
+}
+
+#[test]
+fn infinite_search_holds_the_bestmove_until_ponderhit() {
+    // arrange
+    let mut driver = UciDriver::default();
+    // stalemate, the search has nothing to look at and ends at once
+    driver.handle_command("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    // action
+    driver.handle_command("go infinite");
+    thread::sleep(Duration::from_millis(50));
+    let held = driver.is_searching();
+    driver.handle_command("ponderhit");
+    // assert
+    assert!(held);
+    assert!(wait_for_bestmove(&driver));
+}
+
+#[test]
+fn infinite_search_answers_on_stop() {
+    // arrange
+    let mut driver = UciDriver::default();
+    driver.handle_command("position startpos moves e2e4");
+    // action
+    driver.handle_command("go infinite");
+    thread::sleep(Duration::from_millis(50));
+    let searching = driver.is_searching();
+    driver.handle_command("stop");
+    // assert
+    assert!(searching);
+    assert!(!driver.is_searching());
+}
+
+#[test]
+fn bounded_search_answers_on_its_own() {
+    // arrange
+    let mut driver = UciDriver::default();
+    driver.handle_command("position fen 7k/5Q2/6K1/8/8/8/8/8 b - - 0 1");
+    // action
+    driver.handle_command("go depth 3");
+    // assert
+    assert!(wait_for_bestmove(&driver));
+}
@@ -1,17 +1,52 @@
-use crossterm::event::{self, poll, Event, KeyCode, KeyEvent, KeyEventKind};
-use log::error;
+use crossterm::{
+    event::{
+        self, poll, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent,
+        KeyEventKind,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use log::{error, info};
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
+    prelude::CrosstermBackend,
     widgets::Paragraph,
-    DefaultTerminal, Frame,
+    DefaultTerminal, Frame, Terminal,
+};
+use std::{
+    collections::HashSet,
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
 };
-use std::{io, time::Duration};
 
-use crate::models::{board::Board, game_state::GameState};
+use crate::{
+    driver::{
+        game::{Game, AUTOSAVE_FILE},
+        puzzle::{Puzzle, PuzzleAttempt, PuzzleRating, PuzzleStatus, RATING_FILE},
+        replay::Replay,
+    },
+    logic::{
+        analysis::{AnalysisOptions, Analyzer},
+        tablebase::Tablebase,
+    },
+    models::{
+        board::Board,
+        clock::TimeControl,
+        error::GameError,
+        game_state::GameState,
+        step::StepKind,
+    },
+    utils::fen::Fen,
+};
 
 use super::{
-    state::view_state::ViewState,
-    widgets::{BoardWidget, ClickDirection, Header},
+    state::{editor::BoardEditor, prompt::Prompt, view_state::ViewState},
+    theme::Theme,
+    widgets::{
+        AnalysisWidget, BoardWidget, ClickDirection, ClockWidget, Header, PaletteWidget,
+        PocketWidget, PuzzleWidget, ReplayWidget, SetupWidget,
+    },
 };
 
 #[derive(Debug, Default)]
@@ -20,19 +55,118 @@ pub struct App {
     board_widget: BoardWidget,
     state: ViewState,
     board: Board,
+    time_control: Option<TimeControl>,
+    last_tick: Option<Instant>,
+    prompt: Option<Prompt>,
+    // feedback for the last action, e.g. where a game was saved
+    notice: Option<String>,
+    // where the pockets were drawn, to find the piece picked for a drop
+    pocket_area: Option<Rect>,
+    // where the palette of the board editor was drawn
+    palette_area: Option<Rect>,
+    // adjudicates the games played
+    tablebase: Option<Arc<Tablebase>>,
+    // how the board is analysed while the analysis pane is open
+    analysis_options: AnalysisOptions,
+    // analyses the current board, only while the analysis pane is open
+    analyzer: Option<Analyzer>,
+    // 'p' picks the one closest to the puzzle rating
+    puzzles: Vec<Puzzle>,
+    puzzle_rating: PuzzleRating,
+    // ids of the puzzles tried in this session
+    puzzles_done: HashSet<String>,
 }
 
+/// time controls to cycle through in the lobby, encoded like the PGN tag
+const TIME_CONTROL_PRESETS: [&str; 6] =
+    ["-", "60", "180+2", "300d3", "900+10", "40/5400+30:1800+30"];
+/// where 'w' saves the current game, 'l' loads it from and 'o' replays it from
+pub const SAVE_FILE: &str = "rustychess.pgn";
+// how often the screen is redrawn while a clock is running
+const CLOCK_REFRESH: Duration = Duration::from_millis(100);
+// how often the analysis pane is redrawn while the engine is thinking
+const ANALYSIS_REFRESH: Duration = Duration::from_millis(250);
+// the analysis keeps deepening until the board changes
+const ANALYSIS_DEPTH: usize = 64;
+// width of the side with the analysis, the puzzle, the board editor or the replay
+const WIDE_SIDE_WIDTH: u16 = 40;
+
 impl App {
+    /// an app whose games start from the given position
+    pub fn new(board: Board, time_control: Option<TimeControl>) -> Self {
+        Self {
+            board,
+            time_control,
+            ..Self::default()
+        }
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.board_widget.theme = theme;
+    }
+
+    pub fn set_tablebase(&mut self, tablebase: Tablebase) {
+        self.tablebase = Some(Arc::new(tablebase));
+    }
+
+    /// the puzzles to solve and the rating to pick them by
+    pub fn set_puzzles(&mut self, puzzles: Vec<Puzzle>, rating: PuzzleRating) {
+        self.puzzles = puzzles;
+        self.puzzle_rating = rating;
+    }
+
+    /// the evaluation and the number of lines of the analysis pane
+    pub fn set_analysis_options(&mut self, options: AnalysisOptions) {
+        self.analysis_options = options;
+    }
+
+    /// takes over the terminal until the app exits, and restores it then
+    pub fn run_in_terminal(mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        let mut stdout = io::stdout();
+        execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
+        let backend = CrosstermBackend::new(stdout);
+        let mut terminal = Terminal::new(backend)?;
+        let app_result = self.run(&mut terminal);
+        disable_raw_mode()?;
+        execute!(
+            terminal.backend_mut(),
+            LeaveAlternateScreen,
+            DisableMouseCapture
+        )?;
+        terminal.show_cursor()?;
+        app_result
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
-        self.board = Board::standard_board();
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
+            self.tick_clock();
             self.handle_state();
+            self.follow_board();
         }
         Ok(())
     }
 
+    /// continue a saved game instead of starting in the lobby
+    pub fn resume(&mut self, mut game: Game) {
+        game.tablebase = self.tablebase.clone();
+        self.state = ViewState::from_game(game);
+        self.last_tick = Some(Instant::now());
+    }
+
+    /// step through a finished game instead of starting in the lobby
+    pub fn replay(&mut self, replay: Replay) {
+        self.state = ViewState::Replay {
+            replay,
+            move_number: String::new(),
+            next_step: None,
+        };
+        self.board_widget.reset_clicks();
+        self.last_tick = Some(Instant::now());
+    }
+
     pub fn draw(&mut self, frame: &mut Frame) {
         let layout_outer = Layout::default()
             .direction(Direction::Vertical)
@@ -45,21 +179,54 @@ impl App {
             )
             .split(frame.area());
 
+        // the analysis pane shares the side with the clock
+        let wide_side = self.analyzer.is_some()
+            || matches!(
+                self.state,
+                ViewState::Puzzle { .. } | ViewState::Editor { .. } | ViewState::Replay { .. }
+            );
+        let side_width = if wide_side { WIDE_SIDE_WIDTH } else { 16 };
         let layout_board = Layout::default()
             .direction(Direction::Horizontal)
             .constraints(
                 [
                     Constraint::Length(10),
                     Constraint::Fill(1),
-                    Constraint::Length(10),
+                    Constraint::Length(side_width),
                 ],
             )
             .split(layout_outer[1]);
         frame.render_widget(Header, layout_outer[0]);
 
+        self.pocket_area = None;
+        self.palette_area = None;
         match &mut self.state {
             ViewState::Startup => {
-                let start_paragraph = Paragraph::new("To start a new game, press 's'");
+                let time_control = self
+                    .time_control
+                    .as_ref()
+                    .map_or("none".to_string(), TimeControl::encode);
+                let theme = &self.board_widget.theme;
+                let puzzles = if self.puzzles.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        "\nPuzzles: {} loaded, your rating {:.0} (press 'p' to solve one)",
+                        self.puzzles.len(),
+                        self.puzzle_rating.rating
+                    )
+                };
+                let start_paragraph = Paragraph::new(format!(
+                    "To start a new game, press 's'\n\
+                     Time control: {time_control} (press 't' to change)\n\
+                     Variant: {} (press 'v' to change)\n\
+                     Theme: {} (press 'c' to change)\n\
+                     Pieces: {} (press 'g' to change)\n\
+                     Position: press 'b' to set one up{puzzles}",
+                    self.board.variant().encode(),
+                    theme.name,
+                    theme.glyphs.encode()
+                ));
                 frame.render_widget(start_paragraph, layout_board[1]);
             }
             ViewState::InGame {
@@ -67,17 +234,136 @@ impl App {
                 ..
             } => {
                 frame.render_stateful_widget(&self.board_widget, layout_board[1], &mut self.state);
+                if let ViewState::InGame { game, .. } = &self.state {
+                    let clock_area = match &self.analyzer {
+                        Some(analyzer) => {
+                            let clock_height = if game.clock.is_some() { 5 } else { 0 };
+                            let layout_side = Layout::default()
+                                .direction(Direction::Vertical)
+                                .constraints([
+                                    Constraint::Length(clock_height),
+                                    Constraint::Fill(1),
+                                ])
+                                .split(layout_board[2]);
+                            let analysis = AnalysisWidget {
+                                board: &analyzer.board,
+                                analysis: analyzer.latest(),
+                            };
+                            frame.render_widget(analysis, layout_side[1]);
+                            layout_side[0]
+                        }
+                        None => layout_board[2],
+                    };
+                    frame.render_widget(ClockWidget { game }, clock_area);
+                    if game.board.variant().rules().has_drops() {
+                        let pockets = PocketWidget {
+                            board: &game.board,
+                            theme: &self.board_widget.theme,
+                            selected: self.board_widget.drop_piece,
+                        };
+                        frame.render_widget(pockets, layout_board[0]);
+                        self.pocket_area = Some(layout_board[0]);
+                    }
+                }
             }
-            ViewState::InGame { state, .. } => {
+            ViewState::InGame { state, game, .. } => {
                 let outcome_paragraph = Paragraph::new(state.message());
                 frame.render_widget(outcome_paragraph, layout_board[1]);
+                frame.render_widget(ClockWidget { game }, layout_board[2]);
+            }
+            ViewState::Puzzle { .. } => {
+                frame.render_stateful_widget(&self.board_widget, layout_board[1], &mut self.state);
+                if let ViewState::Puzzle { attempt, .. } = &self.state {
+                    let puzzle = PuzzleWidget {
+                        attempt,
+                        rating: &self.puzzle_rating,
+                    };
+                    frame.render_widget(puzzle, layout_board[2]);
+                }
+            }
+            ViewState::Editor { editor, .. } => {
+                // a single click on the board places the selected piece
+                self.board_widget.drop_piece = Some(editor.selected.kind);
+                let palette = PaletteWidget {
+                    theme: &self.board_widget.theme,
+                    selected: editor.selected,
+                };
+                frame.render_widget(palette, layout_board[0]);
+                self.palette_area = Some(layout_board[0]);
+                frame.render_stateful_widget(&self.board_widget, layout_board[1], &mut self.state);
+                if let ViewState::Editor { editor, .. } = &self.state {
+                    frame.render_widget(SetupWidget { editor }, layout_board[2]);
+                }
+            }
+            ViewState::Replay { .. } => {
+                frame.render_stateful_widget(&self.board_widget, layout_board[1], &mut self.state);
+                if let ViewState::Replay {
+                    replay,
+                    move_number,
+                    ..
+                } = &self.state
+                {
+                    if replay.board.variant().rules().has_drops() {
+                        let pockets = PocketWidget {
+                            board: &replay.board,
+                            theme: &self.board_widget.theme,
+                            selected: None,
+                        };
+                        frame.render_widget(pockets, layout_board[0]);
+                    }
+                    let side = ReplayWidget {
+                        replay,
+                        move_number,
+                    };
+                    frame.render_widget(side, layout_board[2]);
+                }
             }
         }
+
+        let footer = match (&self.prompt, &self.state) {
+            (Some(prompt), _) => prompt.message(),
+            (None, _) if self.notice.is_some() => self.notice.clone().unwrap_or_default(),
+            (None, ViewState::InGame { game, .. }) if !game.state.is_over() => {
+                let abort = if game.can_abort() {
+                    ", 'a' to abort"
+                } else {
+                    ""
+                };
+                format!(
+                    "Press 'r' to resign, 'd' to offer a draw{abort}, 'e' to analyse, \
+                     'w' to save, 'q' to quit"
+                )
+            }
+            (None, ViewState::Editor { .. }) => "Press 's' to start a game from the position, \
+                 'x' to clear, 'r' to reset, Esc to leave, 'q' to quit"
+                .to_string(),
+            (None, ViewState::Replay { .. }) => "Press Left/Right/Home/End to step, a move \
+                 number and Enter to jump, Space to autoplay, +/- for its speed, Esc to leave, \
+                 'q' to quit"
+                .to_string(),
+            (None, ViewState::Puzzle { .. }) => {
+                "Press 'p' for the next puzzle, 's' to start a game, 'q' to quit".to_string()
+            }
+            (None, _) => format!("Press 'l' to load {SAVE_FILE}, 'o' to replay it, 'q' to quit"),
+        };
+        frame.render_widget(Paragraph::new(footer), layout_outer[2]);
     }
     pub fn handle_events(&mut self) -> io::Result<()> {
         // performance optimization, after rendering, wait for an event
-        // but then, consume all the events you can
-        poll(Duration::MAX)?;
+        // but then, consume all the events you can. A running clock needs
+        // regular redraws, so don't block forever in that case
+        let thinking = self
+            .analyzer
+            .as_ref()
+            .is_some_and(|analyzer| !analyzer.is_finished());
+        let timeout = if self.clock_running() || self.autoplaying() {
+            CLOCK_REFRESH
+        } else if thinking {
+            ANALYSIS_REFRESH
+        } else {
+            Duration::MAX
+        };
+        poll(timeout)?;
         loop {
             if poll(Duration::from_millis(0))? {
                 match event::read()? {
@@ -94,18 +380,183 @@ impl App {
     }
 
     fn handle_key_event(&mut self, key_event: KeyEvent, lobby_mode: bool) {
-        match key_event.code {
+        if let Some(prompt) = self.prompt {
+            match key_event.code {
+                KeyCode::Char('y') => self.answer_prompt(prompt, true),
+                KeyCode::Char('n') | KeyCode::Esc => self.answer_prompt(prompt, false),
+                _ => {}
+            }
+            return;
+        }
+        self.notice = None;
+        if matches!(self.state, ViewState::Editor { .. }) {
+            self.handle_editor_key(key_event.code);
+            return;
+        }
+        if matches!(self.state, ViewState::Replay { .. }) {
+            self.handle_replay_key(key_event.code);
+            return;
+        }
+        let turn_color = self.current_game().map(|game| game.board.turn_color());
+        match (key_event.code, turn_color) {
+            (KeyCode::Char('q'), _) => self.exit(),
+            (KeyCode::Char('s'), _) if lobby_mode => self.start_game(),
+            (KeyCode::Char('t'), _) if lobby_mode => self.cycle_time_control(),
+            (KeyCode::Char('l'), _) if lobby_mode => self.load_game(),
+            (KeyCode::Char('o'), _) if lobby_mode => self.open_replay(SAVE_FILE),
+            (KeyCode::Char('v'), _) if lobby_mode => self.cycle_variant(),
+            (KeyCode::Char('b'), _) if lobby_mode => self.open_editor(),
+            (KeyCode::Char('p'), _) if lobby_mode && !self.puzzles.is_empty() => {
+                self.next_puzzle()
+            }
+            (KeyCode::Char('c'), _) if lobby_mode => {
+                self.board_widget.theme = self.board_widget.theme.next_preset()
+            }
+            (KeyCode::Char('g'), _) if lobby_mode => {
+                self.board_widget.theme.glyphs = self.board_widget.theme.glyphs.next()
+            }
+            (KeyCode::Char('w'), Some(_)) => self.save_game(SAVE_FILE),
+            (KeyCode::Char('r'), Some(color)) if !lobby_mode => {
+                self.prompt = Some(Prompt::Resign(color))
+            }
+            (KeyCode::Char('d'), Some(color)) if !lobby_mode => {
+                self.prompt = Some(Prompt::OfferDraw(color))
+            }
+            (KeyCode::Char('a'), Some(_)) if !lobby_mode => self.prompt = Some(Prompt::Abort),
+            (KeyCode::Char('e'), Some(_)) if !lobby_mode => self.toggle_analysis(),
+            _ => {}
+        }
+    }
+
+    fn handle_editor_key(&mut self, code: KeyCode) {
+        let ViewState::Editor { editor, .. } = &mut self.state else {
+            return;
+        };
+        match code {
+            KeyCode::Char('q') => self.exit(),
+            KeyCode::Esc => self.state = ViewState::Startup,
+            KeyCode::Char('s') => self.start_from_editor(),
+            KeyCode::Char('x') => editor.clear(),
+            KeyCode::Char('r') => editor.reset(),
+            KeyCode::Char('m') => editor.toggle_side(),
+            KeyCode::Char('n') => editor.cycle_en_passant(),
+            KeyCode::Char(right @ '1'..='4') => {
+                editor.toggle_castling(right as usize - '1' as usize)
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_replay_key(&mut self, code: KeyCode) {
+        let ViewState::Replay {
+            replay,
+            move_number,
+            ..
+        } = &mut self.state
+        else {
+            return;
+        };
+        match code {
             KeyCode::Char('q') => self.exit(),
-            KeyCode::Char('s') if lobby_mode => self.start_game(),
+            KeyCode::Esc if !move_number.is_empty() => move_number.clear(),
+            KeyCode::Esc => self.state = ViewState::Startup,
+            KeyCode::Left => replay.previous(),
+            KeyCode::Right => replay.next(),
+            KeyCode::Home | KeyCode::Up => replay.first(),
+            KeyCode::End | KeyCode::Down => replay.last(),
+            KeyCode::Char(' ') => {
+                replay.toggle_autoplay();
+                // the time waited for the key doesn't count for the first move
+                self.last_tick = Some(Instant::now());
+            }
+            KeyCode::Char('+') => replay.faster(),
+            KeyCode::Char('-') => replay.slower(),
+            KeyCode::Char(digit @ '0'..='9') if move_number.len() < 4 => move_number.push(digit),
+            KeyCode::Backspace => {
+                move_number.pop();
+            }
+            KeyCode::Enter => {
+                if let Ok(number) = std::mem::take(move_number).parse() {
+                    replay.jump_to_move(number);
+                }
+            }
             _ => {}
         }
     }
 
+    fn answer_prompt(&mut self, prompt: Prompt, accepted: bool) {
+        self.prompt = None;
+        match (prompt, accepted) {
+            (Prompt::Resign(color), true) => self.update_game(|game| game.resign(color)),
+            (Prompt::OfferDraw(color), true) => {
+                self.update_game(|game| game.offer_draw(color).map(|_| game.state));
+                if self
+                    .current_game()
+                    .is_some_and(|game| game.draw_offer == Some(color))
+                {
+                    self.prompt = Some(Prompt::AnswerDraw(color.invert()));
+                }
+            }
+            (Prompt::AnswerDraw(color), true) => self.update_game(|game| game.accept_draw(color)),
+            (Prompt::AnswerDraw(color), false) => {
+                self.update_game(|game| game.decline_draw(color).map(|_| game.state))
+            }
+            (Prompt::Abort, true) => self.update_game(Game::abort),
+            (_, false) => {}
+        }
+    }
+
+    fn current_game(&self) -> Option<&Game> {
+        match &self.state {
+            ViewState::InGame { game, .. } => Some(game),
+            ViewState::Startup
+            | ViewState::Puzzle { .. }
+            | ViewState::Editor { .. }
+            | ViewState::Replay { .. } => None,
+        }
+    }
+
+    /// runs an action on the current game and takes over the resulting state
+    fn update_game(&mut self, action: impl FnOnce(&mut Game) -> Result<GameState, GameError>) {
+        if let ViewState::InGame { game, state, .. } = &mut self.state {
+            match action(game) {
+                Ok(new_state) => *state = new_state,
+                Err(e) => error!("{e}"),
+            }
+        }
+        if self.state.is_lobby_mode() {
+            self.prompt = None;
+        }
+    }
+
     fn handle_mouse_event(&mut self, mouse_event: event::MouseEvent) {
+        // the board of a replay is only watched
+        if matches!(self.state, ViewState::Replay { .. }) {
+            return;
+        }
         match mouse_event.kind {
             event::MouseEventKind::Down(event::MouseButton::Left) => {
-                self.board_widget
-                    .click(self.board_widget.mouse_position, ClickDirection::Down);
+                let position = self.board_widget.mouse_position;
+                let palette_piece = self
+                    .palette_area
+                    .and_then(|area| PaletteWidget::piece_at(area, position));
+                if let (Some(piece), ViewState::Editor { editor, .. }) =
+                    (palette_piece, &mut self.state)
+                {
+                    self.board_widget.reset_clicks();
+                    editor.selected = piece;
+                    return;
+                }
+                let drop_piece = self
+                    .pocket_area
+                    .zip(self.current_game())
+                    .and_then(|(area, game)| PocketWidget::piece_at(&game.board, area, position));
+                if let Some(piece) = drop_piece {
+                    self.board_widget.reset_clicks();
+                    self.board_widget.drop_piece = Some(piece);
+                    return;
+                }
+                self.board_widget.click(position, ClickDirection::Down);
             }
             event::MouseEventKind::Up(event::MouseButton::Left) => {
                 self.board_widget
@@ -122,16 +573,230 @@ impl App {
     }
 
     fn exit(&mut self) {
+        // protect against accidentally quitting a running game
+        if !self.state.is_lobby_mode() {
+            self.save_game(AUTOSAVE_FILE);
+        }
         self.exit = true;
     }
 
+    fn save_game(&mut self, path: &str) {
+        let Some(game) = self.current_game() else {
+            return;
+        };
+        let notice = match game.save(path) {
+            Ok(()) => {
+                info!("saved game to {path}");
+                format!("Game saved to {path}")
+            }
+            Err(e) => {
+                error!("failed saving game to {path}: {e}");
+                format!("Saving failed: {e}")
+            }
+        };
+        self.notice = Some(notice);
+    }
+
+    fn load_game(&mut self) {
+        match Game::load(SAVE_FILE) {
+            Ok(game) => self.resume(game),
+            Err(e) => {
+                error!("failed loading game from {SAVE_FILE}: {e}");
+                self.notice = Some(format!("Loading failed: {e}"));
+            }
+        }
+    }
+
+    fn open_replay(&mut self, path: &str) {
+        match Replay::load(path) {
+            Ok(replay) => self.replay(replay),
+            Err(e) => {
+                error!("failed loading game from {path}: {e}");
+                self.notice = Some(format!("Loading failed: {e}"));
+            }
+        }
+    }
+
     fn start_game(&mut self) {
+        let mut game = Game::from_board(self.board.clone());
+        if let Some(time_control) = self.time_control.clone() {
+            game.set_time_control(time_control);
+        }
+        game.tablebase = self.tablebase.clone();
         self.state
-            .start()
+            .start(game)
             .expect("Just before calling, we check that 'is_lobby_mode'. This should never fail");
+        self.last_tick = Some(Instant::now());
+    }
+
+    /// sets up a position, starting from the one games start from
+    fn open_editor(&mut self) {
+        self.state = ViewState::Editor {
+            editor: BoardEditor::new(&self.board),
+            next_step: None,
+        };
+        self.board_widget.reset_clicks();
+    }
+
+    /// games start from the edited position from now on, if it is legal
+    fn start_from_editor(&mut self) {
+        let ViewState::Editor { editor, .. } = &self.state else {
+            return;
+        };
+        match editor.build() {
+            Ok(board) => {
+                self.board = board;
+                self.state = ViewState::Startup;
+                self.board_widget.reset_clicks();
+                self.start_game();
+            }
+            Err(e) => self.notice = Some(format!("Invalid position: {e}")),
+        }
+    }
+
+    fn cycle_time_control(&mut self) {
+        let current = self
+            .time_control
+            .as_ref()
+            .map_or("-".to_string(), TimeControl::encode);
+        let index = TIME_CONTROL_PRESETS
+            .iter()
+            .position(|preset| *preset == current)
+            .map_or(0, |index| (index + 1) % TIME_CONTROL_PRESETS.len());
+        self.time_control = TimeControl::decode(TIME_CONTROL_PRESETS[index]).ok();
+    }
+
+    /// a start position is swapped for the one of the next variant, a
+    /// custom position keeps its pieces and only changes the rules
+    fn cycle_variant(&mut self) {
+        let current = self.board.variant();
+        let next = current.next();
+        if Fen::encode(&self.board) == Fen::encode(&current.rules().start_position()) {
+            self.board = next.rules().start_position();
+        } else {
+            self.board.set_variant(next);
+        }
+    }
+
+    fn clock_running(&self) -> bool {
+        matches!(
+            &self.state,
+            ViewState::InGame {
+                state: GameState::Ongoing,
+                game,
+                ..
+            } if game.clock.is_some()
+        )
+    }
+
+    fn autoplaying(&self) -> bool {
+        matches!(&self.state, ViewState::Replay { replay, .. } if replay.is_playing())
+    }
+
+    /// lets time pass on the running clock, or for the replay playing
+    /// automatically
+    fn tick_clock(&mut self) {
+        let now = Instant::now();
+        let elapsed = now - self.last_tick.replace(now).unwrap_or(now);
+        match &mut self.state {
+            ViewState::InGame {
+                game,
+                state: state @ GameState::Ongoing,
+                ..
+            } => *state = game.tick(elapsed),
+            ViewState::Replay { replay, .. } => {
+                replay.tick(elapsed);
+            }
+            _ => {}
+        }
+    }
+
+    /// opens the analysis pane for the current board or closes it
+    fn toggle_analysis(&mut self) {
+        if self.analyzer.take().is_none() {
+            self.analyzer = self.current_game().map(|game| self.start_analyzer(&game.board));
+        }
+    }
+
+    fn start_analyzer(&self, board: &Board) -> Analyzer {
+        let options = AnalysisOptions {
+            depth: ANALYSIS_DEPTH,
+            tablebase: self.tablebase.clone(),
+            ..self.analysis_options.clone()
+        };
+        Analyzer::start(board.clone(), options)
+    }
+
+    /// restarts the analysis once a move changed the board, and closes the
+    /// pane when the game is over
+    fn follow_board(&mut self) {
+        let Some(analyzer) = &self.analyzer else {
+            return;
+        };
+        match self.current_game().filter(|game| !game.state.is_over()) {
+            Some(game) if Fen::encode(&game.board) != Fen::encode(&analyzer.board) => {
+                self.analyzer = Some(self.start_analyzer(&game.board));
+            }
+            Some(_) => {}
+            None => self.analyzer = None,
+        }
+    }
+
+    /// starts the puzzle closest to the rating that was not tried yet
+    fn next_puzzle(&mut self) {
+        let Some(puzzle) = self
+            .puzzle_rating
+            .pick(&self.puzzles, &self.puzzles_done)
+            .cloned()
+        else {
+            self.notice = Some("All puzzles are done".to_string());
+            return;
+        };
+        self.puzzles_done.insert(puzzle.id.clone());
+        match PuzzleAttempt::start(puzzle) {
+            Ok(attempt) => {
+                self.state = ViewState::Puzzle {
+                    attempt,
+                    next_step: None,
+                };
+                self.board_widget.reset_clicks();
+            }
+            Err(e) => {
+                error!("failed starting puzzle: {e}");
+                self.notice = Some(format!("Invalid puzzle: {e}"));
+            }
+        }
     }
 
     fn handle_state(&mut self) {
+        if let ViewState::Editor {
+            editor,
+            next_step: next_step @ Some(_),
+        } = &mut self.state
+        {
+            if let Some(StepKind::Drop { to, .. }) = next_step.take() {
+                editor.toggle(&to);
+            }
+            self.board_widget.reset_clicks();
+        }
+        if let ViewState::Puzzle {
+            attempt,
+            next_step: next_step @ Some(_),
+        } = &mut self.state
+        {
+            match attempt.play(next_step.take().unwrap()) {
+                Ok(PuzzleStatus::Playing) => {}
+                Ok(status) => {
+                    let solved = status == PuzzleStatus::Solved;
+                    self.puzzle_rating.update(&attempt.puzzle, solved);
+                    if let Err(e) = self.puzzle_rating.save(RATING_FILE) {
+                        error!("failed saving the puzzle rating: {e}");
+                    }
+                }
+                Err(e) => error!("{e}"),
+            }
+            self.board_widget.reset_clicks();
+        }
         if let ViewState::InGame {
             game,
             next_step: next_step @ Some(_),
@@ -141,9 +806,14 @@ impl App {
             let res = game.apply_stepkind(next_step.take().unwrap());
             match res {
                 Ok(new_state) => *state = new_state,
-                Err(_) => error!("Invalid move!"),
+                Err(e) => {
+                    info!("{e}");
+                    self.notice = Some(format!("Move failed: {e}"));
+                }
             }
             self.board_widget.reset_clicks();
+            // a move answers all open questions, e.g. it declines a draw offer
+            self.prompt = None;
         }
     }
 }
@@ -1,3 +1,7 @@
 pub mod app;
 pub mod state;
+pub mod theme;
 pub mod widgets;
+
+#[cfg(test)]
+mod theme_test;
@@ -0,0 +1,153 @@
+use crate::{
+    models::{
+        board::Board,
+        error::BoardError,
+        field_content::FieldContent,
+        location::Location,
+        piece::{ColoredPiece, PieceColor, PieceKind},
+    },
+    utils::fen::Fen,
+};
+
+/// the castling rights in the order of FEN
+pub const CASTLING_RIGHTS: [char; 4] = ['K', 'Q', 'k', 'q'];
+
+/// A position being set up by hand. Side to move, castling rights and the
+/// en passant square are chosen separately from the pieces and only checked
+/// against them once the position is built.
+#[derive(Debug, Clone)]
+pub struct BoardEditor {
+    /// the pieces placed so far
+    pub board: Board,
+    /// the piece a click on the board places
+    pub selected: ColoredPiece,
+    pub side: PieceColor,
+    /// whether the rights of `CASTLING_RIGHTS` are kept
+    pub castling: [bool; 4],
+    pub en_passant: Option<Location>,
+}
+
+impl BoardEditor {
+    /// starts from the pieces and settings of the board
+    pub fn new(board: &Board) -> Self {
+        let mut castling = [false; 4];
+        for (index, color) in [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .enumerate()
+        {
+            let Some(king) = board.find_king(color) else {
+                continue;
+            };
+            for rook_x in board.castling_rights(color) {
+                let queenside = rook_x < king.x;
+                castling[index * 2 + usize::from(queenside)] = true;
+            }
+        }
+        Self {
+            board: board.clone(),
+            selected: ColoredPiece {
+                kind: PieceKind::Pawn,
+                color: PieceColor::White,
+            },
+            side: board.turn_color(),
+            castling,
+            en_passant: board.en_passant(),
+        }
+    }
+
+    /// places the selected piece, or removes it if it is already there
+    pub fn toggle(&mut self, location: &Location) {
+        if !self.board.in_bounds(location) {
+            return;
+        }
+        match self.board.field_at_location(location) {
+            Some(FieldContent::Occupied { piece, .. }) if *piece == self.selected => {
+                self.board.remove_piece(location)
+            }
+            _ => self.board.put_piece_on_location(self.selected, location, 0),
+        }
+    }
+
+    /// removes all pieces, keeping the size, the variant and the fairy
+    /// pieces of the board
+    pub fn clear(&mut self) {
+        let mut board = Board::new(self.board.width, self.board.height);
+        board.set_variant(self.board.variant());
+        board.set_piece_set(self.board.piece_set().clone());
+        self.board = board;
+        self.castling = [false; 4];
+        self.en_passant = None;
+    }
+
+    /// the start position of the variant, white to move with all rights
+    pub fn reset(&mut self) {
+        *self = Self {
+            selected: self.selected,
+            ..Self::new(&self.board.variant().rules().start_position())
+        };
+    }
+
+    pub fn toggle_side(&mut self) {
+        self.side = self.side.invert();
+        self.en_passant = None;
+    }
+
+    pub fn toggle_castling(&mut self, index: usize) {
+        if let Some(right) = self.castling.get_mut(index) {
+            *right = !*right;
+        }
+    }
+
+    /// the squares a pawn of the side not to move may just have skipped
+    pub fn en_passant_squares(&self) -> Vec<Location> {
+        let mut board = self.board.clone();
+        board.set_turn(usize::from(self.side == PieceColor::Black));
+        board.en_passant_squares()
+    }
+
+    /// picks the next possible en passant square, none after the last one
+    pub fn cycle_en_passant(&mut self) {
+        let squares = self.en_passant_squares();
+        let next = match self.en_passant {
+            Some(current) => squares
+                .iter()
+                .position(|square| *square == current)
+                .and_then(|index| squares.get(index + 1)),
+            None => squares.first(),
+        };
+        self.en_passant = next.copied();
+    }
+
+    /// the castling rights in FEN, e.g. `KQk` or `-`
+    pub fn castling_text(&self) -> String {
+        let rights: String = CASTLING_RIGHTS
+            .iter()
+            .zip(self.castling)
+            .filter(|(_, kept)| *kept)
+            .map(|(right, _)| right)
+            .collect();
+        if rights.is_empty() {
+            "-".to_string()
+        } else {
+            rights
+        }
+    }
+
+    /// the position to play, if it is a legal one, see `Board::validate`
+    pub fn build(&self) -> Result<Board, BoardError> {
+        let placement = Fen::encode(&self.board)
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let side = match self.side {
+            PieceColor::White => "w",
+            PieceColor::Black => "b",
+        };
+        let en_passant = self
+            .en_passant
+            .map_or("-".to_string(), |square| square.encode());
+        let fen = format!("{placement} {side} {} {en_passant}", self.castling_text());
+        Fen::decode_valid_with(&fen, self.board.variant(), self.board.piece_set().clone())
+    }
+}
@@ -0,0 +1,155 @@
+use std::error::Error;
+
+use super::editor::BoardEditor;
+use crate::{
+    models::{
+        board::Board,
+        error::{BoardError, PositionError},
+        location::Location,
+        piece::{ColoredPiece, PieceColor, PieceKind},
+    },
+    utils::fen::{Fen, STANDARD_FEN},
+};
+
+fn piece(kind: PieceKind, color: PieceColor) -> ColoredPiece {
+    ColoredPiece { kind, color }
+}
+
+fn place(
+    editor: &mut BoardEditor,
+    piece: ColoredPiece,
+    square: &str,
+) -> Result<(), Box<dyn Error>> {
+    editor.selected = piece;
+    editor.toggle(&Location::decode(square)?);
+    Ok(())
+}
+
+#[test]
+fn standard_board_round_trips() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let editor = BoardEditor::new(&Board::standard_board());
+    // action
+    let board = editor.build()?;
+    // assert
+    assert_eq!(editor.castling_text(), "KQkq");
+    assert_eq!(Fen::encode(&board), STANDARD_FEN);
+    Ok(())
+}
+
+#[test]
+fn set_up_a_position() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut editor = BoardEditor::new(&Board::standard_board());
+    editor.clear();
+    // action
+    place(&mut editor, piece(PieceKind::King, PieceColor::White), "e1")?;
+    place(&mut editor, piece(PieceKind::Rook, PieceColor::White), "h1")?;
+    place(&mut editor, piece(PieceKind::King, PieceColor::Black), "e8")?;
+    place(&mut editor, piece(PieceKind::Pawn, PieceColor::Black), "d5")?;
+    place(&mut editor, piece(PieceKind::Pawn, PieceColor::White), "e5")?;
+    place(
+        &mut editor,
+        piece(PieceKind::Queen, PieceColor::White),
+        "a4",
+    )?;
+    // a second click removes the piece again
+    place(
+        &mut editor,
+        piece(PieceKind::Queen, PieceColor::White),
+        "a4",
+    )?;
+    editor.toggle_castling(0);
+    editor.cycle_en_passant();
+    let board = editor.build()?;
+    // assert
+    assert_eq!(editor.en_passant_squares(), vec![Location::decode("d6")?]);
+    assert_eq!(Fen::encode(&board), "4k3/8/8/3pP3/8/8/8/4K2R w K d6 0 1");
+    Ok(())
+}
+
+#[test]
+fn reject_illegal_positions() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut editor = BoardEditor::new(&Board::standard_board());
+    editor.clear();
+    place(&mut editor, piece(PieceKind::King, PieceColor::White), "e1")?;
+    // action
+    let no_black_king = editor.build();
+    place(&mut editor, piece(PieceKind::King, PieceColor::Black), "e8")?;
+    place(&mut editor, piece(PieceKind::Pawn, PieceColor::White), "a8")?;
+    let pawn_on_last_rank = editor.build();
+    place(&mut editor, piece(PieceKind::Pawn, PieceColor::White), "a8")?;
+    place(&mut editor, piece(PieceKind::Rook, PieceColor::White), "e4")?;
+    let waiting_side_in_check = editor.build();
+    editor.toggle_side();
+    let side_to_move_in_check = editor.build();
+    editor.toggle_castling(3);
+    let castling_without_rook = editor.build();
+    // assert
+    let invalid = |board: Result<Board, BoardError>| match board {
+        Err(BoardError::Invalid(e)) => Some(e),
+        _ => None,
+    };
+    assert_eq!(
+        invalid(no_black_king),
+        Some(PositionError::KingCount {
+            color: PieceColor::Black,
+            count: 0
+        })
+    );
+    assert_eq!(
+        invalid(pawn_on_last_rank),
+        Some(PositionError::PawnOnBackRank(Location::decode("a8")?))
+    );
+    assert_eq!(
+        invalid(waiting_side_in_check),
+        Some(PositionError::OpponentInCheck(PieceColor::Black))
+    );
+    assert!(side_to_move_in_check.is_ok());
+    // the castling right names no rook
+    assert!(matches!(castling_without_rook, Err(BoardError::Parse(_))));
+    Ok(())
+}
+
+#[test]
+fn reject_checks_from_opposite_sides() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut editor = BoardEditor::new(&Board::standard_board());
+    editor.clear();
+    place(&mut editor, piece(PieceKind::King, PieceColor::White), "e4")?;
+    place(&mut editor, piece(PieceKind::King, PieceColor::Black), "e8")?;
+    place(&mut editor, piece(PieceKind::Rook, PieceColor::Black), "a4")?;
+    place(
+        &mut editor,
+        piece(PieceKind::Bishop, PieceColor::Black),
+        "b1",
+    )?;
+    // action
+    let rook_and_bishop = editor.build();
+    place(&mut editor, piece(PieceKind::Rook, PieceColor::Black), "h4")?;
+    let two_rooks = editor.build();
+    // assert
+    assert!(rook_and_bishop.is_ok());
+    assert_eq!(
+        two_rooks.map(|_| ()),
+        Err(BoardError::Invalid(PositionError::TooManyCheckers {
+            color: PieceColor::White,
+            count: 3
+        }))
+    );
+    Ok(())
+}
+
+#[test]
+fn reset_to_the_start_position() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut editor = BoardEditor::new(&Fen::decode("4k3/8/8/8/8/8/8/4K3 b - - 0 1")?);
+    editor.selected = piece(PieceKind::Knight, PieceColor::Black);
+    // action
+    editor.reset();
+    // assert
+    assert_eq!(Fen::encode(&editor.build()?), STANDARD_FEN);
+    assert_eq!(editor.selected, piece(PieceKind::Knight, PieceColor::Black));
+    Ok(())
+}
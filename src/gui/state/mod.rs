@@ -1 +1,6 @@
+pub mod editor;
+pub mod prompt;
 pub mod view_state;
+
+#[cfg(test)]
+mod editor_test;
@@ -0,0 +1,26 @@
+use crate::models::piece::PieceColor;
+
+/// A question shown in the footer, answered with 'y' or 'n'
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Prompt {
+    Resign(PieceColor),
+    OfferDraw(PieceColor),
+    /// the given color has to answer a draw offer of the opponent
+    AnswerDraw(PieceColor),
+    Abort,
+}
+
+impl Prompt {
+    pub fn message(&self) -> String {
+        match self {
+            Prompt::Resign(color) => format!("{:?}, do you really want to resign? (y/n)", color),
+            Prompt::OfferDraw(color) => format!("{:?}, offer a draw? (y/n)", color),
+            Prompt::AnswerDraw(color) => format!(
+                "{:?} offers a draw. {:?}, do you accept? (y/n)",
+                color.invert(),
+                color
+            ),
+            Prompt::Abort => "Abort the game? (y/n)".to_string(),
+        }
+    }
+}
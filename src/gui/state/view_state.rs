@@ -1,8 +1,12 @@
 use crate::{
-    driver::game::Game,
-    models::{game_state::GameState, step::StepKind},
+    driver::{game::Game, puzzle::PuzzleAttempt, replay::Replay},
+    models::{board::Board, error::GameError, game_state::GameState, step::StepKind},
 };
 
+use super::editor::BoardEditor;
+
+// there is only ever one view state, so the size difference doesn't matter
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Default)]
 pub enum ViewState {
     #[default]
@@ -12,26 +16,66 @@ pub enum ViewState {
         game: Game,
         next_step: Option<StepKind>,
     },
+    /// solving a puzzle, the user's steps are checked against the solution
+    Puzzle {
+        attempt: PuzzleAttempt,
+        next_step: Option<StepKind>,
+    },
+    /// setting up a position, a click on the board comes as a drop of the
+    /// selected piece
+    Editor {
+        editor: BoardEditor,
+        next_step: Option<StepKind>,
+    },
+    /// stepping through a PGN, the board is only watched
+    Replay {
+        replay: Replay,
+        /// the digits typed so far to jump to a move
+        move_number: String,
+        next_step: Option<StepKind>,
+    },
 }
 
 impl ViewState {
     pub fn is_lobby_mode(&self) -> bool {
-        matches!(
-            self,
+        match self {
+            Self::Startup => true,
+            Self::InGame { state, .. } => state.is_over(),
+            // a puzzle, a setup or a replay can be left at any time
+            Self::Puzzle { .. } | Self::Editor { .. } | Self::Replay { .. } => true,
+        }
+    }
+
+    /// the board shown and where a step picked on it goes
+    pub fn board_input(&mut self) -> Option<(&Board, &mut Option<StepKind>)> {
+        match self {
+            Self::Startup => None,
             Self::InGame {
-                state: GameState::Stalemate | GameState::Won(_),
-                ..
-            } | Self::Startup
-        )
+                game, next_step, ..
+            } => Some((&game.board, next_step)),
+            Self::Puzzle { attempt, next_step } => Some((&attempt.game.board, next_step)),
+            Self::Editor { editor, next_step } => Some((&editor.board, next_step)),
+            Self::Replay {
+                replay, next_step, ..
+            } => Some((&replay.board, next_step)),
+        }
+    }
+
+    pub fn from_game(game: Game) -> Self {
+        Self::InGame {
+            state: game.state,
+            game,
+            next_step: None,
+        }
     }
 
-    pub fn start(&mut self) -> Result<(), String> {
+    pub fn start(&mut self, game: Game) -> Result<(), GameError> {
         if !self.is_lobby_mode() {
-            return Err("End this game first".to_string());
+            return Err(GameError::InProgress);
         }
         *self = Self::InGame {
             state: GameState::Ongoing,
-            game: Game::new(),
+            game,
             next_step: None,
         };
         Ok(())
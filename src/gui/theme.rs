@@ -0,0 +1,291 @@
+use std::{path::Path, str::FromStr};
+
+use ratatui::style::{Color, Modifier, Style};
+
+use crate::models::{
+    error::{DataError, FileError, ParseError},
+    piece::{ColoredPiece, PieceColor, PieceKind},
+};
+
+/// where the TUI looks for a theme when none is given on the command line
+pub const CONFIG_FILE: &str = "rustychess.conf";
+
+/// names of the built-in themes, the first one is the default
+pub const PRESETS: [&str; 4] = ["classic", "wood", "high-contrast", "monochrome"];
+
+/// How pieces are drawn. The nerd font codepoints look best but need a
+/// patched font, the other sets work in any terminal.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum GlyphSet {
+    #[default]
+    NerdFont,
+    /// the chess symbols of unicode, outlined for white and filled for black
+    Unicode,
+    /// letters as in FEN, upper case for white and lower case for black
+    Ascii,
+}
+
+impl GlyphSet {
+    pub const ALL: [GlyphSet; 3] = [GlyphSet::NerdFont, GlyphSet::Unicode, GlyphSet::Ascii];
+
+    pub fn symbol(&self, piece: &ColoredPiece) -> String {
+        match self {
+            GlyphSet::NerdFont => piece.kind.to_symbol(),
+            GlyphSet::Unicode => {
+                let (white, black) = match piece.kind {
+                    PieceKind::King => ('♔', '♚'),
+                    PieceKind::Queen => ('♕', '♛'),
+                    PieceKind::Rook => ('♖', '♜'),
+                    PieceKind::Bishop => ('♗', '♝'),
+                    PieceKind::Knight => ('♘', '♞'),
+                    PieceKind::Pawn => ('♙', '♟'),
+                    PieceKind::Fairy(_) => return GlyphSet::Ascii.symbol(piece),
+                };
+                match piece.color {
+                    PieceColor::White => white.to_string(),
+                    PieceColor::Black => black.to_string(),
+                }
+            }
+            GlyphSet::Ascii => match piece.color {
+                PieceColor::White => piece.kind.encode(),
+                PieceColor::Black => piece.kind.encode().to_lowercase(),
+            },
+        }
+    }
+
+    pub fn encode(&self) -> &'static str {
+        match self {
+            GlyphSet::NerdFont => "nerd-font",
+            GlyphSet::Unicode => "unicode",
+            GlyphSet::Ascii => "ascii",
+        }
+    }
+
+    pub fn decode(input: &str) -> Result<GlyphSet, ParseError> {
+        Self::ALL
+            .into_iter()
+            .find(|glyphs| glyphs.encode() == input.trim().to_lowercase())
+            .ok_or_else(|| ParseError::new(input, 0, "one of nerd-font, unicode, ascii"))
+    }
+
+    /// the set after this one, for cycling through them in the TUI
+    pub fn next(&self) -> GlyphSet {
+        let index = Self::ALL
+            .iter()
+            .position(|glyphs| glyphs == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+/// Colors and glyphs of the board. Besides the colors, the sides can be told
+/// apart by a text modifier per side, which is what the monochrome theme
+/// relies on.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub name: &'static str,
+    pub light_square: Color,
+    pub dark_square: Color,
+    pub white_piece: Color,
+    pub black_piece: Color,
+    pub white_modifier: Modifier,
+    pub black_modifier: Modifier,
+    /// border of the field under the mouse
+    pub highlight: Color,
+    /// border of the selected field
+    pub selection: Color,
+    pub border: Color,
+    pub glyphs: GlyphSet,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            name: PRESETS[0],
+            light_square: Color::DarkGray,
+            dark_square: Color::Black,
+            white_piece: Color::Cyan,
+            black_piece: Color::Red,
+            white_modifier: Modifier::BOLD,
+            black_modifier: Modifier::BOLD,
+            highlight: Color::Cyan,
+            selection: Color::Magenta,
+            border: Color::Gray,
+            glyphs: GlyphSet::NerdFont,
+        }
+    }
+}
+
+impl Theme {
+    pub fn preset(name: &str) -> Result<Theme, ParseError> {
+        let classic = Theme::default();
+        match name.trim().to_lowercase().as_str() {
+            "classic" => Ok(classic),
+            // for terminals with a light background
+            "wood" => Ok(Theme {
+                name: "wood",
+                light_square: Color::Rgb(240, 217, 181),
+                dark_square: Color::Rgb(181, 136, 99),
+                white_piece: Color::White,
+                black_piece: Color::Black,
+                highlight: Color::Blue,
+                selection: Color::Red,
+                border: Color::DarkGray,
+                ..classic
+            }),
+            "high-contrast" => Ok(Theme {
+                name: "high-contrast",
+                light_square: Color::Blue,
+                dark_square: Color::Black,
+                white_piece: Color::White,
+                black_piece: Color::LightRed,
+                highlight: Color::LightGreen,
+                selection: Color::LightYellow,
+                border: Color::White,
+                ..classic
+            }),
+            // no colors for the pieces, the sides differ in glyph and style
+            "monochrome" => Ok(Theme {
+                name: "monochrome",
+                light_square: Color::White,
+                dark_square: Color::Gray,
+                white_piece: Color::Black,
+                black_piece: Color::Black,
+                white_modifier: Modifier::BOLD,
+                black_modifier: Modifier::UNDERLINED,
+                highlight: Color::DarkGray,
+                selection: Color::Black,
+                border: Color::Gray,
+                glyphs: GlyphSet::Unicode,
+            }),
+            _ => Err(ParseError::new(
+                name,
+                0,
+                &format!("one of {}", PRESETS.join(", ")),
+            )),
+        }
+    }
+
+    /// the preset after this one, for cycling through them in the TUI
+    pub fn next_preset(&self) -> Theme {
+        let index = PRESETS
+            .iter()
+            .position(|name| *name == self.name)
+            .map_or(0, |index| (index + 1) % PRESETS.len());
+        Self::preset(PRESETS[index]).expect("all presets are known")
+    }
+
+    pub fn square(&self, x: usize, y: usize) -> Color {
+        if (x + y).is_multiple_of(2) {
+            self.dark_square
+        } else {
+            self.light_square
+        }
+    }
+
+    pub fn piece_style(&self, color: PieceColor) -> Style {
+        match color {
+            PieceColor::White => Style::default()
+                .fg(self.white_piece)
+                .add_modifier(self.white_modifier),
+            PieceColor::Black => Style::default()
+                .fg(self.black_piece)
+                .add_modifier(self.black_modifier),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Theme, DataError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        Self::decode(&content)
+    }
+
+    /// the theme of the config file, `CONFIG_FILE` if it exists and none is
+    /// given, overridden by the given preset and glyphs
+    pub fn resolve(
+        config: Option<&Path>,
+        preset: Option<Theme>,
+        glyphs: Option<GlyphSet>,
+    ) -> Result<Theme, DataError> {
+        let default = Path::new(CONFIG_FILE);
+        let config = config.or(default.exists().then_some(default));
+        let mut res = match (preset, config) {
+            (Some(theme), _) => theme,
+            (None, Some(path)) => Self::load(path)?,
+            (None, None) => Theme::default(),
+        };
+        if let Some(glyphs) = glyphs {
+            res.glyphs = glyphs;
+        }
+        Ok(res)
+    }
+
+    /// Reads a config file of `key = value` lines, lines starting with `#` are
+    /// comments. The `preset` key picks the theme to start from, all other keys
+    /// override single values of it:
+    ///
+    /// ```text
+    /// preset = wood
+    /// glyphs = unicode
+    /// dark_square = #b58863
+    /// black_modifier = bold+italic
+    /// ```
+    pub fn decode(input: &str) -> Result<Theme, DataError> {
+        let mut entries = vec![];
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(line, 0, "a 'key = value' line"))?;
+            entries.push((key.trim(), value.trim()));
+        }
+
+        let mut theme = match entries.iter().find(|(key, _)| *key == "preset") {
+            Some((_, preset)) => Self::preset(preset)?,
+            None => Theme::default(),
+        };
+        for (key, value) in entries {
+            let color = || Color::from_str(value).map_err(|_| ParseError::new(value, 0, "a color"));
+            match key {
+                "preset" => continue,
+                "glyphs" => theme.glyphs = GlyphSet::decode(value)?,
+                "light_square" => theme.light_square = color()?,
+                "dark_square" => theme.dark_square = color()?,
+                "white_piece" => theme.white_piece = color()?,
+                "black_piece" => theme.black_piece = color()?,
+                "white_modifier" => theme.white_modifier = Self::decode_modifier(value)?,
+                "black_modifier" => theme.black_modifier = Self::decode_modifier(value)?,
+                "highlight" => theme.highlight = color()?,
+                "selection" => theme.selection = color()?,
+                "border" => theme.border = color()?,
+                _ => return Err(DataError::UnknownKey(key.to_string())),
+            }
+            // glyphs alone don't make a theme of its own
+            if key != "glyphs" {
+                theme.name = "custom";
+            }
+        }
+        Ok(theme)
+    }
+
+    /// modifiers joined by '+', e.g. `bold+underlined`, or `none`
+    fn decode_modifier(input: &str) -> Result<Modifier, ParseError> {
+        let mut res = Modifier::empty();
+        let mut offset = 0;
+        for name in input.split('+') {
+            res |= match name.trim().to_lowercase().as_str() {
+                "none" => Modifier::empty(),
+                "bold" => Modifier::BOLD,
+                "dim" => Modifier::DIM,
+                "italic" => Modifier::ITALIC,
+                "underlined" => Modifier::UNDERLINED,
+                "reversed" => Modifier::REVERSED,
+                _ => return Err(ParseError::new(input, offset, "a modifier")),
+            };
+            offset += name.len() + 1;
+        }
+        Ok(res)
+    }
+}
@@ -0,0 +1,127 @@
+use std::error::Error;
+
+use ratatui::style::{Color, Modifier};
+
+use crate::{
+    gui::theme::{GlyphSet, Theme, PRESETS},
+    models::{
+        error::{DataError, ParseError},
+        piece::{ColoredPiece, PieceColor, PieceKind},
+    },
+};
+
+#[test]
+fn all_presets_exist() {
+    for name in PRESETS {
+        // action
+        let theme = Theme::preset(name);
+
+        // assert
+        assert_eq!(theme.map(|theme| theme.name), Ok(name));
+    }
+    assert!(Theme::preset("rainbow").is_err());
+}
+
+#[test]
+fn cycle_presets() {
+    // arrange
+    let mut theme = Theme::default();
+
+    // action
+    let names: Vec<&str> = (0..PRESETS.len())
+        .map(|_| {
+            theme = theme.next_preset();
+            theme.name
+        })
+        .collect();
+
+    // assert
+    assert_eq!(
+        names,
+        vec!["wood", "high-contrast", "monochrome", "classic"]
+    );
+}
+
+#[test]
+fn monochrome_tells_sides_apart_without_color() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let theme = Theme::preset("monochrome")?;
+    let white = ColoredPiece {
+        kind: PieceKind::Queen,
+        color: PieceColor::White,
+    };
+    let black = ColoredPiece {
+        color: PieceColor::Black,
+        ..white
+    };
+
+    // assert
+    assert_eq!(theme.white_piece, theme.black_piece);
+    assert_ne!(theme.glyphs.symbol(&white), theme.glyphs.symbol(&black));
+    assert_ne!(
+        theme.piece_style(PieceColor::White),
+        theme.piece_style(PieceColor::Black)
+    );
+    Ok(())
+}
+
+#[test]
+fn glyph_sets() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let knight = ColoredPiece {
+        kind: PieceKind::Knight,
+        color: PieceColor::Black,
+    };
+
+    // assert
+    assert_eq!(GlyphSet::Ascii.symbol(&knight), "n");
+    assert_eq!(GlyphSet::Unicode.symbol(&knight), "♞");
+    assert_eq!(
+        GlyphSet::NerdFont.symbol(&knight),
+        PieceKind::Knight.to_symbol()
+    );
+    for glyphs in GlyphSet::ALL {
+        assert_eq!(GlyphSet::decode(glyphs.encode())?, glyphs);
+    }
+    Ok(())
+}
+
+#[test]
+fn decode_config() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let config = "
+        # light terminal
+        dark_square = #b58863
+        preset = high-contrast
+        glyphs = ascii
+        black_modifier = bold+italic
+    ";
+
+    // action
+    let theme = Theme::decode(config)?;
+
+    // assert
+    assert_eq!(theme.name, "custom");
+    assert_eq!(theme.dark_square, Color::Rgb(0xb5, 0x88, 0x63));
+    assert_eq!(theme.light_square, Color::Blue);
+    assert_eq!(theme.glyphs, GlyphSet::Ascii);
+    assert_eq!(theme.black_modifier, Modifier::BOLD | Modifier::ITALIC);
+    Ok(())
+}
+
+#[test]
+fn decode_config_errors() {
+    assert!(matches!(
+        Theme::decode("light_square = no-color"),
+        Err(DataError::Parse(e)) if e == ParseError::new("no-color", 0, "a color")
+    ));
+    assert!(matches!(
+        Theme::decode("background = red"),
+        Err(DataError::UnknownKey(key)) if key == "background"
+    ));
+    assert!(matches!(Theme::decode("glyphs"), Err(DataError::Parse(_))));
+    assert!(matches!(
+        Theme::decode("white_modifier = bold+blinking"),
+        Err(DataError::Parse(e)) if e == ParseError::new("bold+blinking", 5, "a modifier")
+    ));
+}
@@ -0,0 +1,43 @@
+use ratatui::{
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+
+use crate::{logic::analysis::Analysis, models::board::Board};
+
+/// Shows the best lines the engine found for the board so far
+pub struct AnalysisWidget<'a> {
+    pub board: &'a Board,
+    /// nothing until the first depth is done
+    pub analysis: Option<Analysis>,
+}
+
+impl Widget for AnalysisWidget<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let (title, lines) = match &self.analysis {
+            Some(analysis) => (
+                format!(" Analysis, depth {} ", analysis.depth),
+                analysis
+                    .lines
+                    .iter()
+                    .map(|line| {
+                        Line::from(vec![
+                            format!("{:>7} ", line.score_text()).bold(),
+                            line.numbered(self.board).into(),
+                        ])
+                    })
+                    .collect(),
+            ),
+            None => (
+                " Analysis ".to_string(),
+                vec![Line::styled("thinking...", Style::default().italic())],
+            ),
+        };
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().border_set(border::PLAIN).title(title))
+            .render(area, buf);
+    }
+}
@@ -1,15 +1,18 @@
 use log::debug;
 use ratatui::{
     layout::Rect,
-    style::{Color, Style},
+    style::Style,
     symbols::border::{self, Set},
     widgets::{Block, StatefulWidget, Widget},
 };
 
 use crate::{
-    driver::game::Game,
-    gui::state::view_state::ViewState,
-    models::step::{Step, StepKind},
+    gui::{state::view_state::ViewState, theme::Theme},
+    models::{
+        location::Location,
+        piece::PieceKind,
+        step::{Step, StepKind},
+    },
 };
 
 use super::board_field::BoardField;
@@ -19,6 +22,9 @@ pub struct BoardWidget {
     pub mouse_position: (u16, u16),
     pub click_state_1: ClickState,
     pub click_state_2: ClickState,
+    pub theme: Theme,
+    /// piece picked from the pocket, the next click on a field drops it
+    pub drop_piece: Option<PieceKind>,
 }
 
 pub struct BoardState {
@@ -47,18 +53,14 @@ const BORDER_SET: Set = Set {
 impl StatefulWidget for &BoardWidget {
     type State = ViewState;
     fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer, state: &mut ViewState) {
-        // this must match, otherwise this render method is not called:
-        let ViewState::InGame {
-            game: Game { board },
-            next_step,
-            ..
-        } = state
-        else {
+        // there must be a board, otherwise this render method is not called:
+        let Some((board, next_step)) = state.board_input() else {
             unreachable!()
         };
 
-        let full_width = CELL_WIDTH * 8 + COORDINATE_WIDTH * 2 + 2 * BORDER_WIDTH;
-        let full_height = CELL_HEIGHT * 8 + COORDINATE_HEIGHT * 2 + 2 * BORDER_HEIGHT;
+        let (width, height) = (board.width as u16, board.height as u16);
+        let full_width = CELL_WIDTH * width + COORDINATE_WIDTH * 2 + 2 * BORDER_WIDTH;
+        let full_height = CELL_HEIGHT * height + COORDINATE_HEIGHT * 2 + 2 * BORDER_HEIGHT;
         let outer_square = Rect::new(area.x, area.y, full_width, full_height);
         let border_square = Rect::new(
             outer_square.x + COORDINATE_WIDTH,
@@ -69,25 +71,35 @@ impl StatefulWidget for &BoardWidget {
         let fields_square = Rect::new(
             border_square.x + BORDER_WIDTH,
             border_square.y + BORDER_HEIGHT,
-            8 * CELL_WIDTH,
-            8 * CELL_HEIGHT,
+            width * CELL_WIDTH,
+            height * CELL_HEIGHT,
         );
 
         Block::bordered()
             .border_set(BORDER_SET)
-            .border_style(Style::default().fg(Color::Gray))
+            .border_style(Style::default().fg(self.theme.border))
             .render(border_square, buf);
 
         Block::bordered()
             .border_set(border::PLAIN)
             .render(outer_square, buf);
 
-        let board_state = self.board_fields(&fields_square);
-        if let (Some(pos1), Some(pos2)) =
-            (board_state.first_selection, board_state.second_selection)
-        {
-            let stepkind = StepKind::GoTo(Step::new(pos1, pos2));
-            *next_step = Some(stepkind);
+        let board_state = self.board_fields(&fields_square, width, height);
+        match (
+            self.drop_piece,
+            board_state.first_selection,
+            board_state.second_selection,
+        ) {
+            (Some(piece), Some((x, y)), _) => {
+                *next_step = Some(StepKind::Drop {
+                    piece,
+                    to: Location::new(x, y),
+                })
+            }
+            (None, Some(pos1), Some(pos2)) => {
+                *next_step = Some(StepKind::GoTo(Step::new(pos1, pos2)));
+            }
+            _ => {}
         }
         for field in board_state.board_fields.iter() {
             let (x, y) = field.chess_coordinates;
@@ -100,24 +112,20 @@ impl StatefulWidget for &BoardWidget {
 }
 
 impl BoardWidget {
-    fn board_fields(&self, area: &Rect) -> BoardState {
+    fn board_fields(&self, area: &Rect, width: u16, height: u16) -> BoardState {
         let mut res_fields = vec![];
         let selected1 = self.click_state_1.selected.unwrap_or((u16::MAX, u16::MAX));
         let selected2 = self.click_state_2.selected.unwrap_or((u16::MAX, u16::MAX));
         let mut selected1_chess_cords = None;
         let mut selected2_chess_cords = None;
-        for i in 0..8 {
-            for j in 0..8 {
+        for i in 0..width {
+            for j in 0..height {
                 // we draw top to bottom, but the chess board is indexed bottom to top
                 let chess_i = i as usize;
-                let chess_j = 7 - j as usize;
+                let chess_j = (height - 1 - j) as usize;
                 let x = CELL_WIDTH * i;
                 let y = CELL_HEIGHT * j;
-                let color = if (i + j) % 2 == 0 {
-                    Color::DarkGray
-                } else {
-                    Color::Black
-                };
+                let color = self.theme.square(chess_i, chess_j);
                 let left = area.x + x;
                 let right = left + CELL_WIDTH;
                 let top = area.y + y;
@@ -146,6 +154,7 @@ impl BoardWidget {
                     color,
                     contains_mouse,
                     was_clicked: contains_first_click,
+                    theme: self.theme,
                 };
                 res_fields.push(cell);
             }
@@ -182,6 +191,7 @@ impl BoardWidget {
         debug!("reseting all clicks");
         self.click_state_1.reset();
         self.click_state_2.reset();
+        self.drop_piece = None;
     }
 }
 
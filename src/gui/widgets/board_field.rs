@@ -1,11 +1,11 @@
 use ratatui::{
     layout::Rect,
-    style::{Color, Style, Styled, Stylize},
+    style::{Color, Styled, Stylize},
     symbols::border::{self, Set},
     widgets::{Block, Borders, Paragraph, StatefulWidget, Widget},
 };
 
-use crate::models::{field_content::FieldContent, piece::PieceColor};
+use crate::{gui::theme::Theme, models::field_content::FieldContent};
 
 use super::board::{CELL_HEIGHT, CELL_WIDTH};
 
@@ -19,6 +19,7 @@ pub struct BoardField {
     pub color: Color,
     pub contains_mouse: bool,
     pub was_clicked: bool,
+    pub theme: Theme,
 }
 
 pub const BORDER_OUTER: Set = Set {
@@ -45,15 +46,18 @@ impl StatefulWidget for &BoardField {
         if self.was_clicked {
             block = block
                 .border_set(BORDER_OUTER)
-                .magenta()
+                .fg(self.theme.selection)
                 .borders(Borders::ALL);
         } else if self.contains_mouse {
-            block = block.border_set(BORDER_OUTER).cyan().borders(Borders::ALL);
+            block = block
+                .border_set(BORDER_OUTER)
+                .fg(self.theme.highlight)
+                .borders(Borders::ALL);
         }
 
         block.render(rect, buf);
 
-        let content_opt = BoardField::content_paragraph(field);
+        let content_opt = self.content_paragraph(field);
         if let Some(content_par) = content_opt {
             let centered_rect = Rect::new(
                 area.x + self.x + CELL_WIDTH / 2,
@@ -67,18 +71,10 @@ impl StatefulWidget for &BoardField {
 }
 
 impl BoardField {
-    pub fn content_paragraph(content: &FieldContent) -> Option<Paragraph<'_>> {
-        if let Some(color) = content.get_color() {
-            let tui_color = match color {
-                PieceColor::White => Color::Cyan,
-                PieceColor::Black => Color::Red,
-            };
-            let symbol = content.to_uncolored_piece_string();
-            Some(
-                Paragraph::new(symbol)
-                    .set_style(Style::default().fg(tui_color))
-                    .bold(),
-            )
+    pub fn content_paragraph(&self, content: &FieldContent) -> Option<Paragraph<'_>> {
+        if let Some((piece, _)) = content.get_content() {
+            let symbol = self.theme.glyphs.symbol(&piece);
+            Some(Paragraph::new(symbol).set_style(self.theme.piece_style(piece.color)))
         } else {
             None
         }
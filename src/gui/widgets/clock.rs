@@ -0,0 +1,41 @@
+use ratatui::{
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{
+    driver::game::Game,
+    models::{clock::format_duration, piece::PieceColor},
+};
+
+/// Shows the remaining time of both players, the opponent on top
+pub struct ClockWidget<'a> {
+    pub game: &'a Game,
+}
+
+impl Widget for ClockWidget<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let Some(clock) = &self.game.clock else {
+            return;
+        };
+        let line = |color: PieceColor| {
+            let remaining = format_duration(clock.remaining(color));
+            let style = if clock.active() == color {
+                Style::default().bold().reversed()
+            } else {
+                Style::default()
+            };
+            Line::styled(format!("{:?} {remaining}", color), style)
+        };
+        let text = Text::from(vec![
+            line(PieceColor::Black),
+            Line::default(),
+            line(PieceColor::White),
+        ]);
+        Paragraph::new(text)
+            .block(Block::bordered().border_set(border::PLAIN))
+            .render(area, buf);
+    }
+}
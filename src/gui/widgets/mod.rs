@@ -1,6 +1,20 @@
+mod analysis;
 mod board;
 mod board_field;
+mod clock;
 mod header;
+mod palette;
+mod pocket;
+mod puzzle;
+mod replay;
+mod setup;
 
+pub use analysis::AnalysisWidget;
 pub use board::{BoardWidget, ClickDirection};
+pub use clock::ClockWidget;
 pub use header::Header;
+pub use palette::PaletteWidget;
+pub use pocket::PocketWidget;
+pub use puzzle::PuzzleWidget;
+pub use replay::ReplayWidget;
+pub use setup::SetupWidget;
@@ -0,0 +1,79 @@
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{
+    gui::theme::Theme,
+    models::piece::{ColoredPiece, PieceColor, PieceKind},
+};
+
+const KINDS: [PieceKind; 6] = [
+    PieceKind::King,
+    PieceKind::Queen,
+    PieceKind::Rook,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Pawn,
+];
+
+/// The pieces of the board editor, white on top. Clicking one selects it
+/// for placing it on the board.
+pub struct PaletteWidget<'a> {
+    pub theme: &'a Theme,
+    pub selected: ColoredPiece,
+}
+
+impl PaletteWidget<'_> {
+    /// the piece shown at the given screen position
+    pub fn piece_at(area: Rect, position: (u16, u16)) -> Option<ColoredPiece> {
+        let inner = Block::bordered().inner(area);
+        if !inner.contains(Position::new(position.0, position.1)) {
+            return None;
+        }
+        Self::lines()
+            .into_iter()
+            .nth((position.1 - inner.y) as usize)?
+    }
+
+    /// the pieces line by line, with a gap between the colors
+    fn lines() -> Vec<Option<ColoredPiece>> {
+        let mut res = vec![];
+        for color in [PieceColor::White, PieceColor::Black] {
+            res.extend(KINDS.map(|kind| Some(ColoredPiece { kind, color })));
+            res.push(None);
+        }
+        res
+    }
+}
+
+impl Widget for PaletteWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer) {
+        let lines: Vec<Line> = Self::lines()
+            .into_iter()
+            .map(|piece| match piece {
+                Some(piece) => {
+                    let mut style = self.theme.piece_style(piece.color);
+                    if piece == self.selected {
+                        style = style.reversed();
+                    }
+                    Line::styled(
+                        format!("{} {:?}", self.theme.glyphs.symbol(&piece), piece.kind),
+                        style,
+                    )
+                }
+                None => Line::default(),
+            })
+            .collect();
+        Paragraph::new(Text::from(lines))
+            .block(
+                Block::bordered()
+                    .border_set(border::PLAIN)
+                    .border_style(Style::default().fg(self.theme.border)),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,98 @@
+use ratatui::{
+    layout::{Position, Rect},
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{
+    gui::theme::Theme,
+    models::{
+        board::Board,
+        piece::{ColoredPiece, PieceColor, PieceKind},
+    },
+};
+
+/// Shows the pockets of crazyhouse, black on top. Clicking a piece of the
+/// side to move selects it for a drop.
+pub struct PocketWidget<'a> {
+    pub board: &'a Board,
+    pub theme: &'a Theme,
+    /// the piece picked for the next drop
+    pub selected: Option<PieceKind>,
+}
+
+/// what is shown in a line of the panel
+enum PocketLine {
+    Title(PieceColor),
+    Piece(ColoredPiece, usize),
+    Empty,
+}
+
+impl PocketWidget<'_> {
+    /// the piece of the side to move shown at the given screen position
+    pub fn piece_at(board: &Board, area: Rect, position: (u16, u16)) -> Option<PieceKind> {
+        let inner = Block::bordered().inner(area);
+        if !inner.contains(Position::new(position.0, position.1)) {
+            return None;
+        }
+        match Self::lines(board)
+            .into_iter()
+            .nth((position.1 - inner.y) as usize)?
+        {
+            PocketLine::Piece(piece, _) if piece.color == board.turn_color() => Some(piece.kind),
+            _ => None,
+        }
+    }
+
+    fn lines(board: &Board) -> Vec<PocketLine> {
+        let mut res = vec![];
+        for color in [PieceColor::Black, PieceColor::White] {
+            res.push(PocketLine::Title(color));
+            let mut counted: Vec<(PieceKind, usize)> = vec![];
+            for kind in board.pocket(color) {
+                match counted.last_mut() {
+                    Some((last, count)) if last == kind => *count += 1,
+                    _ => counted.push((*kind, 1)),
+                }
+            }
+            res.extend(
+                counted
+                    .into_iter()
+                    .map(|(kind, count)| PocketLine::Piece(ColoredPiece { kind, color }, count)),
+            );
+            res.push(PocketLine::Empty);
+        }
+        res
+    }
+}
+
+impl Widget for PocketWidget<'_> {
+    fn render(self, area: Rect, buf: &mut ratatui::prelude::Buffer) {
+        let lines: Vec<Line> = Self::lines(self.board)
+            .into_iter()
+            .map(|line| match line {
+                PocketLine::Title(color) => Line::from(format!("{color:?}")).bold(),
+                PocketLine::Piece(piece, count) => {
+                    let mut style = self.theme.piece_style(piece.color);
+                    if piece.color == self.board.turn_color() && self.selected == Some(piece.kind) {
+                        style = style.reversed();
+                    }
+                    Line::styled(
+                        format!("{} x{count}", self.theme.glyphs.symbol(&piece)),
+                        style,
+                    )
+                }
+                PocketLine::Empty => Line::default(),
+            })
+            .collect();
+        Paragraph::new(Text::from(lines))
+            .block(
+                Block::bordered()
+                    .border_set(border::PLAIN)
+                    .border_style(Style::default().fg(self.theme.border)),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,62 @@
+use ratatui::{
+    style::Stylize,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    driver::{
+        puzzle::{PuzzleAttempt, PuzzleRating, PuzzleStatus},
+        uci::UciDriver,
+    },
+    utils::san::San,
+};
+
+/// Shows the puzzle being solved and the user's rating. The themes give
+/// the solution away, so they only show once the puzzle is over.
+pub struct PuzzleWidget<'a> {
+    pub attempt: &'a PuzzleAttempt,
+    pub rating: &'a PuzzleRating,
+}
+
+impl Widget for PuzzleWidget<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let (attempt, rating) = (self.attempt, self.rating);
+        let board = &attempt.game.board;
+        let solution = attempt
+            .solution()
+            .and_then(|solution| UciDriver::decode_step(board, solution).ok())
+            .map(|step| San::encode(board, &step))
+            .unwrap_or_default();
+        let status = match attempt.status {
+            PuzzleStatus::Playing => format!("Find the best move for {:?}", attempt.color).bold(),
+            PuzzleStatus::Solved => "Solved!".bold().green(),
+            PuzzleStatus::Failed => format!("Wrong, {solution} was the move").bold().red(),
+        };
+        let mut lines = vec![
+            Line::from(format!(
+                "Puzzle {} ({})",
+                attempt.puzzle.id, attempt.puzzle.rating
+            )),
+            Line::from(status),
+            Line::default(),
+            Line::from(format!("Your rating: {:.0}", rating.rating)),
+            Line::from(format!(
+                "{} solved, {} failed",
+                rating.solved, rating.failed
+            )),
+        ];
+        if attempt.status != PuzzleStatus::Playing {
+            lines.push(Line::default());
+            lines.push(Line::from(format!(
+                "Themes: {}",
+                attempt.puzzle.themes.join(", ")
+            )));
+        }
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().border_set(border::PLAIN))
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,108 @@
+use ratatui::{
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+
+use crate::driver::replay::Replay;
+
+// how many lines of moves are shown around the current one
+const MOVE_LINES: usize = 8;
+
+/// Shows the moves of the game being replayed around the current one, and
+/// the comments and variations of the PGN after it
+pub struct ReplayWidget<'a> {
+    pub replay: &'a Replay,
+    /// the digits typed so far to jump to a move
+    pub move_number: &'a str,
+}
+
+impl ReplayWidget<'_> {
+    /// the moves in pairs of White and Black, the last move played is
+    /// highlighted. Returns the lines and the one with the last move.
+    fn move_lines(&self) -> (Vec<Line<'static>>, usize) {
+        let replay = self.replay;
+        let start = replay.game.start.turn();
+        let mut lines: Vec<Line> = vec![];
+        let mut current = 0;
+        for (index, san) in replay.moves.iter().enumerate() {
+            let turn = start + index;
+            let number = turn / 2 + 1;
+            if turn.is_multiple_of(2) {
+                lines.push(Line::from(format!("{number}. ")));
+            } else if index == 0 {
+                lines.push(Line::from(format!("{number}... ")));
+            }
+            let span = if index + 1 == replay.ply() {
+                current = lines.len() - 1;
+                Span::from(san.clone()).reversed()
+            } else {
+                Span::from(san.clone())
+            };
+            if let Some(line) = lines.last_mut() {
+                line.push_span(span);
+                line.push_span(" ");
+            }
+        }
+        (lines, current)
+    }
+}
+
+impl Widget for ReplayWidget<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let replay = self.replay;
+        let tag = |name| replay.tag(name).unwrap_or("?");
+        let status = if replay.is_playing() {
+            "playing"
+        } else {
+            "paused"
+        };
+        let mut lines = vec![
+            Line::from(format!("{} - {}", tag("White"), tag("Black"))).bold(),
+            Line::from(format!("{}, result {}", tag("Event"), tag("Result"))),
+            Line::from(format!(
+                "Move {} of {}, {status}",
+                replay.ply(),
+                replay.moves.len()
+            )),
+            Line::from(format!(
+                "Autoplay: {:.2}s per move",
+                replay.interval().as_secs_f64()
+            )),
+            Line::default(),
+        ];
+
+        let (move_lines, current) = self.move_lines();
+        let first = current
+            .saturating_sub(MOVE_LINES / 2)
+            .min(move_lines.len().saturating_sub(MOVE_LINES));
+        lines.extend(move_lines.into_iter().skip(first).take(MOVE_LINES));
+
+        let notes = replay.notes().cloned().unwrap_or_default();
+        if !notes.comments.is_empty() || !notes.variations.is_empty() {
+            lines.push(Line::default());
+        }
+        for comment in notes.comments {
+            lines.push(Line::styled(comment, Style::default().italic()));
+        }
+        for variation in notes.variations {
+            lines.push(Line::from(vec!["Instead: ".bold(), variation.into()]));
+        }
+        if !self.move_number.is_empty() {
+            lines.push(Line::default());
+            lines.push(Line::from(format!(
+                "Jump to move {}_ (Enter)",
+                self.move_number
+            )));
+        }
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: true })
+            .block(
+                Block::bordered()
+                    .border_set(border::PLAIN)
+                    .title(" Replay "),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,36 @@
+use ratatui::{
+    style::Stylize,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget, Wrap},
+};
+
+use crate::gui::state::editor::BoardEditor;
+
+/// Shows the settings of the position being set up, and the keys to
+/// change them
+pub struct SetupWidget<'a> {
+    pub editor: &'a BoardEditor,
+}
+
+impl Widget for SetupWidget<'_> {
+    fn render(self, area: ratatui::prelude::Rect, buf: &mut ratatui::prelude::Buffer) {
+        let editor = self.editor;
+        let en_passant = editor
+            .en_passant
+            .map_or("-".to_string(), |square| square.encode());
+        let lines = vec![
+            Line::from("Board editor").bold(),
+            Line::default(),
+            Line::from(format!("Side to move: {:?} (m)", editor.side)),
+            Line::from(format!("Castling: {} (1-4)", editor.castling_text())),
+            Line::from(format!("En passant: {en_passant} (n)")),
+            Line::default(),
+            Line::from("Click a square to place the selected piece, click it again to remove it."),
+        ];
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: true })
+            .block(Block::bordered().border_set(border::PLAIN))
+            .render(area, buf);
+    }
+}
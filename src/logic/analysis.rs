@@ -0,0 +1,215 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    models::{board::Board, step::StepKind},
+    utils::san::San,
+};
+
+use super::{
+    evaluation::EvalParams,
+    nnue::Network,
+    search::{PvLine, Search, SearchInfo, SearchLimits},
+    tablebase::Tablebase,
+    transposition::TranspositionTable,
+};
+
+/// How a position is analysed
+#[derive(Debug, Clone)]
+pub struct AnalysisOptions {
+    pub depth: usize,
+    /// how many of the best moves get a line
+    pub lines: usize,
+    pub threads: usize,
+    /// weights of the evaluation when there is no network
+    pub params: EvalParams,
+    pub network: Option<Arc<Network>>,
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            depth: 4,
+            lines: 3,
+            threads: 1,
+            params: EvalParams::default(),
+            network: None,
+            tablebase: None,
+        }
+    }
+}
+
+/// One of the best moves of an analysed position with the line it leads to
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisLine {
+    /// in centipawns from the point of view of the side to move
+    pub score: i32,
+    /// number of moves until mate, negative if the side to move gets mated
+    pub mate_in: Option<i32>,
+    /// the principal variation, the first step is the move
+    pub steps: Vec<StepKind>,
+    /// the principal variation in standard algebraic notation
+    pub san: Vec<String>,
+}
+
+impl AnalysisLine {
+    fn new(board: &Board, line: &PvLine) -> Self {
+        let mut pv_board = board.clone();
+        let san = line
+            .pv
+            .iter()
+            .map(|step| {
+                let san = San::encode(&pv_board, step);
+                let _ = pv_board.apply_step_kind(step);
+                san
+            })
+            .collect();
+        Self {
+            score: line.score,
+            mate_in: line.mate_in(),
+            steps: line.pv.clone(),
+            san,
+        }
+    }
+
+    /// the score in pawns like `+0.35`, or the mate distance like `#-3`
+    pub fn score_text(&self) -> String {
+        match self.mate_in {
+            Some(moves) => format!("#{moves}"),
+            None => format!("{:+.2}", self.score as f64 / 100.0),
+        }
+    }
+
+    /// the variation with move numbers, e.g. `12... Nf6 13. Bg5`, starting
+    /// at the position the analysis was done for
+    pub fn numbered(&self, board: &Board) -> String {
+        let mut res = vec![];
+        for (index, san) in self.san.iter().enumerate() {
+            let ply = board.turn() + index;
+            if ply.is_multiple_of(2) {
+                res.push(format!("{}. {san}", ply / 2 + 1));
+            } else if index == 0 {
+                res.push(format!("{}... {san}", ply / 2 + 1));
+            } else {
+                res.push(san.clone());
+            }
+        }
+        res.join(" ")
+    }
+}
+
+/// The best lines of a position after one iteration of the search
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Analysis {
+    pub depth: usize,
+    pub nodes: u64,
+    pub elapsed: Duration,
+    /// best first
+    pub lines: Vec<AnalysisLine>,
+}
+
+impl Analysis {
+    pub fn new(board: &Board, info: &SearchInfo) -> Self {
+        Self {
+            depth: info.depth,
+            nodes: info.nodes,
+            elapsed: info.elapsed,
+            lines: info
+                .lines
+                .iter()
+                .map(|line| AnalysisLine::new(board, line))
+                .collect(),
+        }
+    }
+}
+
+/// Searches the best lines of the position, reporting them after every
+/// depth. The search ends early once the stop flag is set.
+pub fn analyze(
+    board: &Board,
+    options: &AnalysisOptions,
+    stop: Option<Arc<AtomicBool>>,
+    mut on_update: impl FnMut(&Analysis),
+) -> Analysis {
+    let limits = SearchLimits {
+        depth: options.depth,
+        stop,
+        threads: options.threads,
+        multi_pv: options.lines,
+        ..Default::default()
+    };
+    let table = TranspositionTable::default();
+    let mut search = Search::new(&options.params, limits).with_transposition_table(&table);
+    if let Some(tablebase) = &options.tablebase {
+        search = search.with_tablebase(tablebase);
+    }
+    if let Some(network) = &options.network {
+        search = search.with_network(network);
+    }
+    let info = search.run(board, |info| on_update(&Analysis::new(board, info)));
+    Analysis::new(board, &info)
+}
+
+/// Analyses a position on a background thread, e.g. while the TUI shows
+/// it. The search ends when the analyzer is stopped or dropped.
+#[derive(Debug)]
+pub struct Analyzer {
+    /// the position being analysed
+    pub board: Board,
+    stop: Arc<AtomicBool>,
+    latest: Arc<Mutex<Option<Analysis>>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Analyzer {
+    pub fn start(board: Board, options: AnalysisOptions) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let latest = Arc::new(Mutex::new(None));
+        let handle = {
+            let (board, stop, latest) = (board.clone(), stop.clone(), latest.clone());
+            thread::spawn(move || {
+                analyze(&board, &options, Some(stop), |analysis| {
+                    if let Ok(mut latest) = latest.lock() {
+                        *latest = Some(analysis.clone());
+                    }
+                });
+            })
+        };
+        Self {
+            board,
+            stop,
+            latest,
+            handle: Some(handle),
+        }
+    }
+
+    /// the lines of the deepest completed iteration so far
+    pub fn latest(&self) -> Option<Analysis> {
+        self.latest.lock().ok()?.clone()
+    }
+
+    /// whether the search reached its depth or was stopped
+    pub fn is_finished(&self) -> bool {
+        self.handle.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    pub fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Analyzer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
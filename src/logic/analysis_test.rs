@@ -0,0 +1,94 @@
+use std::{error::Error, thread, time::Duration};
+
+use crate::{
+    logic::analysis::{analyze, AnalysisLine, AnalysisOptions, Analyzer},
+    models::step::StepKind,
+    utils::{fen::Fen, san::San},
+};
+
+#[test]
+fn analysis_reports_the_best_lines() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1")?;
+    let options = AnalysisOptions {
+        depth: 2,
+        lines: 3,
+        ..Default::default()
+    };
+    let mut depths = vec![];
+    // action
+    let analysis = analyze(&board, &options, None, |update| depths.push(update.depth));
+    // assert
+    assert_eq!(depths, vec![1, 2]);
+    assert_eq!(analysis.lines.len(), 3);
+    assert_eq!(analysis.lines[0].san.first(), Some(&"Rxd5".to_string()));
+    let first_steps: Vec<&StepKind> = analysis.lines.iter().map(|line| &line.steps[0]).collect();
+    assert!(first_steps[1] != first_steps[0] && first_steps[2] != first_steps[0]);
+    assert!(first_steps[1] != first_steps[2]);
+    assert!(analysis
+        .lines
+        .windows(2)
+        .all(|pair| pair[0].score >= pair[1].score));
+    Ok(())
+}
+
+#[test]
+fn analysis_has_at_most_a_line_per_legal_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("7k/8/8/8/8/8/8/K7 w - - 0 1")?;
+    let options = AnalysisOptions {
+        depth: 1,
+        lines: 5,
+        ..Default::default()
+    };
+    // action
+    let analysis = analyze(&board, &options, None, |_| {});
+    // assert
+    assert_eq!(analysis.lines.len(), 3);
+    assert!(analysis
+        .lines
+        .iter()
+        .all(|line| line.score_text() == "+0.00"));
+    Ok(())
+}
+
+#[test]
+fn numbered_line_starts_with_the_side_to_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1")?;
+    let e5 = San::decode(&board, "e5")?;
+    let line = AnalysisLine {
+        score: -25,
+        mate_in: None,
+        steps: vec![e5],
+        san: vec!["e5".to_string(), "Nf3".to_string(), "Nc6".to_string()],
+    };
+    // action
+    let numbered = line.numbered(&board);
+    // assert
+    assert_eq!(numbered, "1... e5 2. Nf3 Nc6");
+    assert_eq!(line.score_text(), "-0.25");
+    Ok(())
+}
+
+#[test]
+fn analyzer_keeps_the_latest_lines() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1")?;
+    let options = AnalysisOptions {
+        depth: 2,
+        lines: 2,
+        ..Default::default()
+    };
+    // action
+    let analyzer = Analyzer::start(board.clone(), options.clone());
+    while !analyzer.is_finished() {
+        thread::sleep(Duration::from_millis(10));
+    }
+    // assert
+    let expected = analyze(&board, &options, None, |_| {});
+    let latest = analyzer.latest().ok_or("no analysis")?;
+    assert_eq!(latest.depth, 2);
+    assert_eq!(latest.lines, expected.lines);
+    Ok(())
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use crate::{
+    models::{
+        board::Board,
+        error::{BookError, FileError},
+        step::StepKind,
+    },
+    utils::polyglot::Polyglot,
+};
+
+// key, move, weight and learn value, all big endian
+const ENTRY_SIZE: usize = 16;
+
+/// One move of a Polyglot book
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookEntry {
+    /// `Polyglot::hash` of the position the move is played in
+    pub key: u64,
+    /// the move as encoded by `Polyglot::encode_step`
+    pub step: u16,
+    /// how often the move should be played compared to the others
+    pub weight: u16,
+    /// unused, kept to write books back unchanged
+    pub learn: u32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BookOptions {
+    /// the book is only played from up to this move number
+    pub depth: usize,
+    /// in percent: 0 always plays the most popular move, 100 picks every
+    /// move in proportion to its weight
+    pub variety: u8,
+}
+
+impl Default for BookOptions {
+    fn default() -> Self {
+        Self {
+            depth: 16,
+            variety: 100,
+        }
+    }
+}
+
+/// An opening book in the Polyglot `.bin` format, which many engines and
+/// GUIs share
+#[derive(Debug, Clone, Default)]
+pub struct OpeningBook {
+    /// sorted by key, like in the file
+    entries: Vec<BookEntry>,
+}
+
+impl OpeningBook {
+    pub fn new(mut entries: Vec<BookEntry>) -> Self {
+        entries.sort_by_key(|entry| entry.key);
+        Self { entries }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        let bytes = FileError::read(path.as_ref())?;
+        Self::decode(&bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, BookError> {
+        if !bytes.len().is_multiple_of(ENTRY_SIZE) {
+            return Err(BookError::Size(bytes.len()));
+        }
+        let entries = bytes
+            .chunks_exact(ENTRY_SIZE)
+            .map(|chunk| BookEntry {
+                key: u64::from_be_bytes(chunk[0..8].try_into().unwrap()),
+                step: u16::from_be_bytes(chunk[8..10].try_into().unwrap()),
+                weight: u16::from_be_bytes(chunk[10..12].try_into().unwrap()),
+                learn: u32::from_be_bytes(chunk[12..16].try_into().unwrap()),
+            })
+            .collect();
+        Ok(Self::new(entries))
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                [
+                    &entry.key.to_be_bytes()[..],
+                    &entry.step.to_be_bytes(),
+                    &entry.weight.to_be_bytes(),
+                    &entry.learn.to_be_bytes(),
+                ]
+                .concat()
+            })
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// the legal book moves of the position with their weights, the most
+    /// popular first. Moves with weight 0 are never played.
+    pub fn steps(&self, board: &Board) -> Vec<(StepKind, u16)> {
+        if !Polyglot::supports(board) {
+            return vec![];
+        }
+        let key = Polyglot::hash(board);
+        let start = self.entries.partition_point(|entry| entry.key < key);
+        let mut res: Vec<(StepKind, u16)> = self.entries[start..]
+            .iter()
+            .take_while(|entry| entry.key == key)
+            .filter(|entry| entry.weight > 0)
+            .filter_map(|entry| Some((Polyglot::decode_step(board, entry.step)?, entry.weight)))
+            .collect();
+        res.sort_by_key(|(_, weight)| std::cmp::Reverse(*weight));
+        res
+    }
+
+    /// Chooses a book move at random, moves with a higher weight more often.
+    /// A lower variety leaves out the moves far less popular than the best
+    /// one. `random` can be any random number.
+    pub fn pick(&self, board: &Board, options: &BookOptions, random: u64) -> Option<StepKind> {
+        if board.turn() / 2 + 1 > options.depth {
+            return None;
+        }
+        let steps = self.steps(board);
+        let best = steps.first()?.1 as u64;
+        let variety = options.variety.min(100) as u64;
+        let threshold = (best * (100 - variety)).div_ceil(100);
+        let candidates: Vec<(StepKind, u64)> = steps
+            .into_iter()
+            .map(|(step, weight)| (step, weight as u64))
+            .filter(|(_, weight)| *weight >= threshold)
+            .collect();
+        let total: u64 = candidates.iter().map(|(_, weight)| weight).sum();
+        let mut target = random % total;
+        for (step, weight) in candidates {
+            if target < weight {
+                return Some(step);
+            }
+            target -= weight;
+        }
+        None
+    }
+}
@@ -0,0 +1,141 @@
+use std::error::Error;
+
+use super::book::{BookEntry, BookOptions, OpeningBook};
+use crate::{
+    models::board::Board,
+    utils::{fen::Fen, san::San},
+};
+
+// a few openings, made with the Polyglot key and move encoding
+const TEST_BOOK: &[u8] = include_bytes!("../../data/test_book.bin");
+
+fn book_steps(book: &OpeningBook, board: &Board) -> Vec<(String, u16)> {
+    book.steps(board)
+        .iter()
+        .map(|(step, weight)| (San::encode(board, step), *weight))
+        .collect()
+}
+
+#[test]
+fn start_position_moves_by_weight() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::decode(TEST_BOOK)?;
+    // action
+    let steps = book_steps(&book, &Board::standard_board());
+    // assert
+    // f3 has weight 0 and is left out
+    assert_eq!(
+        steps,
+        vec![
+            ("e4".to_string(), 10),
+            ("d4".to_string(), 5),
+            ("Nf3".to_string(), 1)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn castling_and_promotion_map_to_steps() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::decode(TEST_BOOK)?;
+    let italian =
+        Fen::decode("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")?;
+    let promotion = Fen::decode("8/4P1k1/8/8/8/8/8/4K3 w - - 0 1")?;
+    // action
+    let italian_steps = book_steps(&book, &italian);
+    let promotion_steps = book_steps(&book, &promotion);
+    // assert
+    assert_eq!(
+        italian_steps,
+        vec![("O-O".to_string(), 8), ("c3".to_string(), 3)]
+    );
+    assert_eq!(promotion_steps, vec![("e8=Q".to_string(), 2)]);
+    Ok(())
+}
+
+#[test]
+fn pick_is_weighted() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::decode(TEST_BOOK)?;
+    let board = Board::standard_board();
+    let options = BookOptions::default();
+    // action
+    let picks: Vec<String> = (0..16)
+        .filter_map(|random| book.pick(&board, &options, random))
+        .map(|step| San::encode(&board, &step))
+        .collect();
+    // assert
+    // the weights 10, 5 and 1 split the random numbers
+    assert_eq!(picks.iter().filter(|san| *san == "e4").count(), 10);
+    assert_eq!(picks.iter().filter(|san| *san == "d4").count(), 5);
+    assert_eq!(picks.iter().filter(|san| *san == "Nf3").count(), 1);
+    Ok(())
+}
+
+#[test]
+fn low_variety_keeps_to_popular_moves() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::decode(TEST_BOOK)?;
+    let board = Board::standard_board();
+    let narrow = BookOptions {
+        variety: 0,
+        ..Default::default()
+    };
+    let half = BookOptions {
+        variety: 50,
+        ..Default::default()
+    };
+    // action
+    let narrow_picks: Vec<_> = (0..16)
+        .filter_map(|random| book.pick(&board, &narrow, random))
+        .map(|step| San::encode(&board, &step))
+        .collect();
+    let half_picks: Vec<_> = (0..16)
+        .filter_map(|random| book.pick(&board, &half, random))
+        .map(|step| San::encode(&board, &step))
+        .collect();
+    // assert
+    assert!(narrow_picks.iter().all(|san| san == "e4"));
+    assert!(half_picks.contains(&"d4".to_string()));
+    assert!(!half_picks.contains(&"Nf3".to_string()));
+    Ok(())
+}
+
+#[test]
+fn book_depth_limits_the_moves_played() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::decode(TEST_BOOK)?;
+    let italian =
+        Fen::decode("r1bqk1nr/pppp1ppp/2n5/2b1p3/2B1P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 4 4")?;
+    let shallow = BookOptions {
+        depth: 3,
+        ..Default::default()
+    };
+    // action
+    let step = book.pick(&italian, &shallow, 0);
+    // assert
+    assert_eq!(step, None);
+    assert!(book.pick(&italian, &BookOptions::default(), 0).is_some());
+    Ok(())
+}
+
+#[test]
+fn encode_round_trip() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let book = OpeningBook::new(vec![BookEntry {
+        key: 0x463b96181691fc9c,
+        step: 0x031c,
+        weight: 7,
+        learn: 0,
+    }]);
+    // action
+    let encoded = book.encode();
+    let decoded = OpeningBook::decode(&encoded)?;
+    // assert
+    assert_eq!(encoded.len(), 16);
+    assert_eq!(decoded.encode(), encoded);
+    assert_eq!(OpeningBook::decode(TEST_BOOK)?.encode(), TEST_BOOK);
+    assert!(OpeningBook::decode(&encoded[..10]).is_err());
+    Ok(())
+}
@@ -0,0 +1,223 @@
+use std::path::Path;
+
+use crate::models::{
+    board::Board,
+    error::{DataError, FileError, ParseError},
+    piece::{PieceColor, PieceKind},
+};
+
+/// Scores a position in centipawns from the point of view of the side to move
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, board: &Board) -> i32;
+}
+
+/// Material with small bonuses for centralized pieces and advanced pawns
+#[derive(Debug, Default, Clone)]
+pub struct MaterialEvaluator;
+
+impl Evaluator for MaterialEvaluator {
+    fn evaluate(&self, board: &Board) -> i32 {
+        DEFAULT_PARAMS.evaluate(board)
+    }
+}
+
+const DEFAULT_PARAMS: EvalParams = EvalParams {
+    pawn: 100,
+    knight: 320,
+    bishop: 330,
+    rook: 500,
+    queen: 900,
+    minor_center: 12,
+    center_step: 2,
+    pawn_advance: 5,
+};
+
+/// The weights of the material evaluation, which can be tuned and loaded
+/// from a file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EvalParams {
+    pub pawn: i32,
+    pub knight: i32,
+    pub bishop: i32,
+    pub rook: i32,
+    pub queen: i32,
+    /// bonus of a knight or bishop on a center square
+    pub minor_center: i32,
+    /// what that bonus loses for every half step away from the center
+    pub center_step: i32,
+    /// bonus of a pawn for every rank it advanced
+    pub pawn_advance: i32,
+}
+
+impl Default for EvalParams {
+    fn default() -> Self {
+        DEFAULT_PARAMS
+    }
+}
+
+impl EvalParams {
+    /// the keys of the file, in the order of `values`
+    pub const NAMES: [&'static str; 8] = [
+        "pawn",
+        "knight",
+        "bishop",
+        "rook",
+        "queen",
+        "minor_center",
+        "center_step",
+        "pawn_advance",
+    ];
+
+    pub fn values(&self) -> [i32; 8] {
+        [
+            self.pawn,
+            self.knight,
+            self.bishop,
+            self.rook,
+            self.queen,
+            self.minor_center,
+            self.center_step,
+            self.pawn_advance,
+        ]
+    }
+
+    pub fn from_values(values: [i32; 8]) -> Self {
+        let [pawn, knight, bishop, rook, queen, minor_center, center_step, pawn_advance] = values;
+        Self {
+            pawn,
+            knight,
+            bishop,
+            rook,
+            queen,
+            minor_center,
+            center_step,
+            pawn_advance,
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, DataError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        Self::decode(&content)
+    }
+
+    /// Reads `key = value` lines with the names of `NAMES`, lines starting
+    /// with `#` are comments. Missing keys keep their default:
+    ///
+    /// ```text
+    /// pawn = 94
+    /// knight = 331
+    /// ```
+    pub fn decode(input: &str) -> Result<Self, DataError> {
+        let mut values = DEFAULT_PARAMS.values();
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(line, 0, "a 'key = value' line"))?;
+            let (key, value) = (key.trim(), value.trim());
+            let index = Self::NAMES
+                .iter()
+                .position(|name| *name == key)
+                .ok_or_else(|| DataError::UnknownKey(key.to_string()))?;
+            values[index] = value
+                .parse()
+                .map_err(|_| ParseError::new(value, 0, "a whole number"))?;
+        }
+        Ok(Self::from_values(values))
+    }
+
+    pub fn encode(&self) -> String {
+        Self::NAMES
+            .iter()
+            .zip(self.values())
+            .map(|(name, value)| format!("{name} = {value}\n"))
+            .collect()
+    }
+
+    fn value(&self, board: &Board, kind: PieceKind) -> i32 {
+        match kind {
+            PieceKind::Pawn => self.pawn,
+            PieceKind::Knight => self.knight,
+            PieceKind::Bishop => self.bishop,
+            PieceKind::Rook => self.rook,
+            PieceKind::Queen => self.queen,
+            PieceKind::King | PieceKind::Fairy(_) => piece_value(board, kind),
+        }
+    }
+}
+
+impl Evaluator for EvalParams {
+    fn evaluate(&self, board: &Board) -> i32 {
+        let score: i32 = board
+            .locations()
+            .iter()
+            .filter_map(|location| {
+                let (piece, _) = board.field_at_location(location)?.get_content()?;
+                let mut value = self.value(board, piece.kind);
+                // distance to the center, in doubled coordinates to stay integer
+                let center_x = board.width as i32 - 1;
+                let center_y = board.height as i32 - 1;
+                let distance = (location.x as i32 * 2 - center_x).abs()
+                    + (location.y as i32 * 2 - center_y).abs();
+                match piece.kind {
+                    PieceKind::Knight | PieceKind::Bishop => {
+                        value += self.minor_center - self.center_step * distance
+                    }
+                    PieceKind::Pawn => {
+                        let advanced = match piece.color {
+                            PieceColor::White => location.y as i32 - 1,
+                            PieceColor::Black => board.height as i32 - 2 - location.y as i32,
+                        };
+                        value += self.pawn_advance * advanced;
+                    }
+                    _ => {}
+                }
+                Some(if piece.color == PieceColor::White {
+                    value
+                } else {
+                    -value
+                })
+            })
+            .sum();
+        // pieces in the pocket are as good as on the board
+        let pockets: i32 = [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .map(|color| {
+                let value: i32 = board
+                    .pocket(color)
+                    .iter()
+                    .map(|kind| self.value(board, *kind))
+                    .sum();
+                if color == PieceColor::White {
+                    value
+                } else {
+                    -value
+                }
+            })
+            .sum();
+        let score = score + pockets;
+        match board.turn_color() {
+            PieceColor::White => score,
+            PieceColor::Black => -score,
+        }
+    }
+}
+
+/// fairy pieces are worth what the piece set of the board says
+pub fn piece_value(board: &Board, kind: PieceKind) -> i32 {
+    match kind {
+        PieceKind::Pawn => 100,
+        PieceKind::Knight => 320,
+        PieceKind::Bishop => 330,
+        PieceKind::Rook => 500,
+        PieceKind::Queen => 900,
+        PieceKind::King => 0,
+        PieceKind::Fairy(_) => board
+            .piece_set()
+            .get(kind)
+            .map_or(0, |definition| definition.value),
+    }
+}
@@ -0,0 +1,34 @@
+use std::error::Error;
+
+use super::evaluation::{EvalParams, Evaluator, MaterialEvaluator};
+use crate::utils::fen::Fen;
+
+#[test]
+fn default_params_evaluate_like_the_material_evaluator() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3")?;
+    // action
+    let score = EvalParams::default().evaluate(&board);
+    // assert
+    assert_eq!(score, MaterialEvaluator.evaluate(&board));
+    Ok(())
+}
+
+#[test]
+fn params_round_trip() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let params = EvalParams {
+        knight: 301,
+        pawn_advance: -2,
+        ..Default::default()
+    };
+    // action
+    let decoded = EvalParams::decode(&format!("# tuned\n{}", params.encode()))?;
+    let partial = EvalParams::decode("queen = 950")?;
+    // assert
+    assert_eq!(decoded, params);
+    assert_eq!(partial.queen, 950);
+    assert_eq!(partial.rook, EvalParams::default().rook);
+    assert!(EvalParams::decode("king = 5").is_err());
+    Ok(())
+}
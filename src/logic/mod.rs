@@ -1,5 +1,36 @@
+pub mod analysis;
 pub mod basic_evaluation;
+pub mod book;
+pub mod evaluation;
 pub mod move_computer;
+pub mod nnue;
+pub mod perft;
+pub mod search;
+pub mod tablebase;
+pub mod transposition;
+pub mod tuning;
+pub mod validation;
+pub mod variants;
 
+#[cfg(test)]
+mod analysis_test;
+#[cfg(test)]
+mod book_test;
+#[cfg(test)]
+mod evaluation_test;
 #[cfg(test)]
 mod move_computer_test;
+#[cfg(test)]
+mod nnue_test;
+#[cfg(test)]
+mod perft_test;
+#[cfg(test)]
+mod search_test;
+#[cfg(test)]
+mod tablebase_test;
+#[cfg(test)]
+mod transposition_test;
+#[cfg(test)]
+mod tuning_test;
+#[cfg(test)]
+mod validation_test;
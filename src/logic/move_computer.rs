@@ -1,5 +1,6 @@
 use crate::models::{
     board::Board,
+    error::{IllegalReason, MoveError},
     field_content::FieldContent,
     location::Location,
     piece::{ColoredPiece, MoveCapability, PieceColor, PieceKind},
@@ -27,10 +28,19 @@ impl StepComputer for Board {
     }
 
     fn compute_steps(&self) -> Vec<StepKind> {
+        let rules = self.variant().rules();
+        if rules.special_outcome(self).is_some() {
+            return vec![];
+        }
         let mut steps = self.compute_simple_steps(None);
         self.extend_promotions(&mut steps);
-        steps.append(&mut self.castle_moves());
-        self.filter_check_steps(&mut steps);
+        if rules.allows_castling() {
+            steps.append(&mut self.castle_moves());
+        }
+        if rules.has_drops() {
+            steps.append(&mut self.drop_steps());
+        }
+        rules.filter_legal(self, &mut steps);
 
         steps
     }
@@ -48,13 +58,18 @@ impl Board {
         color_opt: Option<PieceColor>,
         steps_opt: Option<Vec<StepKind>>,
     ) -> bool {
+        if !self.variant().rules().king_is_royal() {
+            return false;
+        }
         let color = color_opt.unwrap_or(self.turn_color());
-        let king_position = self.find_king(color).expect("No king on the board");
+        // e.g. white in horde has no king that could be checked
+        let Some(king_position) = self.find_king(color) else {
+            return false;
+        };
         let steps = steps_opt.unwrap_or_else(|| self.compute_simple_steps(Some(color.invert())));
-        steps.iter().any(|step| {
-            let target = step.target();
-            target.is_some() && target.unwrap() == king_position
-        })
+        self.variant()
+            .rules()
+            .king_attacked(self, color, king_position, &steps)
     }
 
     /// computes steps just based on the piece's capabilities,
@@ -67,50 +82,169 @@ impl Board {
             .collect()
     }
 
-    /// compute the possible castles
+    /// compute the possible castles. Works for chess960 as well: the king ends
+    /// on the c or g file and the rook next to it, all squares in between must
+    /// be empty apart from the two castling pieces
     pub fn castle_moves(&self) -> Vec<StepKind> {
         let color = self.turn_color();
-        let rank = if color == PieceColor::White {
-            0
-        } else {
-            self.height - 1
+        let rights = self.castling_rights(color);
+        if rights.is_empty() {
+            return vec![];
+        }
+        let rank = self.back_rank(color);
+        let Some(king_location) = self.find_king(color) else {
+            return vec![];
         };
-        let king_location = Location { x: 4, y: rank };
-        // 1. check king hasn't moved
-        let king_field_opt = self.field_at_location(&king_location);
-        match king_field_opt {
-            Some(FieldContent::Occupied {
-                piece:
-                    ColoredPiece {
-                        kind: PieceKind::King,
-                        ..
-                    },
-                turn: 0,
-            }) => {
-                let mut res = vec![];
-                // queenside:
-                // contains rook that hasn't been moved
-                let rook1_loc = Location::new(0, rank);
-                if self.location_contains_piece(&rook1_loc, vec![PieceKind::Rook], color) && self.location_piece_unmoved(&rook1_loc) && (1..3).all(|x| self.location_is_emtpy(&Location::new(x, rank))) {
-                    res.push(StepKind::Castle {
-                        king_step: Step::new((4, rank), (2, rank)),
-                        rook_step: Step::new((0, rank), (3, rank)),
-                    })
-                }
+        // castling out of check is not allowed
+        if self.has_check(Some(color)) {
+            return vec![];
+        }
+        let mut res = vec![];
+        for rook_x in rights {
+            // queenside when the rook is left of the king
+            let (king_to, rook_to) = if rook_x < king_location.x {
+                (2, 3)
+            } else {
+                (self.width - 2, self.width - 3)
+            };
+            let span = |a: usize, b: usize| a.min(b)..=a.max(b);
+            let path_free = span(king_location.x, king_to)
+                .chain(span(rook_x, rook_to))
+                .all(|x| {
+                    x == king_location.x
+                        || x == rook_x
+                        || self.location_is_emtpy(&Location::new(x, rank))
+                });
+            let path_safe = span(king_location.x, king_to)
+                .filter(|x| *x != king_location.x)
+                .all(|x| !self.square_attacked(&king_location, &Location::new(x, rank)));
+            if path_free && path_safe {
+                res.push(StepKind::Castle {
+                    king_step: Step::new((king_location.x, rank), (king_to, rank)),
+                    rook_step: Step::new((rook_x, rank), (rook_to, rank)),
+                })
+            }
+        }
+        res
+    }
 
-                // kingside
-                let rook2_loc = Location::new(self.width - 1, rank);
-                if self.location_contains_piece(&rook2_loc, vec![PieceKind::Rook], color) && self.location_piece_unmoved(&rook2_loc) && (5..=6).all(|x| self.location_is_emtpy(&Location::new(x, rank))) {
-                    res.push(StepKind::Castle {
-                        king_step: Step::new((4, rank), (6, rank)),
-                        rook_step: Step::new((self.width - 1, rank), (5, rank)),
+    /// putting a piece from the pocket on any empty field, but pawns neither
+    /// on the first nor on the last rank
+    pub fn drop_steps(&self) -> Vec<StepKind> {
+        let mut kinds: Vec<PieceKind> = vec![];
+        for kind in self.pocket(self.turn_color()) {
+            if !kinds.contains(kind) {
+                kinds.push(*kind);
+            }
+        }
+        let empty: Vec<Location> = self
+            .locations()
+            .into_iter()
+            .filter(|location| self.location_is_emtpy(location))
+            .collect();
+        kinds
+            .into_iter()
+            .flat_map(|piece| {
+                empty
+                    .iter()
+                    .filter(move |to| {
+                        piece != PieceKind::Pawn || (to.y != 0 && to.y != self.height - 1)
                     })
-                }
+                    .map(move |to| StepKind::Drop { piece, to: *to })
+            })
+            .collect()
+    }
 
-                res
+    /// whether the king would be in check on the given square, used for the
+    /// squares the king passes while castling
+    fn square_attacked(&self, king_location: &Location, square: &Location) -> bool {
+        let color = self.turn_color();
+        let mut new_board = self.clone();
+        if new_board
+            .apply_step(&Step {
+                from: *king_location,
+                to: *square,
+            })
+            .is_err()
+        {
+            return true;
+        }
+        new_board.has_check(Some(color))
+    }
+
+    /// whether the step takes a piece, including en passant
+    pub fn is_capture(&self, step: &StepKind) -> bool {
+        let (from, to) = match step {
+            StepKind::GoTo(step) | StepKind::Promote { step, .. } => (step.from, step.to),
+            StepKind::Castle { .. } | StepKind::Drop { .. } => return false,
+        };
+        !self.location_is_emtpy(&to)
+            || (self.en_passant() == Some(to)
+                && self.location_contains_piece(&from, vec![PieceKind::Pawn], self.turn_color()))
+    }
+
+    /// Explains why the side to move may not play the step, which is not
+    /// one of the legal steps
+    pub fn illegal_step_error(&self, step_kind: &StepKind) -> MoveError {
+        let (from, squares) = match step_kind {
+            StepKind::GoTo(step) | StepKind::Promote { step, .. } => {
+                (Some(step.from), vec![step.from, step.to])
             }
-            _ => vec![],
+            StepKind::Castle {
+                king_step,
+                rook_step,
+            } => (
+                Some(king_step.from),
+                vec![king_step.from, king_step.to, rook_step.from],
+            ),
+            StepKind::Drop { to, .. } => (None, vec![*to]),
+        };
+        if let Some(outside) = squares.into_iter().find(|square| !self.in_bounds(square)) {
+            return MoveError::OutOfBounds(outside);
         }
+        let illegal = |reason| MoveError::Illegal {
+            step: step_kind.clone(),
+            reason,
+        };
+        let color = self.turn_color();
+        let rules = self.variant().rules();
+        if let StepKind::Drop { piece, to } = step_kind {
+            return illegal(if !rules.has_drops() {
+                IllegalReason::ForbiddenByVariant
+            } else if !self.location_is_emtpy(to) {
+                IllegalReason::Occupied(*to)
+            } else if !self.pocket(color).contains(piece) {
+                IllegalReason::NotInPocket(*piece)
+            } else {
+                IllegalReason::Unreachable
+            });
+        }
+        let from = from.expect("only drops have no square they start from");
+        match self
+            .field_at_location(&from)
+            .and_then(FieldContent::get_content)
+        {
+            None => return illegal(IllegalReason::NoPiece(from)),
+            Some((piece, _)) if piece.color != color => {
+                return illegal(IllegalReason::OpponentsPiece(from))
+            }
+            Some(_) => {}
+        }
+        let mut pseudo_legal = self.compute_simple_steps(None);
+        self.extend_promotions(&mut pseudo_legal);
+        if rules.allows_castling() {
+            pseudo_legal.append(&mut self.castle_moves());
+        }
+        if !pseudo_legal.contains(step_kind) {
+            return illegal(IllegalReason::Unreachable);
+        }
+        let mut next = self.clone();
+        let checked = next.apply_step_kind(step_kind).is_ok() && next.has_check(Some(color));
+        illegal(if checked {
+            IllegalReason::LeavesKingInCheck
+        } else {
+            IllegalReason::ForbiddenByVariant
+        })
     }
 
     pub fn filter_check_steps(&self, steps: &mut Vec<StepKind>) {
@@ -140,7 +274,7 @@ impl Board {
             // check if it's a pawn, moving to last row
             if let StepKind::GoTo(Step { from, to }) = step {
                 if to.y != last_row {
-                    return;
+                    continue;
                 }
                 // if this panics, something with the move computation is wrong
                 let field = self.field_at_location(from).unwrap();
@@ -161,7 +295,13 @@ impl Board {
         for index in promotable {
             let step_kind = steps.remove(index);
             if let StepKind::GoTo(step) = step_kind {
-                steps.append(&mut StepKind::possible_promotions(step));
+                steps.extend(
+                    self.variant()
+                        .rules()
+                        .promotions()
+                        .into_iter()
+                        .map(|piece| StepKind::Promote { step, piece }),
+                );
             } else {
                 unreachable!()
             }
@@ -178,9 +318,28 @@ impl Board {
             if piece.color != turn_color {
                 return vec![];
             }
-            piece
-                .piece_move_capabilities()
-                .into_iter()
+            // pawns may only do a double step from their starting rank
+            let double_step =
+                self.variant()
+                    .rules()
+                    .pawn_double_step(self, piece.color, location.y);
+            let standard;
+            let capabilities = match piece.kind {
+                PieceKind::Fairy(_) => self.piece_set().capabilities(piece.kind),
+                _ => {
+                    standard = piece.piece_move_capabilities();
+                    &standard
+                }
+            };
+            capabilities
+                .iter()
+                .cloned()
+                .map(|mut cap| {
+                    if piece.kind == PieceKind::Pawn && !double_step {
+                        cap.distance = cap.distance.min(1);
+                    }
+                    cap
+                })
                 .flat_map(|cap| {
                     self.compute_steps_with_capablity(location, location, 0, piece.color, &cap)
                 })
@@ -223,7 +382,15 @@ impl Board {
             } else {
                 // the case where there is no piece
                 if cap.must_take {
-                    vec![]
+                    // only a pawn taking en passant may go to an empty field
+                    if self.en_passant() == Some(next_location) {
+                        vec![StepKind::GoTo(Step {
+                            from: *origin_location,
+                            to: next_location,
+                        })]
+                    } else {
+                        vec![]
+                    }
                 } else {
                     let mut res_recursive = self.compute_steps_with_capablity(
                         origin_location,
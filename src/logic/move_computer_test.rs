@@ -1,10 +1,12 @@
+use std::error::Error;
+
 use crate::driver::game::Game;
 use crate::logic::move_computer::StepComputer;
 use crate::models::game_state::GameState;
 use crate::models::{
     board::Board,
     piece::{ColoredPiece, PieceColor, PieceKind},
-    step::StepKind,
+    step::{Step, StepKind},
 };
 use crate::utils::board_creation::BoardCreation;
 
@@ -76,7 +78,7 @@ pub fn castle() {
 }
 
 #[test]
-pub fn checkmove() -> Result<(), String> {
+pub fn checkmove() -> Result<(), Box<dyn Error>> {
     let mut game = Game::new();
     let _ = game.apply_input("e2e4")?;
     let _ = game.apply_input("a7a6")?;
@@ -90,11 +92,12 @@ pub fn checkmove() -> Result<(), String> {
 }
 
 #[test]
-pub fn simple_take() -> Result<(), String> {
+pub fn simple_take() -> Result<(), Box<dyn Error>> {
     let descr: String = r#"
     a1kw
     a2rb
     b1rb
+    h8kb
     "#
     .to_string();
     let board = BoardCreation::from_description(descr)?;
@@ -103,3 +106,24 @@ pub fn simple_take() -> Result<(), String> {
     assert_eq!(possible_moves.len(), 2);
     Ok(())
 }
+
+#[test]
+pub fn steps_on_a_10x10_board() -> Result<(), Box<dyn Error>> {
+    let descr: String = r#"
+    a10kw
+    j1kb
+    "#
+    .to_string();
+    let board = BoardCreation::from_description_sized(10, 10, descr)?;
+    let possible_moves: Vec<String> = board
+        .compute_steps()
+        .iter()
+        .map(|step| step.encode_uci())
+        .collect();
+    assert_eq!(possible_moves, vec!["a10b9", "a10a9", "a10b10"]);
+    assert_eq!(
+        StepKind::decode("a10b9")?,
+        StepKind::GoTo(Step::new((0, 9), (1, 8)))
+    );
+    Ok(())
+}
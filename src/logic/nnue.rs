@@ -0,0 +1,356 @@
+use std::path::Path;
+
+use crate::models::{
+    board::Board,
+    error::{FileError, NetworkError},
+    piece::{ColoredPiece, PieceColor, PieceKind},
+};
+
+use super::evaluation::{Evaluator, MaterialEvaluator};
+
+/// inputs of the network, one for each color and kind of piece on each square
+pub const FEATURES: usize = 768;
+// quantization of the accumulator, which is also where the activation clips
+const QA: i32 = 255;
+// quantization of the output weights
+const QB: i32 = 64;
+// converts the output of the network to centipawns
+const SCALE: i32 = 400;
+const MAGIC: &[u8; 4] = b"RCNN";
+const VERSION: u32 = 1;
+// magic, version and hidden size
+const HEADER_SIZE: usize = 12;
+
+/// An efficiently updatable neural network with 768 inputs, one hidden
+/// layer of N neurons and a single output. The hidden layer is computed for
+/// both sides, each seeing the board from its own side, and the output
+/// weighs the side to move and its opponent differently.
+///
+/// The file format is little endian: `RCNN`, the version and the hidden size
+/// as u32, then i16 values for the 768 x N feature weights (feature major),
+/// the N feature biases, the 2 x N output weights (side to move first) and
+/// the output bias. Feature weights are quantized by 255, output weights by
+/// 64, and the output is scaled by 400 to centipawns, like networks trained
+/// with the common `bullet` trainer for this architecture.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Network {
+    hidden: usize,
+    feature_weights: Vec<i16>,
+    feature_bias: Vec<i16>,
+    output_weights: Vec<i16>,
+    output_bias: i16,
+}
+
+/// The hidden layer before its activation, for both sides. Kept along the
+/// searched line and updated with the pieces that changed, instead of being
+/// computed from all pieces again.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Accumulator {
+    /// from White's side, then from Black's
+    values: [Vec<i16>; 2],
+}
+
+impl Network {
+    /// The hidden size has to be a multiple of 16, the width the SIMD code
+    /// works on.
+    pub fn new(
+        feature_weights: Vec<i16>,
+        feature_bias: Vec<i16>,
+        output_weights: Vec<i16>,
+        output_bias: i16,
+    ) -> Result<Self, NetworkError> {
+        let hidden = feature_bias.len();
+        if hidden == 0 || !hidden.is_multiple_of(simd::LANES) {
+            return Err(NetworkError::HiddenSize(hidden));
+        }
+        if feature_weights.len() != FEATURES * hidden || output_weights.len() != 2 * hidden {
+            return Err(NetworkError::Weights { hidden });
+        }
+        Ok(Self {
+            hidden,
+            feature_weights,
+            feature_bias,
+            output_weights,
+            output_bias,
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, NetworkError> {
+        let bytes = FileError::read(path.as_ref())?;
+        Self::decode(&bytes)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, NetworkError> {
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err(NetworkError::NotANetwork);
+        }
+        let word = |index: usize| {
+            u32::from_le_bytes(bytes[index..index + 4].try_into().expect("four bytes")) as usize
+        };
+        if word(4) != VERSION as usize {
+            return Err(NetworkError::UnsupportedVersion(word(4) as u32));
+        }
+        let hidden = word(8);
+        let expected = HEADER_SIZE + 2 * (FEATURES * hidden + hidden + 2 * hidden + 1);
+        if bytes.len() != expected {
+            return Err(NetworkError::Size {
+                expected,
+                found: bytes.len(),
+            });
+        }
+        let mut values = bytes[HEADER_SIZE..]
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]));
+        let mut take = |count: usize| values.by_ref().take(count).collect::<Vec<_>>();
+        let feature_weights = take(FEATURES * hidden);
+        let feature_bias = take(hidden);
+        let output_weights = take(2 * hidden);
+        let output_bias = take(1)[0];
+        Self::new(feature_weights, feature_bias, output_weights, output_bias)
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend(VERSION.to_le_bytes());
+        bytes.extend((self.hidden as u32).to_le_bytes());
+        let values = self
+            .feature_weights
+            .iter()
+            .chain(&self.feature_bias)
+            .chain(&self.output_weights)
+            .chain([&self.output_bias]);
+        for value in values {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes
+    }
+
+    pub fn hidden_size(&self) -> usize {
+        self.hidden
+    }
+
+    /// only the six standard pieces on an 8x8 board are inputs of the network
+    pub fn supports(board: &Board) -> bool {
+        board.width == 8
+            && board.height == 8
+            && board.locations().iter().all(|location| {
+                board
+                    .field_at_location(location)
+                    .and_then(|field| field.get_content())
+                    .is_none_or(|(piece, _)| !matches!(piece.kind, PieceKind::Fairy(_)))
+            })
+    }
+
+    /// the accumulator computed from all pieces of a supported board
+    pub fn refresh(&self, board: &Board) -> Accumulator {
+        let mut accumulator = Accumulator {
+            values: [self.feature_bias.clone(), self.feature_bias.clone()],
+        };
+        for (square, piece) in Self::pieces(board) {
+            self.add_piece(&mut accumulator, piece, square);
+        }
+        accumulator
+    }
+
+    /// Sets the accumulator to the one of the parent position with the
+    /// pieces that differ between the boards before and after the step
+    /// swapped. Comparing the boards covers every kind of step, including
+    /// castling, drops and explosions.
+    pub fn update(
+        &self,
+        accumulator: &mut Accumulator,
+        parent: &Accumulator,
+        before: &Board,
+        after: &Board,
+    ) {
+        accumulator.clone_from(parent);
+        for location in before.locations() {
+            let square = location.y * 8 + location.x;
+            let piece = |board: &Board| {
+                board
+                    .field_at_location(&location)
+                    .and_then(|field| field.get_content())
+                    .map(|(piece, _)| piece)
+            };
+            let (old, new) = (piece(before), piece(after));
+            if old == new {
+                continue;
+            }
+            if let Some(old) = old {
+                self.remove_piece(accumulator, old, square);
+            }
+            if let Some(new) = new {
+                self.add_piece(accumulator, new, square);
+            }
+        }
+    }
+
+    /// the score in centipawns for the side to move
+    pub fn evaluate_accumulator(&self, accumulator: &Accumulator, color: PieceColor) -> i32 {
+        let (us, them) = match color {
+            PieceColor::White => (&accumulator.values[0], &accumulator.values[1]),
+            PieceColor::Black => (&accumulator.values[1], &accumulator.values[0]),
+        };
+        let (us_weights, them_weights) = self.output_weights.split_at(self.hidden);
+        let sum = simd::clipped_dot(us, us_weights, QA as i16)
+            + simd::clipped_dot(them, them_weights, QA as i16);
+        (sum / QA + self.output_bias as i32) * SCALE / (QA * QB)
+    }
+
+    fn add_piece(&self, accumulator: &mut Accumulator, piece: ColoredPiece, square: usize) {
+        for (side, values) in accumulator.values.iter_mut().enumerate() {
+            simd::add(values, self.weights(Self::feature(side, piece, square)));
+        }
+    }
+
+    fn remove_piece(&self, accumulator: &mut Accumulator, piece: ColoredPiece, square: usize) {
+        for (side, values) in accumulator.values.iter_mut().enumerate() {
+            simd::sub(values, self.weights(Self::feature(side, piece, square)));
+        }
+    }
+
+    fn weights(&self, feature: usize) -> &[i16] {
+        &self.feature_weights[feature * self.hidden..(feature + 1) * self.hidden]
+    }
+
+    /// The input of a piece as seen by a side, 0 for White and 1 for Black.
+    /// Black sees the board flipped, with its own pieces first like White.
+    fn feature(side: usize, piece: ColoredPiece, square: usize) -> usize {
+        let own = (piece.color == PieceColor::White) == (side == 0);
+        let square = if side == 0 { square } else { square ^ 56 };
+        let kind = match piece.kind {
+            PieceKind::Pawn => 0,
+            PieceKind::Knight => 1,
+            PieceKind::Bishop => 2,
+            PieceKind::Rook => 3,
+            PieceKind::Queen => 4,
+            PieceKind::King | PieceKind::Fairy(_) => 5,
+        };
+        (if own { 0 } else { 384 }) + kind * 64 + square
+    }
+
+    /// pieces by square index, a1 = 0 to h8 = 63
+    fn pieces(board: &Board) -> impl Iterator<Item = (usize, ColoredPiece)> + '_ {
+        board.locations().into_iter().filter_map(move |location| {
+            let (piece, _) = board.field_at_location(&location)?.get_content()?;
+            Some((location.y * 8 + location.x, piece))
+        })
+    }
+}
+
+impl Evaluator for Network {
+    /// computes the accumulator from scratch, the search updates it instead
+    fn evaluate(&self, board: &Board) -> i32 {
+        if !Self::supports(board) {
+            return MaterialEvaluator.evaluate(board);
+        }
+        self.evaluate_accumulator(&self.refresh(board), board.turn_color())
+    }
+}
+
+/// Vector operations of the network, using AVX2 where the CPU has it
+pub(crate) mod simd {
+    /// values handled at once, every layer is a multiple of it
+    pub const LANES: usize = 16;
+
+    pub fn add(values: &mut [i16], weights: &[i16]) {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: the CPU supports AVX2, the slices have the same length
+            // which is a multiple of the lanes
+            return unsafe { avx2::add(values, weights) };
+        }
+        scalar::add(values, weights)
+    }
+
+    pub fn sub(values: &mut [i16], weights: &[i16]) {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: as in `add`
+            return unsafe { avx2::sub(values, weights) };
+        }
+        scalar::sub(values, weights)
+    }
+
+    /// the dot product of the values clipped to 0..=max with the weights
+    pub fn clipped_dot(values: &[i16], weights: &[i16], max: i16) -> i32 {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            // SAFETY: as in `add`
+            return unsafe { avx2::clipped_dot(values, weights, max) };
+        }
+        scalar::clipped_dot(values, weights, max)
+    }
+
+    pub mod scalar {
+        pub fn add(values: &mut [i16], weights: &[i16]) {
+            for (value, weight) in values.iter_mut().zip(weights) {
+                *value = value.wrapping_add(*weight);
+            }
+        }
+
+        pub fn sub(values: &mut [i16], weights: &[i16]) {
+            for (value, weight) in values.iter_mut().zip(weights) {
+                *value = value.wrapping_sub(*weight);
+            }
+        }
+
+        pub fn clipped_dot(values: &[i16], weights: &[i16], max: i16) -> i32 {
+            values
+                .iter()
+                .zip(weights)
+                .map(|(value, weight)| (*value).clamp(0, max) as i32 * *weight as i32)
+                .fold(0, i32::wrapping_add)
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    mod avx2 {
+        use std::arch::x86_64::*;
+
+        use super::LANES;
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn add(values: &mut [i16], weights: &[i16]) {
+            debug_assert!(values.len() == weights.len() && values.len().is_multiple_of(LANES));
+            for index in (0..values.len()).step_by(LANES) {
+                let value = values.as_mut_ptr().add(index) as *mut __m256i;
+                let weight = weights.as_ptr().add(index) as *const __m256i;
+                let sum = _mm256_add_epi16(_mm256_loadu_si256(value), _mm256_loadu_si256(weight));
+                _mm256_storeu_si256(value, sum);
+            }
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn sub(values: &mut [i16], weights: &[i16]) {
+            debug_assert!(values.len() == weights.len() && values.len().is_multiple_of(LANES));
+            for index in (0..values.len()).step_by(LANES) {
+                let value = values.as_mut_ptr().add(index) as *mut __m256i;
+                let weight = weights.as_ptr().add(index) as *const __m256i;
+                let difference =
+                    _mm256_sub_epi16(_mm256_loadu_si256(value), _mm256_loadu_si256(weight));
+                _mm256_storeu_si256(value, difference);
+            }
+        }
+
+        #[target_feature(enable = "avx2")]
+        pub unsafe fn clipped_dot(values: &[i16], weights: &[i16], max: i16) -> i32 {
+            debug_assert!(values.len() == weights.len() && values.len().is_multiple_of(LANES));
+            let zero = _mm256_setzero_si256();
+            let max = _mm256_set1_epi16(max);
+            let mut sum = _mm256_setzero_si256();
+            for index in (0..values.len()).step_by(LANES) {
+                let value = _mm256_loadu_si256(values.as_ptr().add(index) as *const __m256i);
+                let weight = _mm256_loadu_si256(weights.as_ptr().add(index) as *const __m256i);
+                let clipped = _mm256_min_epi16(_mm256_max_epi16(value, zero), max);
+                // multiplies the lanes and adds neighbouring products as i32
+                sum = _mm256_add_epi32(sum, _mm256_madd_epi16(clipped, weight));
+            }
+            let mut lanes = [0i32; 8];
+            _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+            lanes
+                .iter()
+                .fold(0, |total, lane| total.wrapping_add(*lane))
+        }
+    }
+}
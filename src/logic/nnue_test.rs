@@ -0,0 +1,154 @@
+use std::error::Error;
+
+use super::{
+    evaluation::Evaluator,
+    move_computer::StepComputer,
+    nnue::{simd, Network, FEATURES},
+    search::{Search, SearchLimits, MATE_SCORE},
+};
+use crate::utils::fen::Fen;
+
+const KIWIPETE: &str = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+
+/// small values from a fixed seed, a network only has to be deterministic here
+fn values(seed: u64, count: usize, range: i16) -> Vec<i16> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % (2 * range as u64 + 1)) as i16 - range
+        })
+        .collect()
+}
+
+fn network(hidden: usize) -> Network {
+    Network::new(
+        values(1, FEATURES * hidden, 64),
+        values(2, hidden, 64),
+        values(3, 2 * hidden, 64),
+        17,
+    )
+    .expect("valid network")
+}
+
+#[test]
+fn encode_round_trip() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let network = network(32);
+    // action
+    let decoded = Network::decode(&network.encode())?;
+    // assert
+    assert_eq!(decoded, network);
+    assert_eq!(decoded.hidden_size(), 32);
+    Ok(())
+}
+
+#[test]
+fn rejects_invalid_files() {
+    // arrange
+    let bytes = network(16).encode();
+    // action
+    let truncated = Network::decode(&bytes[..bytes.len() - 2]);
+    let wrong_magic = Network::decode(b"NNUE\x01\x00\x00\x00\x10\x00\x00\x00");
+    let odd_size = Network::new(vec![0; FEATURES * 10], vec![0; 10], vec![0; 20], 0);
+    // assert
+    assert!(truncated.is_err());
+    assert!(wrong_magic.is_err());
+    assert!(odd_size.is_err());
+}
+
+#[test]
+fn incremental_updates_match_a_refresh() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let network = network(32);
+    let positions = [
+        KIWIPETE,
+        // en passant
+        "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        // promotions with and without capture
+        "1n5k/P7/8/8/8/8/8/4K3 w - - 0 1",
+    ];
+    for fen in positions {
+        let board = Fen::decode(fen)?;
+        let accumulator = network.refresh(&board);
+        for step in board.compute_steps() {
+            let mut next = board.clone();
+            next.apply_step_kind(&step)?;
+            let mut updated = accumulator.clone();
+            // action
+            network.update(&mut updated, &accumulator, &board, &next);
+            // assert
+            assert_eq!(
+                updated,
+                network.refresh(&next),
+                "{fen} {}",
+                step.encode_uci()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn mirrored_positions_evaluate_the_same() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let network = network(32);
+    let board = Fen::decode(KIWIPETE)?;
+    let mirrored =
+        Fen::decode("r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQkq - 0 1")?;
+    // action
+    let score = network.evaluate(&board);
+    let mirrored_score = network.evaluate(&mirrored);
+    // assert
+    assert_eq!(score, mirrored_score);
+    Ok(())
+}
+
+#[test]
+fn simd_matches_scalar() {
+    // arrange
+    let mut values = values(4, 64, 400);
+    let mut scalar_values = values.clone();
+    let weights = self::values(5, 64, 300);
+    // action
+    simd::add(&mut values, &weights);
+    simd::scalar::add(&mut scalar_values, &weights);
+    simd::sub(&mut values, &weights);
+    simd::sub(&mut values, &weights);
+    simd::scalar::sub(&mut scalar_values, &weights);
+    simd::scalar::sub(&mut scalar_values, &weights);
+    // assert
+    assert_eq!(values, scalar_values);
+    assert_eq!(
+        simd::clipped_dot(&values, &weights, 255),
+        simd::scalar::clipped_dot(&values, &weights, 255)
+    );
+}
+
+#[test]
+fn search_uses_the_network() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let network = network(32);
+    let quiet = Fen::decode("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")?;
+    let mate = Fen::decode("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")?;
+    let limits = SearchLimits {
+        depth: 2,
+        ..Default::default()
+    };
+    // action
+    let quiet_score = Search::new(&network, limits.clone())
+        .with_network(&network)
+        .quiescence(&quiet, -MATE_SCORE, MATE_SCORE);
+    let info = Search::new(&network, limits)
+        .with_network(&network)
+        .run(&mate, |_| {});
+    // assert
+    assert_eq!(quiet_score, network.evaluate(&quiet));
+    assert_eq!(
+        info.best_step().map(|step| step.encode_uci()),
+        Some("a1a8".to_string())
+    );
+    Ok(())
+}
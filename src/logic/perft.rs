@@ -0,0 +1,42 @@
+use crate::models::{board::Board, step::StepKind};
+
+use super::move_computer::StepComputer;
+
+impl Board {
+    /// counts the leaf nodes of the move tree up to the given depth. Comparing
+    /// with published numbers is the standard way to verify move generation.
+    pub fn perft(&self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let steps = self.compute_steps();
+        if depth == 1 {
+            return steps.len() as u64;
+        }
+        steps
+            .iter()
+            .map(|step| {
+                let mut next_board = self.clone();
+                next_board
+                    .apply_step_kind(step)
+                    .expect("computed steps must be applicable");
+                next_board.perft(depth - 1)
+            })
+            .sum()
+    }
+
+    /// perft split up by the first step, useful to find bugs
+    pub fn perft_divide(&self, depth: usize) -> Vec<(StepKind, u64)> {
+        self.compute_steps()
+            .into_iter()
+            .map(|step| {
+                let mut next_board = self.clone();
+                next_board
+                    .apply_step_kind(&step)
+                    .expect("computed steps must be applicable");
+                let nodes = next_board.perft(depth.saturating_sub(1));
+                (step, nodes)
+            })
+            .collect()
+    }
+}
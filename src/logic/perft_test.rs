@@ -0,0 +1,110 @@
+use std::error::Error;
+
+use crate::{models::board::Board, utils::fen::Fen};
+
+#[test]
+fn perft_start_position() {
+    // arrange
+    let board = Board::standard_board();
+    // action
+    let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+    // assert
+    assert_eq!(nodes, vec![20, 400, 8902]);
+}
+
+#[test]
+fn perft_kiwipete() -> Result<(), Box<dyn Error>> {
+    // arrange: castling, en passant and pins
+    let board =
+        Fen::decode("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")?;
+    // action
+    let nodes: Vec<u64> = (1..=2).map(|depth| board.perft(depth)).collect();
+    // assert
+    assert_eq!(nodes, vec![48, 2039]);
+    Ok(())
+}
+
+#[test]
+fn perft_en_passant_endgame() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1")?;
+    // action
+    let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+    // assert
+    assert_eq!(nodes, vec![14, 191, 2812]);
+    Ok(())
+}
+
+#[test]
+fn perft_promotions() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1")?;
+    // action
+    let nodes: Vec<u64> = (1..=2).map(|depth| board.perft(depth)).collect();
+    // assert
+    assert_eq!(nodes, vec![6, 264]);
+    Ok(())
+}
+
+#[test]
+fn perft_chess960() -> Result<(), Box<dyn Error>> {
+    // arrange: positions and numbers from the chess960 perft suite
+    let positions = [
+        (
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            [21, 528, 12189],
+        ),
+        (
+            "2nnrbkr/p1qppppp/8/1ppb4/6PP/3PP3/PPP2P2/BQNNRBKR w HEhe - 1 9",
+            [21, 807, 18002],
+        ),
+        (
+            "b1q1rrkb/pppppppp/3nn3/8/P7/1PPP4/4PPPP/BQNNRKRB w GE - 1 9",
+            [20, 479, 10471],
+        ),
+        (
+            "qbbnnrkr/2pp2pp/p7/1p2pp2/8/P3PP2/1PPP1KPP/QBBNNR1R w hf - 0 9",
+            [22, 593, 13440],
+        ),
+        (
+            "1nbbnrkr/p1p1ppp1/3p4/1p3P1p/3Pq2P/8/PPP1P1P1/QNBBNRKR w HFhf - 0 9",
+            [28, 1120, 31058],
+        ),
+        (
+            "qnbnr1kr/ppp1b1pp/4p3/3p1p2/8/2NPP3/PPP1BPPP/QNB1R1KR w HEhe - 1 9",
+            [29, 899, 26578],
+        ),
+    ];
+    for (fen, expected) in positions {
+        let board = Fen::decode(fen)?;
+        // action
+        let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+        // assert
+        assert_eq!(nodes, expected, "{fen}");
+    }
+    Ok(())
+}
+
+#[test]
+fn perft_chess960_start_positions() -> Result<(), Box<dyn Error>> {
+    for index in [0, 959] {
+        // arrange
+        let board = Board::chess960_board(index)?;
+        // action
+        let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+        // assert
+        assert_eq!(nodes, vec![20, 400, 9006]);
+    }
+    Ok(())
+}
+
+#[test]
+fn perft_chess960_inner_rooks() -> Result<(), Box<dyn Error>> {
+    // arrange: the castling rooks are not the outermost ones
+    let board = Fen::decode("r3k1rr/8/8/8/8/8/8/RR2K2R w KBgq - 0 1")?;
+    // action
+    let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+    // assert
+    assert_eq!(nodes, vec![31, 859, 26705]);
+    Ok(())
+}
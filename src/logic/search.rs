@@ -0,0 +1,553 @@
+use std::{
+    cmp::Reverse,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    models::{board::Board, field_content::FieldContent, game_state::GameState, step::StepKind},
+    utils::zobrist::Zobrist,
+};
+
+use super::{
+    evaluation::{piece_value, Evaluator},
+    move_computer::StepComputer,
+    nnue::{Accumulator, Network},
+    tablebase::{Tablebase, TABLEBASE_WIN_SCORE},
+    transposition::{Bound, TableEntry, TranspositionTable},
+};
+
+/// score of being mated right now, mates further away score a bit less
+pub const MATE_SCORE: i32 = 1_000_000;
+// scores beyond this are mates or tablebase wins, which depend on the ply
+const WIN_SCORE: i32 = TABLEBASE_WIN_SCORE - 1000;
+
+#[derive(Debug, Clone, Default)]
+pub struct SearchLimits {
+    pub depth: usize,
+    pub deadline: Option<Instant>,
+    /// set from outside to end the search early
+    pub stop: Option<Arc<AtomicBool>>,
+    /// Threads searching together, sharing what they find through the
+    /// transposition table. Without a table, or with 0 or 1 threads, the
+    /// search runs on the calling thread and is deterministic.
+    pub threads: usize,
+    /// how many of the best root steps get a principal variation, 0 and 1
+    /// both mean only the best one
+    pub multi_pv: usize,
+}
+
+/// One of the best lines of the search, a principal variation and its score
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PvLine {
+    /// in centipawns from the point of view of the side to move
+    pub score: i32,
+    pub pv: Vec<StepKind>,
+}
+
+impl PvLine {
+    /// number of moves until mate, negative if the side to move gets mated
+    pub fn mate_in(&self) -> Option<i32> {
+        mate_in(self.score)
+    }
+}
+
+/// Result of one iteration of the search
+#[derive(Debug, Clone, Default)]
+pub struct SearchInfo {
+    pub depth: usize,
+    /// in centipawns from the point of view of the side to move
+    pub score: i32,
+    pub nodes: u64,
+    /// positions whose score came from the endgame tablebases
+    pub tb_hits: u64,
+    /// permille of the transposition table used
+    pub hashfull: usize,
+    pub elapsed: Duration,
+    /// principal variation, the first step is the best one
+    pub pv: Vec<StepKind>,
+    /// the lines of the best root steps, best first, as many as `multi_pv`
+    /// asks for and the position has steps
+    pub lines: Vec<PvLine>,
+}
+
+impl SearchInfo {
+    pub fn best_step(&self) -> Option<&StepKind> {
+        self.pv.first()
+    }
+
+    /// number of moves until mate, negative if the side to move gets mated
+    pub fn mate_in(&self) -> Option<i32> {
+        mate_in(self.score)
+    }
+}
+
+// how many nodes a helper thread counts before publishing them
+const NODE_BATCH: u64 = 256;
+
+/// Iterative deepening alpha-beta search with a quiescence search on captures.
+/// With more threads it is a Lazy SMP search: helper threads search the same
+/// position, and the main thread profits from the results they leave in the
+/// transposition table.
+pub struct Search<'a> {
+    evaluator: &'a dyn Evaluator,
+    limits: SearchLimits,
+    tablebase: Option<&'a Tablebase>,
+    table: Option<&'a TranspositionTable>,
+    /// the steps the tablebase allows at the root, if it covers the position
+    root_steps: Option<Vec<StepKind>>,
+    /// root steps left out because they already lead an earlier line
+    excluded: Vec<StepKind>,
+    nodes: u64,
+    tb_hits: u64,
+    stopped: bool,
+    /// where a helper thread publishes its nodes
+    node_counter: Option<&'a AtomicU64>,
+    network: Option<&'a Network>,
+    /// the accumulators of the network along the searched line by ply, empty
+    /// if the network doesn't support the position
+    accumulators: Vec<Accumulator>,
+}
+
+impl<'a> Search<'a> {
+    pub fn new(evaluator: &'a dyn Evaluator, limits: SearchLimits) -> Self {
+        Self {
+            evaluator,
+            limits,
+            tablebase: None,
+            table: None,
+            root_steps: None,
+            excluded: vec![],
+            nodes: 0,
+            tb_hits: 0,
+            stopped: false,
+            node_counter: None,
+            network: None,
+            accumulators: vec![],
+        }
+    }
+
+    /// scores positions covered by the tablebase without searching them,
+    /// and only plays steps at the root that keep their result
+    pub fn with_tablebase(mut self, tablebase: &'a Tablebase) -> Self {
+        self.tablebase = Some(tablebase);
+        self
+    }
+
+    /// evaluates with the network, updating its accumulator step by step
+    /// along the searched line instead of computing it for every position
+    pub fn with_network(mut self, network: &'a Network) -> Self {
+        self.evaluator = network;
+        self.network = Some(network);
+        self
+    }
+
+    /// remembers results to skip positions that were already searched,
+    /// the table may be shared with other searches
+    pub fn with_transposition_table(mut self, table: &'a TranspositionTable) -> Self {
+        self.table = Some(table);
+        self
+    }
+
+    /// searches with increasing depth, reporting every completed iteration
+    pub fn run(&mut self, board: &Board, mut on_iteration: impl FnMut(&SearchInfo)) -> SearchInfo {
+        let start = Instant::now();
+        self.root_steps = self
+            .tablebase
+            .and_then(|tablebase| tablebase.root_steps(board));
+        self.refresh_accumulator(board);
+        let Some(table) = self.table else {
+            return self.iterate(board, 1, start, None, &mut on_iteration);
+        };
+        table.new_search();
+        if self.limits.threads <= 1 {
+            return self.iterate(board, 1, start, None, &mut on_iteration);
+        }
+
+        let done = Arc::new(AtomicBool::new(false));
+        let helper_nodes = AtomicU64::new(0);
+        let counter = &helper_nodes;
+        let mut best = thread::scope(|scope| {
+            for index in 1..self.limits.threads {
+                let mut helper = Search {
+                    evaluator: self.evaluator,
+                    limits: SearchLimits {
+                        depth: self.limits.depth,
+                        deadline: None,
+                        stop: Some(done.clone()),
+                        threads: 1,
+                        multi_pv: 1,
+                    },
+                    tablebase: self.tablebase,
+                    table: self.table,
+                    root_steps: self.root_steps.clone(),
+                    excluded: vec![],
+                    nodes: 0,
+                    tb_hits: 0,
+                    stopped: false,
+                    node_counter: Some(counter),
+                    network: self.network,
+                    accumulators: self.accumulators.clone(),
+                };
+                scope.spawn(move || {
+                    // every other helper is one depth ahead, so that the
+                    // threads don't all search the same tree
+                    helper.iterate(board, 1 + index % 2, start, None, &mut |_| {});
+                    counter.fetch_add(helper.nodes % NODE_BATCH, Ordering::Relaxed);
+                });
+            }
+            let best = self.iterate(board, 1, start, Some(counter), &mut on_iteration);
+            done.store(true, Ordering::Relaxed);
+            best
+        });
+        best.nodes = self.nodes + helper_nodes.load(Ordering::Relaxed);
+        best
+    }
+
+    /// The iterative deepening loop from the given depth on. Every depth
+    /// searches the root once per line, leaving out the steps of the lines
+    /// before. The nodes of the helper threads are included in what is
+    /// reported.
+    fn iterate(
+        &mut self,
+        board: &Board,
+        first_depth: usize,
+        start: Instant,
+        helper_nodes: Option<&AtomicU64>,
+        on_iteration: &mut impl FnMut(&SearchInfo),
+    ) -> SearchInfo {
+        let root_steps = match &self.root_steps {
+            Some(steps) => steps.len(),
+            None => board.compute_steps().len(),
+        };
+        let line_count = self.limits.multi_pv.min(root_steps).max(1);
+        let mut best = SearchInfo::default();
+        for depth in first_depth..=self.limits.depth.max(first_depth) {
+            let mut lines: Vec<PvLine> = vec![];
+            while lines.len() < line_count {
+                let previous = best
+                    .lines
+                    .get(lines.len())
+                    .map_or(&[][..], |line| line.pv.as_slice());
+                let mut pv = vec![];
+                let score =
+                    self.negamax(board, depth, 0, -MATE_SCORE, MATE_SCORE, previous, &mut pv);
+                // a partial search can't be trusted, unless there is nothing better
+                if self.stopped && !(lines.is_empty() && best.pv.is_empty()) {
+                    break;
+                }
+                self.excluded.extend(pv.first().cloned());
+                lines.push(PvLine { score, pv });
+                if self.stopped {
+                    break;
+                }
+            }
+            self.excluded.clear();
+            if self.stopped && !best.pv.is_empty() {
+                break;
+            }
+            lines.sort_by_key(|line| Reverse(line.score));
+            best = SearchInfo {
+                depth,
+                score: lines[0].score,
+                nodes: self.nodes + helper_nodes.map_or(0, |nodes| nodes.load(Ordering::Relaxed)),
+                tb_hits: self.tb_hits,
+                hashfull: self.table.map_or(0, |table| table.hashfull()),
+                elapsed: start.elapsed(),
+                pv: lines[0].pv.clone(),
+                lines,
+            };
+            on_iteration(&best);
+            let all_mates = best.lines.iter().all(|line| line.mate_in().is_some());
+            if self.stopped || all_mates {
+                break;
+            }
+        }
+        best
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        board: &Board,
+        depth: usize,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+        previous_pv: &[StepKind],
+        pv: &mut Vec<StepKind>,
+    ) -> i32 {
+        self.count_node();
+        if self.should_stop() {
+            return 0;
+        }
+        let key = self.table.map(|_| Zobrist::hash(board));
+        let entry = self
+            .table
+            .zip(key)
+            .and_then(|(table, key)| table.probe(key));
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth as usize >= depth) {
+            let score = score_from_table(entry.score, ply);
+            let cutoff = match entry.bound {
+                Bound::Exact => true,
+                Bound::Lower => score >= beta,
+                Bound::Upper => score <= alpha,
+            };
+            if cutoff {
+                return score;
+            }
+        }
+        let all_steps = board.compute_steps();
+        let mut steps = all_steps.clone();
+        if steps.is_empty() {
+            return match board.game_state() {
+                GameState::Won(color) if color == board.turn_color() => MATE_SCORE - ply as i32,
+                GameState::Won(_) => -MATE_SCORE + ply as i32,
+                _ => 0,
+            };
+        }
+        if ply == 0 {
+            if let Some(root_steps) = &self.root_steps {
+                steps.retain(|step| root_steps.contains(step));
+            }
+            steps.retain(|step| !self.excluded.contains(step));
+        } else if let Some(wdl) = self
+            .tablebase
+            .and_then(|tablebase| tablebase.probe_wdl(board))
+        {
+            self.tb_hits += 1;
+            return wdl.score(ply);
+        }
+        if depth == 0 {
+            return self.quiescence_at(board, ply, alpha, beta);
+        }
+
+        let table_step = entry
+            .and_then(|entry| entry.step_index)
+            .and_then(|index| all_steps.get(index as usize));
+        self.order_steps(board, &mut steps, previous_pv.first().or(table_step));
+        let original_alpha = alpha;
+        for step in steps {
+            let mut next_board = board.clone();
+            next_board
+                .apply_step_kind(&step)
+                .expect("computed steps must be applicable");
+            self.update_accumulator(ply, board, &next_board);
+            let mut child_pv = vec![];
+            let child_previous = match previous_pv.split_first() {
+                Some((first, rest)) if *first == step => rest,
+                _ => &[],
+            };
+            let score = -self.negamax(
+                &next_board,
+                depth - 1,
+                ply + 1,
+                -beta,
+                -alpha,
+                child_previous,
+                &mut child_pv,
+            );
+            if self.stopped {
+                return 0;
+            }
+            if score > alpha {
+                alpha = score;
+                pv.clear();
+                pv.push(step);
+                pv.append(&mut child_pv);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        // without all root steps, the score is not the one of the position
+        let complete = ply > 0 || self.excluded.is_empty();
+        if let Some((table, key)) = self.table.zip(key).filter(|_| complete) {
+            let bound = if alpha >= beta {
+                Bound::Lower
+            } else if alpha > original_alpha {
+                Bound::Exact
+            } else {
+                Bound::Upper
+            };
+            let step_index = pv
+                .first()
+                .filter(|_| bound != Bound::Upper)
+                .and_then(|best| all_steps.iter().position(|step| step == best))
+                .map(|index| index as u16);
+            table.store(
+                key,
+                TableEntry {
+                    depth: depth.min(u8::MAX as usize) as u8,
+                    bound,
+                    score: score_to_table(alpha, ply),
+                    step_index,
+                },
+            );
+        }
+        alpha
+    }
+
+    /// only looks at captures and promotions, so that the evaluation is not
+    /// done in the middle of an exchange
+    pub fn quiescence(&mut self, board: &Board, alpha: i32, beta: i32) -> i32 {
+        self.refresh_accumulator(board);
+        self.quiescence_at(board, 0, alpha, beta)
+    }
+
+    fn quiescence_at(&mut self, board: &Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.count_node();
+        let stand_pat = self.evaluate(board, ply);
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut steps: Vec<StepKind> = board
+            .compute_steps()
+            .into_iter()
+            .filter(|step| is_tactical(board, step))
+            .collect();
+        self.order_steps(board, &mut steps, None);
+        for step in steps {
+            let mut next_board = board.clone();
+            next_board
+                .apply_step_kind(&step)
+                .expect("computed steps must be applicable");
+            self.update_accumulator(ply, board, &next_board);
+            let score = -self.quiescence_at(&next_board, ply + 1, -beta, -alpha);
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// the best step of the last iteration first, then captures of valuable pieces
+    fn order_steps(&self, board: &Board, steps: &mut [StepKind], best: Option<&StepKind>) {
+        steps.sort_by_cached_key(|step| {
+            if Some(step) == best {
+                i32::MIN
+            } else {
+                -captured_value(board, step)
+            }
+        });
+    }
+
+    fn evaluate(&self, board: &Board, ply: usize) -> i32 {
+        match (self.network, self.accumulators.get(ply)) {
+            (Some(network), Some(accumulator)) => {
+                network.evaluate_accumulator(accumulator, board.turn_color())
+            }
+            _ => self.evaluator.evaluate(board),
+        }
+    }
+
+    fn refresh_accumulator(&mut self, board: &Board) {
+        self.accumulators.clear();
+        if let Some(network) = self.network.filter(|_| Network::supports(board)) {
+            self.accumulators.push(network.refresh(board));
+        }
+    }
+
+    /// the accumulator of the position after a step from the one at the ply
+    fn update_accumulator(&mut self, ply: usize, board: &Board, next_board: &Board) {
+        let Some(network) = self.network.filter(|_| !self.accumulators.is_empty()) else {
+            return;
+        };
+        if self.accumulators.len() == ply + 1 {
+            self.accumulators.push(self.accumulators[ply].clone());
+        }
+        let (parents, children) = self.accumulators.split_at_mut(ply + 1);
+        network.update(&mut children[0], &parents[ply], board, next_board);
+    }
+
+    fn count_node(&mut self) {
+        self.nodes += 1;
+        if let Some(counter) = self.node_counter {
+            if self.nodes.is_multiple_of(NODE_BATCH) {
+                counter.fetch_add(NODE_BATCH, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn should_stop(&mut self) -> bool {
+        // checking the clock is comparably expensive, only do it now and then
+        if !self.stopped && self.nodes.is_multiple_of(256) {
+            let timeout = self
+                .limits
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline);
+            let stopped = self
+                .limits
+                .stop
+                .as_ref()
+                .is_some_and(|stop| stop.load(Ordering::Relaxed));
+            self.stopped = timeout || stopped;
+        }
+        self.stopped
+    }
+}
+
+/// number of moves until mate, negative if the side to move gets mated
+fn mate_in(score: i32) -> Option<i32> {
+    let plies = MATE_SCORE - score.abs();
+    (plies < 1000).then(|| {
+        let moves = (plies + 1) / 2;
+        if score > 0 {
+            moves
+        } else {
+            -moves
+        }
+    })
+}
+
+/// mate scores count from the root, in the table they count from the position
+fn score_to_table(score: i32, ply: usize) -> i32 {
+    if score > WIN_SCORE {
+        score + ply as i32
+    } else if score < -WIN_SCORE {
+        score - ply as i32
+    } else {
+        score
+    }
+}
+
+fn score_from_table(score: i32, ply: usize) -> i32 {
+    if score > WIN_SCORE {
+        score - ply as i32
+    } else if score < -WIN_SCORE {
+        score + ply as i32
+    } else {
+        score
+    }
+}
+
+fn captured_value(board: &Board, step: &StepKind) -> i32 {
+    let promotion = match step {
+        StepKind::Promote { piece, .. } => piece_value(board, *piece),
+        _ => 0,
+    };
+    let captured = step
+        .target()
+        .and_then(|target| board.field_at_location(&target))
+        .and_then(FieldContent::get_content)
+        .map_or(0, |(piece, _)| piece_value(board, piece.kind));
+    promotion + captured
+}
+
+fn is_tactical(board: &Board, step: &StepKind) -> bool {
+    matches!(step, StepKind::Promote { .. })
+        || step.target().is_some_and(|target| {
+            !board.location_is_emtpy(&target) || board.en_passant() == Some(target)
+        })
+}
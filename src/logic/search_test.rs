@@ -0,0 +1,118 @@
+use std::error::Error;
+
+use crate::{
+    logic::{
+        evaluation::MaterialEvaluator,
+        search::{Search, SearchLimits},
+        transposition::TranspositionTable,
+    },
+    utils::{fen::Fen, zobrist::Zobrist},
+};
+
+#[test]
+fn finds_mate_in_one() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")?;
+    let limits = SearchLimits {
+        depth: 2,
+        ..Default::default()
+    };
+    // action
+    let info = Search::new(&MaterialEvaluator, limits).run(&board, |_| {});
+    // assert
+    assert_eq!(
+        info.best_step().map(|step| step.encode_uci()),
+        Some("a1a8".to_string())
+    );
+    assert_eq!(info.mate_in(), Some(1));
+    Ok(())
+}
+
+#[test]
+fn wins_hanging_queen() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1")?;
+    let limits = SearchLimits {
+        depth: 1,
+        ..Default::default()
+    };
+    // action
+    let info = Search::new(&MaterialEvaluator, limits).run(&board, |_| {});
+    // assert
+    assert_eq!(
+        info.best_step().map(|step| step.encode_uci()),
+        Some("d2d5".to_string())
+    );
+    assert!(info.score >= 500);
+    Ok(())
+}
+
+#[test]
+fn single_thread_search_is_deterministic() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("r1bqkbnr/pppp1ppp/2n5/4p3/2B1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 3 3")?;
+    let search = || {
+        let limits = SearchLimits {
+            depth: 3,
+            threads: 1,
+            ..Default::default()
+        };
+        let table = TranspositionTable::new(1);
+        Search::new(&MaterialEvaluator, limits)
+            .with_transposition_table(&table)
+            .run(&board, |_| {})
+    };
+    // action
+    let first = search();
+    let second = search();
+    // assert
+    assert_eq!(first.nodes, second.nodes);
+    assert_eq!(first.score, second.score);
+    assert_eq!(first.pv, second.pv);
+    Ok(())
+}
+
+#[test]
+fn threads_share_the_table() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")?;
+    let limits = SearchLimits {
+        depth: 2,
+        threads: 4,
+        ..Default::default()
+    };
+    let table = TranspositionTable::new(1);
+    // action
+    let info = Search::new(&MaterialEvaluator, limits)
+        .with_transposition_table(&table)
+        .run(&board, |_| {});
+    // assert
+    assert_eq!(
+        info.best_step().map(|step| step.encode_uci()),
+        Some("a1a8".to_string())
+    );
+    assert_eq!(info.mate_in(), Some(1));
+    assert!(table.probe(Zobrist::hash(&board)).is_some());
+    Ok(())
+}
+
+#[test]
+fn multi_pv_keeps_the_best_line() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1")?;
+    let limits = |multi_pv| SearchLimits {
+        depth: 2,
+        multi_pv,
+        ..Default::default()
+    };
+    // action
+    let single = Search::new(&MaterialEvaluator, limits(1)).run(&board, |_| {});
+    let multi = Search::new(&MaterialEvaluator, limits(4)).run(&board, |_| {});
+    // assert
+    assert_eq!(single.lines.len(), 1);
+    assert_eq!(multi.lines.len(), 4);
+    assert_eq!(multi.best_step(), single.best_step());
+    assert_eq!(multi.mate_in(), Some(1));
+    assert!(multi.lines[1..].iter().all(|line| line.mate_in().is_none()));
+    Ok(())
+}
@@ -0,0 +1,177 @@
+use std::{fmt, path::Path};
+
+use shakmaty::{fen::Fen as ShakmatyFen, CastlingMode, Chess};
+use shakmaty_syzygy::{Tablebase as SyzygyTables, Wdl as SyzygyWdl};
+
+use crate::{
+    models::{
+        board::Board, error::FileError, game_state::GameState, piece::PieceColor, step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::fen::Fen,
+};
+
+use super::{move_computer::StepComputer, search::MATE_SCORE};
+
+/// score of a won tablebase position, below every mate the search can find
+pub const TABLEBASE_WIN_SCORE: i32 = MATE_SCORE - 2000;
+
+/// The outcome of a tablebase position for the side to move. A cursed win
+/// can't be forced before the fifty-move rule ends the game in a draw, a
+/// blessed loss is saved by it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Wdl {
+    Loss,
+    BlessedLoss,
+    Draw,
+    CursedWin,
+    Win,
+}
+
+impl Wdl {
+    /// the search score, wins reached later score a bit less
+    pub fn score(&self, ply: usize) -> i32 {
+        match self {
+            Wdl::Win => TABLEBASE_WIN_SCORE - ply as i32,
+            Wdl::Loss => -TABLEBASE_WIN_SCORE + ply as i32,
+            Wdl::CursedWin | Wdl::Draw | Wdl::BlessedLoss => 0,
+        }
+    }
+
+    pub fn invert(&self) -> Wdl {
+        match self {
+            Wdl::Loss => Wdl::Win,
+            Wdl::BlessedLoss => Wdl::CursedWin,
+            Wdl::Draw => Wdl::Draw,
+            Wdl::CursedWin => Wdl::BlessedLoss,
+            Wdl::Win => Wdl::Loss,
+        }
+    }
+
+    fn from_syzygy(wdl: SyzygyWdl) -> Wdl {
+        match wdl {
+            SyzygyWdl::Loss => Wdl::Loss,
+            SyzygyWdl::BlessedLoss => Wdl::BlessedLoss,
+            SyzygyWdl::Draw => Wdl::Draw,
+            SyzygyWdl::CursedWin => Wdl::CursedWin,
+            SyzygyWdl::Win => Wdl::Win,
+        }
+    }
+}
+
+/// Syzygy endgame tablebases read from a directory of `.rtbw` (win, draw
+/// or loss) and `.rtbz` (distance to zeroing the fifty-move counter) files.
+/// Only standard chess positions are probed.
+pub struct Tablebase {
+    tables: SyzygyTables<Chess>,
+}
+
+impl fmt::Debug for Tablebase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tablebase")
+            .field("max_pieces", &self.max_pieces())
+            .finish()
+    }
+}
+
+impl Tablebase {
+    /// adds all tables of the directory, which may also hold other files
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, FileError> {
+        let path = path.as_ref();
+        let mut tables = SyzygyTables::new();
+        tables
+            .add_directory(path)
+            .map_err(|e| FileError::new(path, e))?;
+        Ok(Self { tables })
+    }
+
+    /// the most pieces, kings included, of any table found
+    pub fn max_pieces(&self) -> usize {
+        self.tables.max_pieces()
+    }
+
+    /// Win, draw or loss for the side to move, taking the halfmove clock of
+    /// the board into account when distance tables are available. None if
+    /// the position is not covered.
+    pub fn probe_wdl(&self, board: &Board) -> Option<Wdl> {
+        let position = self.position(board)?;
+        let wdl = match self.tables.probe_wdl(&position) {
+            // a win that might be cursed by rounding or the halfmove clock
+            // counts as a win
+            Ok(wdl) => wdl.after_zeroing(),
+            Err(_) => self.tables.probe_wdl_after_zeroing(&position).ok()?,
+        };
+        Some(Wdl::from_syzygy(wdl))
+    }
+
+    /// plies until a capture or pawn move that keeps the result, negative if
+    /// the side to move is losing. Might be off by one ply in some tables.
+    /// The halfmove clock of the board is not included.
+    pub fn probe_dtz(&self, board: &Board) -> Option<i32> {
+        let position = self.position(board)?;
+        let dtz = self.tables.probe_dtz(&position).ok()?;
+        Some(dtz.ignore_rounding().0)
+    }
+
+    /// The legal steps that keep the best result, the ones converting a win
+    /// or dragging out a loss fastest first. Used to keep the search from
+    /// spoiling a tablebase result at the root.
+    pub fn root_steps(&self, board: &Board) -> Option<Vec<StepKind>> {
+        self.position(board)?;
+        let mut rated = vec![];
+        for step in board.compute_steps() {
+            let mut next = board.clone();
+            next.apply_step_kind(&step).ok()?;
+            let wdl = self.probe_wdl(&next)?.invert();
+            // the opponent's distance, small when a win is converted fast
+            let dtz = self.probe_dtz(&next).map_or(0, |dtz| dtz.abs());
+            rated.push((step, wdl, dtz));
+        }
+        let best = rated.iter().map(|(_, wdl, _)| *wdl).max()?;
+        rated.retain(|(_, wdl, _)| *wdl == best);
+        rated.sort_by_key(|(_, wdl, dtz)| if *wdl > Wdl::Draw { *dtz } else { -dtz });
+        Some(rated.into_iter().map(|(step, _, _)| step).collect())
+    }
+
+    /// The result of the game with best play from here on. Cursed wins and
+    /// blessed losses are draws by the fifty-move rule.
+    pub fn adjudicate(&self, board: &Board) -> Option<GameState> {
+        let color = board.turn_color();
+        Some(match self.probe_wdl(board)? {
+            Wdl::Win => GameState::TablebaseWin(color),
+            Wdl::Loss => GameState::TablebaseWin(color.invert()),
+            _ => GameState::TablebaseDraw,
+        })
+    }
+
+    /// the position in the form the tables are probed with, if it is covered
+    fn position(&self, board: &Board) -> Option<Chess> {
+        if board.variant() != VariantKind::Standard || board.width != 8 || board.height != 8 {
+            return None;
+        }
+        let pieces = board
+            .locations()
+            .iter()
+            .filter(|location| !board.location_is_emtpy(location))
+            .count();
+        // two kings are always a draw, even without any table
+        if pieces > self.max_pieces().max(2) {
+            return None;
+        }
+        let has_castling = [PieceColor::White, PieceColor::Black]
+            .iter()
+            .any(|color| !board.castling_rights(*color).is_empty());
+        if has_castling {
+            return None;
+        }
+        let mode = if board.is_chess960() {
+            CastlingMode::Chess960
+        } else {
+            CastlingMode::Standard
+        };
+        ShakmatyFen::from_ascii(Fen::encode(board).as_bytes())
+            .ok()?
+            .into_position(mode)
+            .ok()
+    }
+}
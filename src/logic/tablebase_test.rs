@@ -0,0 +1,150 @@
+use super::{
+    evaluation::MaterialEvaluator,
+    search::{Search, SearchLimits},
+    tablebase::{Tablebase, Wdl, TABLEBASE_WIN_SCORE},
+};
+use crate::{
+    driver::game::Game,
+    models::{board::Board, game_state::GameState, piece::PieceColor},
+    utils::fen::Fen,
+};
+use std::{error::Error, sync::Arc};
+
+const FIXTURES: &str = "data/syzygy";
+
+/// the tables listed in data/syzygy/SOURCE.txt, an error while they are missing
+fn fixtures() -> Result<Tablebase, Box<dyn Error>> {
+    let tablebase = Tablebase::open(FIXTURES)?;
+    if tablebase.max_pieces() < 3 {
+        return Err(format!("the tables of {FIXTURES}/SOURCE.txt are missing").into());
+    }
+    Ok(tablebase)
+}
+
+#[test]
+fn bare_kings_are_a_draw_without_tables() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let tablebase = Tablebase::open(FIXTURES)?;
+    let board = Fen::decode("8/8/4k3/8/8/3K4/8/8 w - - 0 1")?;
+    // action
+    let wdl = tablebase.probe_wdl(&board);
+    // assert
+    assert_eq!(wdl, Some(Wdl::Draw));
+    Ok(())
+}
+
+#[test]
+fn uncovered_positions_are_not_probed() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let tablebase = Tablebase::open(FIXTURES)?;
+    // action
+    let start = tablebase.probe_wdl(&Board::standard_board());
+    // assert
+    assert_eq!(start, None);
+    assert!(Tablebase::open("data/missing").is_err());
+    Ok(())
+}
+
+#[test]
+fn bare_kings_are_a_draw_before_the_tablebase_is_asked() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/3q4/4K3 w - - 0 1")?);
+    game.tablebase = Some(Arc::new(Tablebase::open(FIXTURES)?));
+    // action
+    let state = game.apply_input("Kxd2")?;
+    // assert
+    assert_eq!(state, GameState::InsufficientMaterial);
+    assert_eq!(state.result(), "1/2-1/2");
+    Ok(())
+}
+
+#[test]
+fn probes_wins_and_draws() -> Result<(), Box<dyn Error>> {
+    let tablebase = fixtures()?;
+    for (fen, expected) in [
+        ("4k3/8/8/8/8/8/8/3QK3 w - - 0 1", Wdl::Win),
+        ("4k3/8/8/8/8/8/8/3QK3 b - - 0 1", Wdl::Loss),
+        ("4k3/8/8/8/8/8/8/3NK3 w - - 0 1", Wdl::Draw),
+        ("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", Wdl::Win),
+    ] {
+        // arrange
+        let board = Fen::decode(fen)?;
+        // action
+        let wdl = tablebase.probe_wdl(&board);
+        // assert
+        assert_eq!(wdl, Some(expected), "{fen}");
+    }
+    Ok(())
+}
+
+#[test]
+fn search_keeps_the_tablebase_win() -> Result<(), Box<dyn Error>> {
+    let tablebase = fixtures()?;
+    // arrange
+    // every queen move wins, except Qc7 which stalemates
+    let board = Fen::decode("k7/8/1K6/8/8/8/8/2Q5 w - - 0 1")?;
+    let limits = SearchLimits {
+        depth: 1,
+        ..Default::default()
+    };
+    // action
+    let root_steps = tablebase.root_steps(&board).ok_or("not covered")?;
+    let info = Search::new(&MaterialEvaluator, limits)
+        .with_tablebase(&tablebase)
+        .run(&board, |_| {});
+    // assert
+    assert!(!root_steps.iter().any(|step| step.encode_uci() == "c1c7"));
+    assert!(root_steps.contains(info.best_step().ok_or("no step")?));
+    assert!(info.score > TABLEBASE_WIN_SCORE - 10);
+    assert!(info.tb_hits > 0);
+    Ok(())
+}
+
+#[test]
+fn game_is_adjudicated_as_a_win() -> Result<(), Box<dyn Error>> {
+    let tablebase = fixtures()?;
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/3r4/3QK3 w - - 0 1")?);
+    game.tablebase = Some(Arc::new(tablebase));
+    // action
+    let state = game.apply_input("Qxd2")?;
+    // assert
+    assert_eq!(state, GameState::TablebaseWin(PieceColor::White));
+    assert_eq!(state.result(), "1-0");
+    Ok(())
+}
+
+#[test]
+fn game_is_adjudicated_as_a_draw() -> Result<(), Box<dyn Error>> {
+    let tablebase = fixtures()?;
+    // arrange
+    let mut game = Game::from_board(Fen::decode("2k5/8/8/8/8/8/8/4K2Q w - - 0 1")?);
+    game.tablebase = Some(Arc::new(tablebase));
+    // action
+    // the king takes the queen
+    let state = game.apply_input("Qb7+")?;
+    // assert
+    assert_eq!(state, GameState::TablebaseDraw);
+    assert_eq!(game.state, GameState::TablebaseDraw);
+    Ok(())
+}
+
+#[test]
+fn halfmove_clock_curses_the_win() -> Result<(), Box<dyn Error>> {
+    let tablebase = fixtures()?;
+    // arrange
+    let fresh = Fen::decode("4k3/8/8/8/8/8/8/3QK3 w - - 0 60")?;
+    let late = Fen::decode("4k3/8/8/8/8/8/8/3QK3 w - - 99 60")?;
+    // action
+    let fresh_state = tablebase.adjudicate(&fresh);
+    let late_wdl = tablebase.probe_wdl(&late);
+    let late_state = tablebase.adjudicate(&late);
+    // assert
+    assert_eq!(
+        fresh_state,
+        Some(GameState::TablebaseWin(PieceColor::White))
+    );
+    assert_eq!(late_wdl, Some(Wdl::CursedWin));
+    assert_eq!(late_state, Some(GameState::TablebaseDraw));
+    Ok(())
+}
@@ -0,0 +1,202 @@
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+/// size used when nothing else is configured, the default of the UCI `Hash` option
+pub const DEFAULT_HASH_MB: usize = 16;
+pub const MAX_HASH_MB: usize = 65536;
+
+// entries sharing an index, one cache line together
+const BUCKET_SIZE: usize = 4;
+const NO_STEP: u16 = u16::MAX;
+
+/// How the stored score relates to the real one: a cutoff only proves a
+/// lower bound, a search where no step raised alpha only an upper one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+/// What the search learned about a position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TableEntry {
+    pub depth: u8,
+    pub bound: Bound,
+    pub score: i32,
+    /// index of the best step in `compute_steps` of the position
+    pub step_index: Option<u16>,
+}
+
+impl TableEntry {
+    fn pack(&self, generation: u8) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 0,
+            Bound::Lower => 1,
+            Bound::Upper => 2,
+        };
+        (self.score as u32 as u64)
+            | (self.step_index.unwrap_or(NO_STEP) as u64) << 32
+            | (self.depth as u64) << 48
+            | bound << 56
+            | ((generation & 0x3f) as u64) << 58
+    }
+
+    fn unpack(data: u64) -> (TableEntry, u8) {
+        let step_index = (data >> 32) as u16;
+        let bound = match (data >> 56) & 3 {
+            0 => Bound::Exact,
+            1 => Bound::Lower,
+            _ => Bound::Upper,
+        };
+        let entry = TableEntry {
+            depth: (data >> 48) as u8,
+            bound,
+            score: data as u32 as i32,
+            step_index: (step_index != NO_STEP).then_some(step_index),
+        };
+        (entry, (data >> 58) as u8)
+    }
+}
+
+/// One slot of the table. The key is stored xored with the data, so that
+/// an entry torn by two threads writing at once doesn't match any position
+/// and is ignored instead of being trusted.
+#[derive(Debug, Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+impl Slot {
+    /// the data if the slot holds the position, or the data of whatever
+    /// it holds instead
+    fn load(&self, key: u64) -> (Option<u64>, u64) {
+        let data = self.data.load(Ordering::Relaxed);
+        let stored = self.key.load(Ordering::Relaxed);
+        let empty = stored == 0 && data == 0;
+        let matches = !empty && stored ^ data == key;
+        (matches.then_some(data), data)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.key.load(Ordering::Relaxed) == 0 && self.data.load(Ordering::Relaxed) == 0
+    }
+}
+
+#[derive(Debug, Default)]
+#[repr(align(64))]
+struct Bucket {
+    slots: [Slot; BUCKET_SIZE],
+}
+
+/// Fixed size cache of search results keyed by `Zobrist::hash`. It can be
+/// shared between search threads without locking.
+#[derive(Debug)]
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    /// counts the searches, entries of older ones are replaced first
+    generation: AtomicU8,
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_HASH_MB)
+    }
+}
+
+impl TranspositionTable {
+    /// a table using about the given number of megabytes
+    pub fn new(megabytes: usize) -> Self {
+        let bytes = megabytes.clamp(1, MAX_HASH_MB) * 1024 * 1024;
+        let count = (bytes / size_of::<Bucket>()).max(1);
+        Self {
+            buckets: (0..count).map(|_| Bucket::default()).collect(),
+            generation: AtomicU8::new(0),
+        }
+    }
+
+    pub fn size_mb(&self) -> usize {
+        self.buckets.len() * size_of::<Bucket>() / (1024 * 1024)
+    }
+
+    pub fn clear(&self) {
+        for slot in self.buckets.iter().flat_map(|bucket| &bucket.slots) {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.generation.store(0, Ordering::Relaxed);
+    }
+
+    /// called before every search, so that its entries are preferred
+    pub fn new_search(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn probe(&self, key: u64) -> Option<TableEntry> {
+        self.bucket(key)
+            .slots
+            .iter()
+            .find_map(|slot| slot.load(key).0)
+            .map(|data| TableEntry::unpack(data).0)
+    }
+
+    /// Replaces the entry of the same position, or else the one worth the
+    /// least: entries of earlier searches go first, then shallow ones. A
+    /// shallower result only replaces one of the same position if it is
+    /// exact or the old one is from an earlier search.
+    pub fn store(&self, key: u64, entry: TableEntry) {
+        let generation = self.generation.load(Ordering::Relaxed) & 0x3f;
+        let bucket = self.bucket(key);
+        let mut victim = &bucket.slots[0];
+        let mut victim_worth = i32::MAX;
+        for slot in &bucket.slots {
+            let (same, data) = slot.load(key);
+            let (old, old_generation) = TableEntry::unpack(data);
+            if same.is_some() {
+                let keep = old_generation == generation
+                    && old.depth > entry.depth
+                    && entry.bound != Bound::Exact;
+                if keep {
+                    return;
+                }
+                victim = slot;
+                break;
+            }
+            let worth = if slot.is_empty() {
+                i32::MIN
+            } else {
+                let age = generation.wrapping_sub(old_generation) & 0x3f;
+                old.depth as i32 - 8 * age as i32
+            };
+            if worth < victim_worth {
+                victim = slot;
+                victim_worth = worth;
+            }
+        }
+        let data = entry.pack(generation);
+        victim.key.store(key ^ data, Ordering::Relaxed);
+        victim.data.store(data, Ordering::Relaxed);
+    }
+
+    /// how full the table is in permille, estimated from its start like the
+    /// UCI `hashfull` info expects
+    pub fn hashfull(&self) -> usize {
+        let generation = self.generation.load(Ordering::Relaxed) & 0x3f;
+        let sample = self.buckets.len().min(1000 / BUCKET_SIZE);
+        let used = self.buckets[..sample]
+            .iter()
+            .flat_map(|bucket| &bucket.slots)
+            .filter(|slot| {
+                !slot.is_empty()
+                    && TableEntry::unpack(slot.data.load(Ordering::Relaxed)).1 == generation
+            })
+            .count();
+        used * 1000 / (sample * BUCKET_SIZE)
+    }
+
+    fn bucket(&self, key: u64) -> &Bucket {
+        // the high bits of the product spread the keys evenly over the buckets
+        let index = ((key as u128 * self.buckets.len() as u128) >> 64) as usize;
+        &self.buckets[index]
+    }
+}
@@ -0,0 +1,119 @@
+use std::{error::Error, thread};
+
+use super::{
+    evaluation::MaterialEvaluator,
+    search::{Search, SearchLimits},
+    transposition::{Bound, TableEntry, TranspositionTable},
+};
+use crate::utils::fen::Fen;
+
+fn entry(depth: u8, score: i32) -> TableEntry {
+    TableEntry {
+        depth,
+        bound: Bound::Lower,
+        score,
+        step_index: Some(3),
+    }
+}
+
+#[test]
+fn stores_and_probes() {
+    // arrange
+    let table = TranspositionTable::new(1);
+    let stored = TableEntry {
+        depth: 7,
+        bound: Bound::Upper,
+        score: -999_990,
+        step_index: None,
+    };
+    // action
+    table.store(42, stored);
+    // assert
+    assert_eq!(table.probe(42), Some(stored));
+    assert_eq!(table.probe(43), None);
+    assert_eq!(table.size_mb(), 1);
+}
+
+#[test]
+fn deeper_results_of_the_same_search_are_kept() {
+    // arrange
+    let table = TranspositionTable::new(1);
+    table.store(42, entry(6, 10));
+    // action
+    table.store(42, entry(2, 20));
+    // assert
+    assert_eq!(table.probe(42), Some(entry(6, 10)));
+    // a later search may overwrite it
+    table.new_search();
+    table.store(42, entry(2, 20));
+    assert_eq!(table.probe(42), Some(entry(2, 20)));
+}
+
+#[test]
+fn full_buckets_replace_the_shallowest_entry() {
+    // arrange
+    // keys this small all fall into the first bucket and compete for its
+    // four slots
+    let table = TranspositionTable::new(1);
+    let keys: Vec<u64> = (1..=4).collect();
+    for key in &keys {
+        table.store(*key, entry(*key as u8 + 4, 0));
+    }
+    // action
+    table.store(100, entry(9, 0));
+    // assert
+    assert_eq!(table.probe(1), None);
+    assert!(keys[1..].iter().all(|key| table.probe(*key).is_some()));
+    assert_eq!(table.probe(100), Some(entry(9, 0)));
+    table.clear();
+    assert_eq!(table.probe(100), None);
+}
+
+#[test]
+fn shared_between_threads() {
+    // arrange
+    let table = TranspositionTable::new(1);
+    // spread like real position hashes
+    let key = |index: u64| index.wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    // action
+    thread::scope(|scope| {
+        for offset in 0..4u64 {
+            let table = &table;
+            scope.spawn(move || {
+                for index in (offset..40_000).step_by(4) {
+                    table.store(key(index), entry(5, index as i32));
+                }
+            });
+        }
+    });
+    // assert
+    // every entry found belongs to its key, none was mixed up with another
+    let found: Vec<TableEntry> = (0..40_000u64)
+        .filter_map(|index| table.probe(key(index)).map(|found| (index, found)))
+        .inspect(|(index, found)| assert_eq!(found.score, *index as i32))
+        .map(|(_, found)| found)
+        .collect();
+    assert!(found.len() > 30_000);
+    assert!(table.hashfull() > 400);
+}
+
+#[test]
+fn search_finds_the_same_step_with_fewer_nodes() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("r1bqkbnr/pppp1ppp/2n5/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 2 3")?;
+    let limits = SearchLimits {
+        depth: 3,
+        ..Default::default()
+    };
+    let table = TranspositionTable::new(1);
+    // action
+    let plain = Search::new(&MaterialEvaluator, limits.clone()).run(&board, |_| {});
+    let cached = Search::new(&MaterialEvaluator, limits)
+        .with_transposition_table(&table)
+        .run(&board, |_| {});
+    // assert
+    assert_eq!(cached.score, plain.score);
+    assert!(cached.nodes < plain.nodes);
+    Ok(())
+}
+
@@ -0,0 +1,214 @@
+use std::{path::Path, thread};
+
+use crate::{
+    models::{
+        board::Board,
+        error::{DataError, FileError, ParseError},
+        piece::PieceColor,
+        variant_kind::VariantKind,
+    },
+    utils::fen::Fen,
+};
+
+use super::{
+    evaluation::EvalParams,
+    search::{Search, SearchLimits, MATE_SCORE},
+};
+
+/// A position with the result of the game it was played in
+#[derive(Debug, Clone)]
+pub struct TuningPosition {
+    pub board: Board,
+    /// 1 if White won, 0.5 for a draw and 0 if Black won
+    pub result: f64,
+}
+
+impl TuningPosition {
+    /// Reads a `<fen> | <score> | <result>` line as `selfplay` writes it, or
+    /// a FEN followed by the result as `1-0`, `0-1`, `1/2-1/2` or in
+    /// brackets like `[0.5]`.
+    pub fn decode(line: &str) -> Result<Self, DataError> {
+        let line = line.trim();
+        let (fen, result) = match line.split_once('|') {
+            Some((fen, rest)) => (fen, rest.rsplit('|').next().unwrap_or_default()),
+            None => line
+                .rsplit_once(' ')
+                .ok_or_else(|| ParseError::new(line, line.len(), "a result"))?,
+        };
+        let result = match result.trim().trim_matches(['[', ']', '"']) {
+            "1-0" => 1.0,
+            "0-1" => 0.0,
+            "1/2-1/2" => 0.5,
+            result => result
+                .parse::<f64>()
+                .ok()
+                .filter(|result| (0.0..=1.0).contains(result))
+                .ok_or_else(|| ParseError::new(result, 0, "a result between 0 and 1"))?,
+        };
+        Ok(Self {
+            board: Fen::decode_valid(fen.trim(), VariantKind::Standard)?,
+            result,
+        })
+    }
+}
+
+/// Texel tuning: changes the evaluation parameters one at a time as long as
+/// that lowers the mean squared difference between the game results and
+/// the quiescence search scores of the positions, mapped to an expected
+/// result by a logistic curve.
+pub struct Tuner {
+    positions: Vec<TuningPosition>,
+    pub params: EvalParams,
+    /// scales the scores on the logistic curve, fitted once to the start
+    /// parameters
+    pub k: f64,
+    /// completed passes over all parameters
+    pub iteration: usize,
+}
+
+impl Tuner {
+    pub fn new(positions: Vec<TuningPosition>, params: EvalParams) -> Self {
+        let mut res = Self {
+            positions,
+            params,
+            k: 1.0,
+            iteration: 0,
+        };
+        res.k = res.fit_k();
+        res
+    }
+
+    /// continues the tuning saved to the file, if there is one
+    pub fn resume(
+        positions: Vec<TuningPosition>,
+        path: impl AsRef<Path>,
+    ) -> Result<Self, DataError> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new(positions, EvalParams::default()));
+        }
+        let content = FileError::read_to_string(path)?;
+        let state = |key: &str| {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix(&format!("# {key} = ")))
+                .ok_or_else(|| DataError::MissingKey(key.to_string()))
+        };
+        let k = state("k")?;
+        let iteration = state("iteration")?;
+        Ok(Self {
+            positions,
+            params: EvalParams::decode(&content)?,
+            k: k.parse().map_err(|_| ParseError::new(k, 0, "a number"))?,
+            iteration: iteration
+                .parse()
+                .map_err(|_| ParseError::new(iteration, 0, "a whole number"))?,
+        })
+    }
+
+    /// Writes the parameters with the tuning state in comments, so that the
+    /// file can be loaded as parameters and tuning can resume from it
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FileError> {
+        let content = format!(
+            "# tuned on {} positions\n# k = {}\n# iteration = {}\n{}",
+            self.positions.len(),
+            self.k,
+            self.iteration,
+            self.params.encode()
+        );
+        FileError::write(path.as_ref(), content)
+    }
+
+    /// One pass over all parameters, each is moved by one in the direction
+    /// that lowers the error, if any. Returns the error after the pass.
+    pub fn step(&mut self) -> f64 {
+        let mut best = self.error(&self.params);
+        for index in 0..EvalParams::NAMES.len() {
+            for change in [1, -1] {
+                let mut values = self.params.values();
+                values[index] += change;
+                let params = EvalParams::from_values(values);
+                let error = self.error(&params);
+                if error < best {
+                    best = error;
+                    self.params = params;
+                    break;
+                }
+            }
+        }
+        self.iteration += 1;
+        best
+    }
+
+    /// the mean squared error of the parameters, spread over all cores
+    pub fn error(&self, params: &EvalParams) -> f64 {
+        self.error_with(params, self.k)
+    }
+
+    fn error_with(&self, params: &EvalParams, k: f64) -> f64 {
+        if self.positions.is_empty() {
+            return 0.0;
+        }
+        let threads = thread::available_parallelism().map_or(1, |threads| threads.get());
+        let chunk_size = self.positions.len().div_ceil(threads);
+        let total: f64 = thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .positions
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|position| {
+                                let expected = sigmoid(Self::score(params, &position.board), k);
+                                (position.result - expected).powi(2)
+                            })
+                            .sum::<f64>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("error threads don't panic"))
+                .sum()
+        });
+        total / self.positions.len() as f64
+    }
+
+    /// the quiescence search score for White
+    fn score(params: &EvalParams, board: &Board) -> i32 {
+        let score =
+            Search::new(params, SearchLimits::default()).quiescence(board, -MATE_SCORE, MATE_SCORE);
+        match board.turn_color() {
+            PieceColor::White => score,
+            PieceColor::Black => -score,
+        }
+    }
+
+    /// the scale with the lowest error, searched from 0 to 3 in steps of 0.1
+    /// and then in finer steps around the best one
+    fn fit_k(&self) -> f64 {
+        let mut best = 1.0;
+        let (mut low, mut high, mut step) = (0.0, 3.0, 0.1);
+        for _ in 0..3 {
+            let mut best_error = f64::MAX;
+            let mut k = low;
+            while k <= high {
+                let error = self.error_with(&self.params, k);
+                if error < best_error {
+                    best_error = error;
+                    best = k;
+                }
+                k += step;
+            }
+            (low, high) = ((best - step).max(0.0), best + step);
+            step /= 5.0;
+        }
+        best
+    }
+}
+
+/// the expected result for White of a score in centipawns
+fn sigmoid(score: i32, k: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-k * score as f64 / 400.0))
+}
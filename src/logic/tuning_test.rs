@@ -0,0 +1,80 @@
+use std::{error::Error, fs};
+
+use super::{
+    evaluation::EvalParams,
+    tuning::{Tuner, TuningPosition},
+};
+
+#[test]
+fn decodes_result_formats() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let lines = [
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 | 870 | 1.0",
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0",
+        "4k3/8/8/8/8/8/8/3qK3 w - - 0 1 [0.0]",
+        "4k3/8/8/8/8/8/8/4K3 w - - 0 1 \"1/2-1/2\"",
+    ];
+    // action
+    let results = lines
+        .iter()
+        .map(|line| TuningPosition::decode(line).map(|position| position.result))
+        .collect::<Result<Vec<_>, _>>()?;
+    // assert
+    assert_eq!(results, vec![1.0, 1.0, 0.0, 0.5]);
+    assert!(TuningPosition::decode("4k3/8/8/8/8/8/8/4K3 w - - 0 1 2-0").is_err());
+    // only one king
+    assert!(TuningPosition::decode("8/8/8/8/8/8/8/4K3 w - - 0 1 1-0").is_err());
+    Ok(())
+}
+
+#[test]
+fn tuning_lowers_the_error() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let lines = [
+        // an extra knight wins
+        "4k3/pppp4/8/8/8/5N2/PPPP4/4K3 w - - 0 1 1-0",
+        "4k3/pppp4/2n5/8/8/8/PPPP4/4K3 b - - 0 1 0-1",
+        "3nk3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1 0-1",
+        "4k3/pppp4/8/8/8/8/PPPP4/1N2K3 b - - 0 1 1-0",
+        "4k3/pppp4/8/8/8/8/PPPP4/4K3 w - - 0 1 1/2-1/2",
+    ];
+    let positions = lines
+        .iter()
+        .map(|line| TuningPosition::decode(line))
+        .collect::<Result<Vec<_>, _>>()?;
+    let params = EvalParams {
+        knight: 40,
+        ..Default::default()
+    };
+    let mut tuner = Tuner::new(positions, params);
+    let error = tuner.error(&params);
+    // action
+    let tuned_error = tuner.step();
+    // assert
+    assert!(tuned_error < error);
+    assert_eq!(tuner.params.knight, 41);
+    assert_eq!(tuner.iteration, 1);
+    Ok(())
+}
+
+#[test]
+fn resumes_from_the_saved_state() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let path = std::env::temp_dir().join("rustychess_tuning_test.params");
+    let positions = vec![TuningPosition::decode(
+        "4k3/8/8/8/8/8/8/3QK3 w - - 0 1 1-0",
+    )?];
+    let mut tuner = Tuner::new(positions.clone(), EvalParams::default());
+    tuner.step();
+    // action
+    tuner.save(&path)?;
+    let resumed = Tuner::resume(positions, &path)?;
+    let loaded = EvalParams::load(&path)?;
+    fs::remove_file(&path).map_err(|e| e.to_string())?;
+    // assert
+    assert_eq!(resumed.params, tuner.params);
+    assert_eq!(resumed.k, tuner.k);
+    assert_eq!(resumed.iteration, 1);
+    assert_eq!(loaded, tuner.params);
+    Ok(())
+}
@@ -0,0 +1,157 @@
+use crate::models::{
+    board::Board,
+    error::PositionError,
+    location::Location,
+    piece::{PieceColor, PieceKind},
+    step::StepKind,
+};
+
+use super::move_computer::StepComputer;
+
+impl Board {
+    /// Checks that the position can come up in a game of its variant, so
+    /// that the move generation can rely on it, e.g. on the kings
+    pub fn validate(&self) -> Result<(), PositionError> {
+        let rules = self.variant().rules();
+        let on_board = self
+            .locations()
+            .into_iter()
+            .filter_map(|location| self.field_at_location(&location)?.get_content())
+            .map(|(piece, _)| piece.kind);
+        let in_pockets = [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .flat_map(|color| self.pocket(color).iter().copied());
+        for kind in on_board.chain(in_pockets) {
+            if let PieceKind::Fairy(symbol) = kind {
+                if self.piece_set().get(kind).is_none() {
+                    return Err(PositionError::UnknownPiece(symbol));
+                }
+            }
+        }
+        for color in [PieceColor::White, PieceColor::Black] {
+            let count = self.pieces(PieceKind::King, color).len();
+            if !rules.king_count(color).contains(&count) {
+                return Err(PositionError::KingCount { color, count });
+            }
+        }
+        for color in [PieceColor::White, PieceColor::Black] {
+            let own_rank_allowed = rules.pawns_on_back_rank(color);
+            let misplaced = self
+                .pieces(PieceKind::Pawn, color)
+                .into_iter()
+                .find(|pawn| {
+                    pawn.y == self.back_rank(color.invert())
+                        || (pawn.y == self.back_rank(color) && !own_rank_allowed)
+                });
+            if let Some(pawn) = misplaced {
+                return Err(PositionError::PawnOnBackRank(pawn));
+            }
+        }
+
+        let (color, waiting) = (self.turn_color(), self.turn_color().invert());
+        if self.has_check(Some(waiting)) {
+            return Err(PositionError::OpponentInCheck(waiting));
+        }
+        let checkers = self.checkers(color);
+        if checkers.len() > 2 {
+            return Err(PositionError::TooManyCheckers {
+                color,
+                count: checkers.len(),
+            });
+        }
+        if let (Some(king), [first, second]) = (self.find_king(color), &checkers[..]) {
+            // the one that moved would have blocked the other one before
+            let (x1, y1) = Self::offset(&king, first);
+            let (x2, y2) = Self::offset(&king, second);
+            if x1 * y2 == x2 * y1 {
+                return Err(PositionError::ImpossibleCheck(color));
+            }
+        }
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            let rights = self.castling_rights(color);
+            let Some(king) = self.find_king(color) else {
+                continue;
+            };
+            let queenside = rights.iter().filter(|x| **x < king.x).count();
+            if queenside > 1 || rights.len() - queenside > 1 {
+                return Err(PositionError::CastlingRights(color));
+            }
+            let corners = [0, self.width - 1];
+            let off_start = king.x != self.width / 2 || rights.iter().any(|x| !corners.contains(x));
+            if !rights.is_empty() && !self.is_chess960() && off_start {
+                return Err(PositionError::CastlingSquares(color));
+            }
+        }
+
+        if let Some(square) = self.en_passant() {
+            if !self.en_passant_squares().contains(&square) {
+                return Err(PositionError::EnPassant(square));
+            }
+        }
+        Ok(())
+    }
+
+    /// The squares the opponent's pawns may just have skipped with a double
+    /// step: the pawn stands right in front of it, and the square it came
+    /// from is empty.
+    pub fn en_passant_squares(&self) -> Vec<Location> {
+        let color = self.turn_color().invert();
+        let rules = self.variant().rules();
+        // the ranks of the skipped square and the ones before and after it
+        let ranks = |skipped: usize| match color {
+            PieceColor::White => Some((skipped.checked_sub(1)?, skipped + 1)),
+            PieceColor::Black => Some((skipped + 1, skipped.checked_sub(1)?)),
+        };
+        self.locations()
+            .into_iter()
+            .filter(|square| {
+                let Some((from, to)) = ranks(square.y) else {
+                    return false;
+                };
+                let (from, to) = (Location::new(square.x, from), Location::new(square.x, to));
+                self.in_bounds(&from)
+                    && self.in_bounds(&to)
+                    && rules.pawn_double_step(self, color, from.y)
+                    && self.location_contains_piece(&to, vec![PieceKind::Pawn], color)
+                    && self.location_is_emtpy(square)
+                    && self.location_is_emtpy(&from)
+            })
+            .collect()
+    }
+
+    /// the pieces of the opponent that check the king of the given color
+    fn checkers(&self, color: PieceColor) -> Vec<Location> {
+        let Some(king) = self.find_king(color) else {
+            return vec![];
+        };
+        let mut checkers: Vec<Location> = self
+            .compute_simple_steps(Some(color.invert()))
+            .into_iter()
+            .filter_map(|step| match step {
+                StepKind::GoTo(step) | StepKind::Promote { step, .. } if step.to == king => {
+                    Some(step.from)
+                }
+                _ => None,
+            })
+            .collect();
+        checkers.sort_by_key(|location| (location.x, location.y));
+        checkers.dedup();
+        checkers
+    }
+
+    /// files and ranks from one square to another
+    fn offset(from: &Location, to: &Location) -> (isize, isize) {
+        (
+            to.x as isize - from.x as isize,
+            to.y as isize - from.y as isize,
+        )
+    }
+
+    fn pieces(&self, kind: PieceKind, color: PieceColor) -> Vec<Location> {
+        self.locations()
+            .into_iter()
+            .filter(|location| self.location_contains_piece(location, vec![kind], color))
+            .collect()
+    }
+}
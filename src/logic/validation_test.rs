@@ -0,0 +1,191 @@
+use std::error::Error;
+
+use crate::{
+    models::{
+        error::{BoardError, PositionError},
+        location::Location,
+        piece::PieceColor,
+        variant_kind::VariantKind,
+    },
+    utils::{board_creation::BoardCreation, fen::Fen},
+};
+
+fn validate(fen: &str) -> Result<Result<(), PositionError>, BoardError> {
+    Ok(Fen::decode(fen)?.validate())
+}
+
+#[test]
+fn start_positions_are_valid() -> Result<(), Box<dyn Error>> {
+    for variant in VariantKind::ALL {
+        // arrange
+        let board = variant.rules().start_position();
+        // action
+        let res = board.validate();
+        // assert
+        assert_eq!(res, Ok(()), "{variant:?}");
+    }
+    Ok(())
+}
+
+#[test]
+fn count_the_kings() -> Result<(), Box<dyn Error>> {
+    // action
+    let missing = validate("8/8/8/8/8/8/8/4K3 w - - 0 1")?;
+    let two = validate("4k3/8/8/8/8/8/8/3KK3 w - - 0 1")?;
+    let mut antichess = Fen::decode("8/8/8/8/8/8/8/4K3 w - - 0 1")?;
+    antichess.set_variant(VariantKind::Antichess);
+    let mut horde = Fen::decode("4k3/8/8/8/8/8/8/PPPPKPPP w - - 0 1")?;
+    horde.set_variant(VariantKind::Horde);
+    // assert
+    assert_eq!(
+        missing,
+        Err(PositionError::KingCount {
+            color: PieceColor::Black,
+            count: 0
+        })
+    );
+    assert_eq!(
+        two,
+        Err(PositionError::KingCount {
+            color: PieceColor::White,
+            count: 2
+        })
+    );
+    assert_eq!(antichess.validate(), Ok(()));
+    assert_eq!(
+        horde.validate(),
+        Err(PositionError::KingCount {
+            color: PieceColor::White,
+            count: 1
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn pawns_stay_off_the_back_ranks() -> Result<(), Box<dyn Error>> {
+    // action
+    let last_rank = validate("P3k3/8/8/8/8/8/8/4K3 w - - 0 1")?;
+    let first_rank = validate("4k3/8/8/8/8/8/8/p3K3 w - - 0 1")?;
+    // assert
+    assert_eq!(
+        last_rank,
+        Err(PositionError::PawnOnBackRank(Location::decode("a8")?))
+    );
+    assert_eq!(
+        first_rank,
+        Err(PositionError::PawnOnBackRank(Location::decode("a1")?))
+    );
+    Ok(())
+}
+
+#[test]
+fn reject_impossible_checks() -> Result<(), Box<dyn Error>> {
+    // action
+    let waiting_side = validate("4k3/4R3/8/8/8/8/8/4K3 w - - 0 1")?;
+    let double_check = validate("4k3/8/3N4/8/8/8/8/K3R3 b - - 0 1")?;
+    let triple_check = validate("4k3/8/3N2B1/8/8/8/8/K3R3 b - - 0 1")?;
+    let sliders = validate("4k3/8/8/8/B7/8/8/K3R3 b - - 0 1")?;
+    let opposite_sides = validate("r3K2r/8/8/8/8/8/8/4k3 w - - 0 1")?;
+    // assert
+    assert_eq!(
+        waiting_side,
+        Err(PositionError::OpponentInCheck(PieceColor::Black))
+    );
+    assert_eq!(double_check, Ok(()));
+    assert_eq!(sliders, Ok(()));
+    assert_eq!(
+        opposite_sides,
+        Err(PositionError::ImpossibleCheck(PieceColor::White))
+    );
+    assert_eq!(
+        triple_check,
+        Err(PositionError::TooManyCheckers {
+            color: PieceColor::Black,
+            count: 3
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn one_castling_rook_per_side() -> Result<(), Box<dyn Error>> {
+    // action
+    let res = validate("4k3/8/8/8/8/8/8/RR2K3 w AB - 0 1")?;
+    // assert
+    assert_eq!(res, Err(PositionError::CastlingRights(PieceColor::White)));
+    assert_eq!(validate("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1")?, Ok(()));
+    Ok(())
+}
+
+#[test]
+fn castling_starts_from_the_usual_squares() -> Result<(), Box<dyn Error>> {
+    // arrange
+    // placed by hand, the king and rooks count as unmoved
+    let king_off_start = BoardCreation::from_description("b1kw\na1rw\ne8kb".to_string());
+    let rook_off_start = BoardCreation::from_description("e1kw\nb1rw\ne8kb".to_string());
+    let chess960 = Fen::decode("4k3/8/8/8/8/8/8/RK6 w A - 0 1")?;
+    let mut not_chess960 = chess960.clone();
+    not_chess960.set_chess960(false);
+    // action
+    let usual = BoardCreation::from_description("e1kw\na1rw\nh1rw\ne8kb".to_string());
+    // assert
+    assert_eq!(
+        king_off_start.map(|_| ()),
+        Err(BoardError::Invalid(PositionError::CastlingSquares(
+            PieceColor::White
+        )))
+    );
+    assert_eq!(
+        rook_off_start.map(|_| ()),
+        Err(BoardError::Invalid(PositionError::CastlingSquares(
+            PieceColor::White
+        )))
+    );
+    assert_eq!(chess960.validate(), Ok(()));
+    assert_eq!(
+        not_chess960.validate(),
+        Err(PositionError::CastlingSquares(PieceColor::White))
+    );
+    assert!(usual.is_ok());
+    Ok(())
+}
+
+#[test]
+fn en_passant_needs_a_double_step() -> Result<(), Box<dyn Error>> {
+    // action
+    let without_pawn = validate("4k3/8/8/8/8/8/8/4K3 w - e6 0 1")?;
+    let after_double_step = validate("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1")?;
+    let wrong_side = validate("4k3/8/8/3pP3/8/8/8/4K3 b - d6 0 1")?;
+    // assert
+    assert_eq!(
+        without_pawn,
+        Err(PositionError::EnPassant(Location::decode("e6")?))
+    );
+    assert_eq!(after_double_step, Ok(()));
+    assert_eq!(
+        wrong_side,
+        Err(PositionError::EnPassant(Location::decode("d6")?))
+    );
+    Ok(())
+}
+
+#[test]
+fn imports_are_validated() -> Result<(), Box<dyn Error>> {
+    // action
+    let fen = Fen::decode_valid("4k3/8/8/8/8/8/8/8 w - - 0 1", VariantKind::Standard);
+    let horde = Fen::decode_valid("4k3/8/8/8/8/8/8/PPPPPPPP w - - 0 1", VariantKind::Horde);
+    let description = BoardCreation::from_description("a1kw\na2pb".to_string());
+    // assert
+    assert_eq!(
+        fen.map(|_| ()),
+        Err(BoardError::Invalid(PositionError::KingCount {
+            color: PieceColor::White,
+            count: 0
+        }))
+    );
+    assert!(horde.is_ok());
+    assert!(description.is_err());
+    assert!(Location::decode("").is_err());
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use std::ops::RangeInclusive;
+
+use crate::models::{
+    board::Board,
+    game_state::GameState,
+    piece::{PieceColor, PieceKind},
+    step::StepKind,
+    variant_kind::VariantKind,
+};
+
+use super::Variant;
+
+/// Also known as losing chess: whoever can capture has to, the king is an
+/// ordinary piece and the player who runs out of moves wins
+pub struct Antichess;
+
+impl Variant for Antichess {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Antichess
+    }
+
+    fn filter_legal(&self, board: &Board, steps: &mut Vec<StepKind>) {
+        if steps.iter().any(|step| board.is_capture(step)) {
+            steps.retain(|step| board.is_capture(step));
+        }
+    }
+
+    fn promotions(&self) -> Vec<PieceKind> {
+        vec![
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            PieceKind::King,
+        ]
+    }
+
+    fn allows_castling(&self) -> bool {
+        false
+    }
+
+    fn king_is_royal(&self) -> bool {
+        false
+    }
+
+    /// kings can be captured, and pawns promote to them
+    fn king_count(&self, _color: PieceColor) -> RangeInclusive<usize> {
+        0..=usize::MAX
+    }
+
+    fn no_steps_outcome(&self, board: &Board) -> GameState {
+        GameState::Won(board.turn_color())
+    }
+
+    /// the side that loses all its pieces wins, a lone king is no draw
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{
+    logic::move_computer::StepComputer,
+    models::{
+        board::Board,
+        game_state::GameState,
+        location::Location,
+        piece::{PieceColor, PieceKind},
+        step::{Direction, StepKind},
+        variant_kind::VariantKind,
+    },
+};
+
+use super::Variant;
+
+/// Captures explode: the capturing piece and all pieces but pawns around the
+/// target field are removed from the board. Blowing up the enemy king wins,
+/// so kings may not capture and a king touching the other one can't be
+/// checked.
+pub struct Atomic;
+
+impl Variant for Atomic {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Atomic
+    }
+
+    fn filter_legal(&self, board: &Board, steps: &mut Vec<StepKind>) {
+        let color = board.turn_color();
+        steps.retain(|step| {
+            let king_captures = match step {
+                StepKind::GoTo(inner) => {
+                    board.is_capture(step)
+                        && board.location_contains_piece(&inner.from, vec![PieceKind::King], color)
+                }
+                _ => false,
+            };
+            if king_captures {
+                return false;
+            }
+            let mut next_board = board.clone();
+            if next_board.apply_step_kind(step).is_err() {
+                return false;
+            }
+            // exploding the enemy king wins, even when in check
+            match (
+                next_board.find_king(color),
+                next_board.find_king(color.invert()),
+            ) {
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(_), Some(_)) => !next_board.has_check(Some(color)),
+            }
+        });
+    }
+
+    fn king_attacked(
+        &self,
+        board: &Board,
+        color: PieceColor,
+        king: Location,
+        attacks: &[StepKind],
+    ) -> bool {
+        // capturing a king next to the own one would blow up both
+        let kings_touch = board
+            .find_king(color.invert())
+            .is_some_and(|other| other.x.abs_diff(king.x) <= 1 && other.y.abs_diff(king.y) <= 1);
+        !kings_touch && attacks.iter().any(|step| step.target() == Some(king))
+    }
+
+    fn special_outcome(&self, board: &Board) -> Option<GameState> {
+        match (
+            board.find_king(PieceColor::White),
+            board.find_king(PieceColor::Black),
+        ) {
+            (Some(_), None) => Some(GameState::Won(PieceColor::White)),
+            (None, Some(_)) => Some(GameState::Won(PieceColor::Black)),
+            _ => None,
+        }
+    }
+
+    /// kings explode along with captured pieces next to them, so mating
+    /// material doesn't decide whether a side can win
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn after_capture(&self, board: &mut Board, target: Location) {
+        board.remove_piece(&target);
+        for x in -1..=1 {
+            for y in -1..=1 {
+                let Some(location) = board.add_location_direction(&target, &Direction(x, y)) else {
+                    continue;
+                };
+                let is_pawn = board.location_contains_piece(
+                    &location,
+                    vec![PieceKind::Pawn],
+                    PieceColor::White,
+                ) || board.location_contains_piece(
+                    &location,
+                    vec![PieceKind::Pawn],
+                    PieceColor::Black,
+                );
+                if !is_pawn {
+                    board.remove_piece(&location);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::OnceLock;
+
+use crate::{
+    models::{
+        board::Board,
+        piece::PieceKind,
+        piece_definition::{PieceDefinition, PieceSet},
+        variant_kind::VariantKind,
+    },
+    utils::fen::Fen,
+};
+
+use super::Variant;
+
+pub const CAPABLANCA_FEN: &str =
+    "rnabqkbcnr/pppppppppp/10/10/10/10/PPPPPPPPPP/RNABQKBCNR w KQkq - 0 1";
+
+// the definitions shipped with the engine, the archbishop and chancellor are
+// taken from them
+const FAIRY_PIECES: &str = include_str!("../../../data/fairy_pieces.conf");
+
+/// Chess on a 10x8 board with two additional pieces: the archbishop, moving
+/// like a bishop and a knight, and the chancellor, moving like a rook and a
+/// knight
+pub struct Capablanca;
+
+impl Capablanca {
+    pub const ARCHBISHOP: PieceKind = PieceKind::Fairy('A');
+    pub const CHANCELLOR: PieceKind = PieceKind::Fairy('C');
+}
+
+impl Variant for Capablanca {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Capablanca
+    }
+
+    fn pieces(&self) -> PieceSet {
+        static PIECES: OnceLock<PieceSet> = OnceLock::new();
+        PIECES
+            .get_or_init(|| {
+                let definitions = PieceDefinition::decode(FAIRY_PIECES)
+                    .expect("the shipped piece definitions are valid")
+                    .into_iter()
+                    .filter(|definition| ['A', 'C'].contains(&definition.symbol))
+                    .collect();
+                PieceSet::new(definitions).expect("the shipped piece definitions are valid")
+            })
+            .clone()
+    }
+
+    fn start_position(&self) -> Board {
+        let mut board = Fen::decode(CAPABLANCA_FEN).expect("the capablanca FEN is valid");
+        board.set_variant(self.kind());
+        board.set_piece_set(self.pieces());
+        board
+    }
+
+    fn promotions(&self) -> Vec<PieceKind> {
+        vec![
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+            Self::ARCHBISHOP,
+            Self::CHANCELLOR,
+        ]
+    }
+}
@@ -0,0 +1,23 @@
+use crate::models::{board::Board, variant_kind::VariantKind};
+
+use super::Variant;
+
+/// Standard chess, but captured pieces change sides: they go to the pocket
+/// of the capturing player, who may drop them on an empty field instead of
+/// moving
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Crazyhouse
+    }
+
+    fn has_drops(&self) -> bool {
+        true
+    }
+
+    /// captured pieces come back into play, so material never runs out
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
@@ -0,0 +1,63 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    models::{board::Board, game_state::GameState, piece::PieceColor, variant_kind::VariantKind},
+    utils::fen::Fen,
+};
+
+use super::Variant;
+
+pub const HORDE_FEN: &str =
+    "rnbqkbnr/pppppppp/8/1PP2PP1/PPPPPPPP/PPPPPPPP/PPPPPPPP/PPPPPPPP w kq - 0 1";
+
+/// White has 36 pawns and no king and wins by mating black, black wins by
+/// capturing every white piece
+pub struct Horde;
+
+impl Variant for Horde {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Horde
+    }
+
+    fn start_position(&self) -> Board {
+        let mut board = Fen::decode(HORDE_FEN).expect("the horde FEN is valid");
+        board.set_variant(self.kind());
+        board
+    }
+
+    fn king_count(&self, color: PieceColor) -> RangeInclusive<usize> {
+        match color {
+            PieceColor::White => 0..=0,
+            PieceColor::Black => 1..=1,
+        }
+    }
+
+    /// the horde starts with pawns on the first rank
+    fn pawns_on_back_rank(&self, color: PieceColor) -> bool {
+        color == PieceColor::White
+    }
+
+    /// the pawns on the first rank may double step as well
+    fn pawn_double_step(&self, board: &Board, color: PieceColor, rank: usize) -> bool {
+        match color {
+            PieceColor::White => rank <= 1,
+            PieceColor::Black => rank + 2 == board.height,
+        }
+    }
+
+    /// as on lichess, only the double steps from the second rank can be
+    /// captured en passant
+    fn double_step_en_passant(&self, color: PieceColor, rank: usize) -> bool {
+        color == PieceColor::Black || rank == 1
+    }
+
+    fn special_outcome(&self, board: &Board) -> Option<GameState> {
+        let white_left = board.locations().iter().any(|location| {
+            board
+                .field_at_location(location)
+                .and_then(|field| field.get_color())
+                == Some(PieceColor::White)
+        });
+        (!white_left).then_some(GameState::Won(PieceColor::Black))
+    }
+}
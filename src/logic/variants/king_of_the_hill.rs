@@ -0,0 +1,35 @@
+use crate::models::{
+    board::Board, game_state::GameState, piece::PieceColor, variant_kind::VariantKind,
+};
+
+use super::Variant;
+
+/// Standard chess, but bringing the own king to one of the four center
+/// squares wins the game
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn kind(&self) -> VariantKind {
+        VariantKind::KingOfTheHill
+    }
+
+    fn special_outcome(&self, board: &Board) -> Option<GameState> {
+        let on_hill = |x: usize, y: usize| {
+            (board.width / 2 - 1..=board.width / 2).contains(&x)
+                && (board.height / 2 - 1..=board.height / 2).contains(&y)
+        };
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| {
+                board
+                    .find_king(*color)
+                    .is_some_and(|king| on_hill(king.x, king.y))
+            })
+            .map(GameState::Won)
+    }
+
+    /// a lone king can still win by reaching the hill
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
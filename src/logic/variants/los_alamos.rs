@@ -0,0 +1,36 @@
+use crate::{
+    models::{board::Board, piece::PieceColor, piece::PieceKind, variant_kind::VariantKind},
+    utils::fen::Fen,
+};
+
+use super::Variant;
+
+pub const LOS_ALAMOS_FEN: &str = "rnqknr/pppppp/6/6/PPPPPP/RNQKNR w - - 0 1";
+
+/// The first chess variant played by a computer: a 6x6 board without
+/// bishops, castling or pawn double steps
+pub struct LosAlamos;
+
+impl Variant for LosAlamos {
+    fn kind(&self) -> VariantKind {
+        VariantKind::LosAlamos
+    }
+
+    fn start_position(&self) -> Board {
+        let mut board = Fen::decode(LOS_ALAMOS_FEN).expect("the los alamos FEN is valid");
+        board.set_variant(self.kind());
+        board
+    }
+
+    fn promotions(&self) -> Vec<PieceKind> {
+        vec![PieceKind::Queen, PieceKind::Rook, PieceKind::Knight]
+    }
+
+    fn allows_castling(&self) -> bool {
+        false
+    }
+
+    fn pawn_double_step(&self, _board: &Board, _color: PieceColor, _rank: usize) -> bool {
+        false
+    }
+}
@@ -0,0 +1,184 @@
+mod antichess;
+mod atomic;
+mod capablanca;
+mod crazyhouse;
+mod horde;
+mod king_of_the_hill;
+mod los_alamos;
+mod standard;
+mod three_check;
+
+#[cfg(test)]
+mod variants_test;
+
+pub use antichess::Antichess;
+pub use atomic::Atomic;
+pub use capablanca::{Capablanca, CAPABLANCA_FEN};
+pub use crazyhouse::Crazyhouse;
+pub use horde::{Horde, HORDE_FEN};
+pub use king_of_the_hill::KingOfTheHill;
+pub use los_alamos::{LosAlamos, LOS_ALAMOS_FEN};
+pub use standard::Standard;
+pub use three_check::{ThreeCheck, CHECKS_TO_WIN};
+
+use std::ops::RangeInclusive;
+
+use crate::models::{
+    board::Board,
+    game_state::GameState,
+    location::Location,
+    piece::{PieceColor, PieceKind},
+    piece_definition::PieceSet,
+    step::StepKind,
+    variant_kind::VariantKind,
+};
+
+use super::move_computer::StepComputer;
+
+/// The rules of a variant. The default methods implement standard chess, so
+/// a variant only overrides what it changes.
+pub trait Variant: Send + Sync {
+    fn kind(&self) -> VariantKind;
+
+    /// the fairy pieces boards of the variant are played with
+    fn pieces(&self) -> PieceSet {
+        PieceSet::default()
+    }
+
+    fn start_position(&self) -> Board {
+        let mut board = Board::standard_board();
+        board.set_variant(self.kind());
+        board
+    }
+
+    /// removes the pseudo legal steps that are not allowed, by default the
+    /// ones leaving the own king in check
+    fn filter_legal(&self, board: &Board, steps: &mut Vec<StepKind>) {
+        board.filter_check_steps(steps);
+    }
+
+    /// whether the king of the given color on the given field is in check,
+    /// given all the steps of the opponent
+    fn king_attacked(
+        &self,
+        _board: &Board,
+        _color: PieceColor,
+        king: Location,
+        attacks: &[StepKind],
+    ) -> bool {
+        attacks.iter().any(|step| step.target() == Some(king))
+    }
+
+    /// the pieces a pawn may turn into on the last rank
+    fn promotions(&self) -> Vec<PieceKind> {
+        vec![
+            PieceKind::Queen,
+            PieceKind::Rook,
+            PieceKind::Bishop,
+            PieceKind::Knight,
+        ]
+    }
+
+    fn allows_castling(&self) -> bool {
+        true
+    }
+
+    /// whether the king may not be left in check. Without a royal king,
+    /// there is neither check nor mate.
+    fn king_is_royal(&self) -> bool {
+        true
+    }
+
+    /// how many kings a side may have in a legal position
+    fn king_count(&self, _color: PieceColor) -> RangeInclusive<usize> {
+        1..=1
+    }
+
+    /// whether pawns of the given color may stand on their own back rank
+    fn pawns_on_back_rank(&self, _color: PieceColor) -> bool {
+        false
+    }
+
+    /// whether captured pieces go to a pocket and can be dropped again
+    fn has_drops(&self) -> bool {
+        false
+    }
+
+    /// whether a pawn of the given color may do a double step from the rank
+    fn pawn_double_step(&self, board: &Board, color: PieceColor, rank: usize) -> bool {
+        match color {
+            PieceColor::White => rank == 1,
+            PieceColor::Black => rank + 2 == board.height,
+        }
+    }
+
+    /// whether a double step of a pawn of the given color from the rank can
+    /// be captured en passant
+    fn double_step_en_passant(&self, _color: PieceColor, _rank: usize) -> bool {
+        true
+    }
+
+    /// ends the game before the moves run out, e.g. a king reaching the hill
+    fn special_outcome(&self, _board: &Board) -> Option<GameState> {
+        None
+    }
+
+    /// whether neither side can win anymore, by default when both lack the
+    /// material to mate
+    fn insufficient_material(&self, board: &Board) -> bool {
+        !board.has_mating_material(PieceColor::White)
+            && !board.has_mating_material(PieceColor::Black)
+    }
+
+    /// the result when the side to move has no legal steps
+    fn no_steps_outcome(&self, board: &Board) -> GameState {
+        if board.has_check(None) {
+            GameState::Won(board.turn_color().invert())
+        } else {
+            GameState::Stalemate
+        }
+    }
+
+    /// called after a capturing step was applied, with the field the
+    /// capturing piece moved to
+    fn after_capture(&self, _board: &mut Board, _target: Location) {}
+
+    /// called after every applied step, to keep track of variant state
+    fn after_step(&self, _board: &mut Board) {}
+}
+
+impl VariantKind {
+    pub fn rules(&self) -> &'static dyn Variant {
+        match self {
+            VariantKind::Standard => &Standard,
+            VariantKind::KingOfTheHill => &KingOfTheHill,
+            VariantKind::ThreeCheck => &ThreeCheck,
+            VariantKind::Antichess => &Antichess,
+            VariantKind::Horde => &Horde,
+            VariantKind::Capablanca => &Capablanca,
+            VariantKind::LosAlamos => &LosAlamos,
+            VariantKind::Crazyhouse => &Crazyhouse,
+            VariantKind::Atomic => &Atomic,
+        }
+    }
+}
+
+impl Board {
+    /// whether the game is over in this position, and how, under the rules
+    /// of the board's variant
+    pub fn game_state(&self) -> GameState {
+        let rules = self.variant().rules();
+        if let Some(outcome) = rules.special_outcome(self) {
+            return outcome;
+        }
+        if self.compute_steps().is_empty() {
+            rules.no_steps_outcome(self)
+        } else if self.halfmove_clock() >= 100 {
+            GameState::FiftyMoves
+        } else if rules.insufficient_material(self) {
+            GameState::InsufficientMaterial
+        } else {
+            GameState::Ongoing
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use crate::models::variant_kind::VariantKind;
+
+use super::Variant;
+
+pub struct Standard;
+
+impl Variant for Standard {
+    fn kind(&self) -> VariantKind {
+        VariantKind::Standard
+    }
+}
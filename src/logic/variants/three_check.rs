@@ -0,0 +1,37 @@
+use crate::{
+    logic::move_computer::StepComputer,
+    models::{board::Board, game_state::GameState, piece::PieceColor, variant_kind::VariantKind},
+};
+
+use super::Variant;
+
+/// how many checks win the game
+pub const CHECKS_TO_WIN: usize = 3;
+
+/// Standard chess, but the player giving the third check wins
+pub struct ThreeCheck;
+
+impl Variant for ThreeCheck {
+    fn kind(&self) -> VariantKind {
+        VariantKind::ThreeCheck
+    }
+
+    fn special_outcome(&self, board: &Board) -> Option<GameState> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| board.checks_given(*color) >= CHECKS_TO_WIN)
+            .map(GameState::Won)
+    }
+
+    /// a lone knight or bishop can still give the checks
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn after_step(&self, board: &mut Board) {
+        if board.has_check(None) {
+            let checking = board.turn_color().invert();
+            board.set_checks_given(checking, board.checks_given(checking) + 1);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use std::error::Error;
+
+use crate::{
+    driver::game::Game,
+    logic::move_computer::StepComputer,
+    models::{
+        error::{BoardError, ParseError},
+        game_state::GameState,
+        piece::{PieceColor, PieceKind},
+        step::StepKind,
+        variant_kind::VariantKind,
+    },
+    utils::{fen::Fen, san::San},
+};
+
+fn game(fen: &str, variant: VariantKind) -> Result<Game, BoardError> {
+    let mut board = Fen::decode(fen)?;
+    board.set_variant(variant);
+    Ok(Game::from_board(board))
+}
+
+#[test]
+fn perft_start_positions() {
+    for (variant, expected) in [
+        (VariantKind::Standard, [20, 400, 8902]),
+        (VariantKind::KingOfTheHill, [20, 400, 8902]),
+        (VariantKind::ThreeCheck, [20, 400, 8902]),
+        (VariantKind::Antichess, [20, 400, 8067]),
+        (VariantKind::Horde, [8, 128, 1274]),
+        (VariantKind::Capablanca, [28, 784, 25228]),
+        (VariantKind::LosAlamos, [10, 100, 1212]),
+        (VariantKind::Atomic, [20, 400, 8902]),
+    ] {
+        // arrange
+        let board = variant.rules().start_position();
+        // action
+        let nodes: Vec<u64> = (1..=3).map(|depth| board.perft(depth)).collect();
+        // assert
+        assert_eq!(nodes, expected, "{variant:?}");
+    }
+}
+
+#[test]
+fn king_of_the_hill_wins_in_the_center() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("4k3/8/8/8/8/4K3/8/8 w - - 0 1", VariantKind::KingOfTheHill)?;
+    // action
+    let state = game.apply_input("Ke4")?;
+    // assert
+    assert_eq!(state, GameState::Won(PieceColor::White));
+    assert!(game.board.compute_steps().is_empty());
+    Ok(())
+}
+
+#[test]
+fn three_check_wins_with_the_third_check() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game(
+        "4k3/8/8/8/8/8/8/4K2R w - - 2+3 0 1",
+        VariantKind::ThreeCheck,
+    )?;
+    // action
+    game.apply_input("Rh8+")?;
+    let state = game.apply_input("Kd7")?;
+    let encoded_before = Fen::encode(&game.board);
+    let final_state = game.apply_input("Rh7+")?;
+    // assert
+    assert_eq!(state, GameState::Ongoing);
+    assert_eq!(encoded_before, "7R/3k4/8/8/8/8/8/4K3 w - - 1+3 2 2");
+    assert_eq!(final_state, GameState::Won(PieceColor::White));
+    Ok(())
+}
+
+#[test]
+fn three_check_fen_counts() -> Result<(), Box<dyn Error>> {
+    // action
+    let given = Fen::decode("4k3/8/8/8/8/8/8/4K2R w - - +1+2 0 1")?;
+    let left = Fen::decode("4k3/8/8/8/8/8/8/4K2R w - - 2+1 0 1")?;
+    // assert
+    for board in [given, left] {
+        assert_eq!(board.checks_given(PieceColor::White), 1);
+        assert_eq!(board.checks_given(PieceColor::Black), 2);
+    }
+    Ok(())
+}
+
+#[test]
+fn antichess_captures_are_compulsory() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let game = game(
+        "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w - - 0 2",
+        VariantKind::Antichess,
+    )?;
+    // action
+    let steps: Vec<String> = game
+        .board
+        .compute_steps()
+        .iter()
+        .map(|step| step.encode_uci())
+        .collect();
+    // assert
+    assert_eq!(steps, vec!["e4d5"]);
+    Ok(())
+}
+
+#[test]
+fn antichess_losing_everything_wins() -> Result<(), Box<dyn Error>> {
+    // arrange: the king is an ordinary piece that can be taken
+    let mut game = game("8/8/8/8/8/8/1k6/K7 b - - 0 1", VariantKind::Antichess)?;
+    // action
+    let state = game.apply_input("Kxa1")?;
+    // assert
+    assert_eq!(state, GameState::Won(PieceColor::White));
+    Ok(())
+}
+
+#[test]
+fn antichess_promotes_to_king() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("8/P7/8/8/8/8/8/7k w - - 0 1", VariantKind::Antichess)?;
+    // action
+    game.apply_input("a8=K")?;
+    // assert
+    assert_eq!(Fen::encode(&game.board), "K7/8/8/8/8/8/8/7k b - - 0 1");
+    Ok(())
+}
+
+#[test]
+fn horde_pawns_double_step_from_the_first_rank() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("4k3/8/8/8/8/8/8/P7 w - - 0 1", VariantKind::Horde)?;
+    // action
+    let state = game.apply_input("a3")?;
+    // assert
+    assert_eq!(state, GameState::Ongoing);
+    assert_eq!(game.board.en_passant(), None);
+    Ok(())
+}
+
+#[test]
+fn horde_double_steps_from_the_first_rank_cannot_be_taken_en_passant() -> Result<(), Box<dyn Error>>
+{
+    // arrange
+    let mut first_rank = game("4k3/8/8/8/8/1p6/8/P7 w - - 0 1", VariantKind::Horde)?;
+    let mut second_rank = game("4k3/8/8/8/1p6/8/P7/8 w - - 0 1", VariantKind::Horde)?;
+    // action
+    first_rank.apply_input("a3")?;
+    second_rank.apply_input("a4")?;
+    // assert
+    assert!(first_rank.apply_input("bxa2").is_err());
+    assert_eq!(
+        Fen::encode(&first_rank.board),
+        "4k3/8/8/8/8/Pp6/8/8 b - - 0 1"
+    );
+    assert_eq!(
+        second_rank.board.en_passant().map(|ep| ep.encode()),
+        Some("a3".to_string())
+    );
+    second_rank.apply_input("bxa3")?;
+    assert_eq!(
+        Fen::encode(&second_rank.board),
+        "4k3/8/8/8/8/p7/8/8 w - - 0 2"
+    );
+    Ok(())
+}
+
+#[test]
+fn horde_black_wins_by_capturing_everything() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("4k3/8/8/8/8/8/3q4/4P3 b - - 0 1", VariantKind::Horde)?;
+    // action
+    let state = game.apply_input("Qxe1")?;
+    // assert
+    assert_eq!(state, GameState::Won(PieceColor::Black));
+    Ok(())
+}
+
+#[test]
+fn decode_variant_names() -> Result<(), Box<dyn Error>> {
+    for variant in VariantKind::ALL {
+        assert_eq!(VariantKind::decode(variant.encode())?, variant);
+        assert_eq!(VariantKind::decode(variant.encode_uci())?, variant);
+    }
+    assert_eq!(
+        VariantKind::decode("bughouse"),
+        Err(ParseError::new("bughouse", 0, "a known variant"))
+    );
+    Ok(())
+}
+
+#[test]
+fn capablanca_game_on_10x8() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(VariantKind::Capablanca.rules().start_position());
+    // action
+    for input in [
+        "Nj3", "Nj6", "Ci3", "Ci6", "h3", "h6", "Bh2", "Bh7", "O-O", "O-O",
+    ] {
+        game.apply_input(input)?;
+    }
+    // assert
+    assert_eq!(
+        Fen::encode(&game.board),
+        "rnabq2rk1/pppppppbpp/7pcn/10/10/7PCN/PPPPPPPBPP/RNABQ2RK1 w - - 4 6"
+    );
+    Ok(())
+}
+
+#[test]
+fn los_alamos_game_on_6x6() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(VariantKind::LosAlamos.rules().start_position());
+    // action
+    game.apply_input("b3")?;
+    game.apply_input("e4")?;
+    let double_step = game.apply_input("a2a4");
+    // assert
+    assert!(double_step.is_err());
+    assert_eq!(
+        Fen::encode(&game.board),
+        "rnqknr/pppp1p/4p1/1P4/P1PPPP/RNQKNR w - - 0 2"
+    );
+    Ok(())
+}
+
+#[test]
+fn los_alamos_promotes_without_bishops() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = game("k5/4P1/6/6/6/K5 w - - 0 1", VariantKind::LosAlamos)?.board;
+    // action
+    let promotions: Vec<String> = board
+        .compute_steps()
+        .iter()
+        .map(|step| step.encode_uci())
+        .filter(|step| step.starts_with("e5"))
+        .collect();
+    // assert
+    assert_eq!(promotions, vec!["e5e6q", "e5e6r", "e5e6n"]);
+    Ok(())
+}
+
+#[test]
+fn crazyhouse_perft() -> Result<(), Box<dyn Error>> {
+    for (fen, expected) in [
+        (
+            "r1b1kbnr/ppp2ppp/2n5/3qp3/8/5N2/PPPP1PPP/RNBQKB1R[Pp] w KQkq - 0 5",
+            [58, 4475],
+        ),
+        ("r2q~k3/8/8/8/8/8/8/4K2R[] w K - 0 1", [13, 344]),
+    ] {
+        // arrange
+        let board = game(fen, VariantKind::Crazyhouse)?.board;
+        // action
+        let nodes: Vec<u64> = (1..=2).map(|depth| board.perft(depth)).collect();
+        // assert
+        assert_eq!(nodes, expected, "{fen}");
+    }
+    Ok(())
+}
+
+#[test]
+fn crazyhouse_captures_can_be_dropped() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(VariantKind::Crazyhouse.rules().start_position());
+    for input in ["e4", "d5", "exd5", "Qxd5"] {
+        game.apply_input(input)?;
+    }
+    let pockets = Fen::encode(&game.board);
+    // action
+    let drop = San::decode(&game.board, "P@e4")?;
+    let san = San::encode(&game.board, &drop);
+    game.apply_stepkind(drop)?;
+    game.apply_input("Qxe4+")?;
+    // assert
+    assert_eq!(
+        pockets,
+        "rnb1kbnr/ppp1pppp/8/3q4/8/8/PPPP1PPP/RNBQKBNR[Pp] w KQkq - 0 3"
+    );
+    assert_eq!(san, "P@e4");
+    assert_eq!(game.board.pocket(PieceColor::White), &[]);
+    assert_eq!(
+        game.board.pocket(PieceColor::Black),
+        &[PieceKind::Pawn, PieceKind::Pawn]
+    );
+    Ok(())
+}
+
+#[test]
+fn crazyhouse_promoted_pieces_are_captured_as_pawns() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game(
+        "4k3/8/8/8/8/8/4K3/q~6R[] w - - 0 1",
+        VariantKind::Crazyhouse,
+    )?;
+    // action
+    game.apply_input("Rxa1")?;
+    // assert
+    assert_eq!(
+        Fen::encode(&game.board),
+        "4k3/8/8/8/8/8/4K3/R7[P] b - - 0 1"
+    );
+    Ok(())
+}
+
+#[test]
+fn crazyhouse_no_pawn_drops_on_the_back_ranks() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = game("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1", VariantKind::Crazyhouse)?.board;
+    // action
+    let drops: Vec<StepKind> = board
+        .compute_steps()
+        .into_iter()
+        .filter(|step| matches!(step, StepKind::Drop { .. }))
+        .collect();
+    // assert
+    assert_eq!(drops.len(), 6 * 8);
+    assert!(drops
+        .iter()
+        .all(|step| step.target().is_some_and(|to| to.y != 0 && to.y != 7)));
+    assert_eq!(StepKind::decode("@e4")?, StepKind::decode("P@e4")?);
+    Ok(())
+}
+
+#[test]
+fn atomic_perft() -> Result<(), Box<dyn Error>> {
+    for (fen, expected) in [
+        (
+            "r4b1r/2kb1N2/p2Bpnp1/8/2Pp3p/1P1PPP2/P5PP/R3K2R b KQ - 0 1",
+            [4, 148],
+        ),
+        ("1R1Q4/8/8/8/8/8/4kK2/8 w - - 0 1", [35, 181]),
+        ("8/8/8/8/8/8/2k5/rR4K1 w - - 0 1", [10, 107]),
+    ] {
+        // arrange
+        let board = game(fen, VariantKind::Atomic)?.board;
+        // action
+        let nodes: Vec<u64> = (1..=2).map(|depth| board.perft(depth)).collect();
+        // assert
+        assert_eq!(nodes, expected, "{fen}");
+    }
+    Ok(())
+}
+
+#[test]
+fn atomic_captures_explode_all_but_pawns() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("4k3/8/2rpn3/3b4/8/8/3R4/4K3 w - - 0 1", VariantKind::Atomic)?;
+    // action
+    game.apply_input("Rxd5")?;
+    // assert
+    assert_eq!(Fen::encode(&game.board), "4k3/8/3p4/8/8/8/8/4K3 b - - 0 1");
+    Ok(())
+}
+
+#[test]
+fn atomic_exploding_the_king_wins() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = game("4k3/3p4/8/8/8/8/8/3QK3 w - - 0 1", VariantKind::Atomic)?;
+    // action
+    let state = game.apply_input("Qxd7")?;
+    // assert
+    assert_eq!(state, GameState::Won(PieceColor::White));
+    Ok(())
+}
+
+#[test]
+fn atomic_kings_neither_capture_nor_check_each_other() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let capture = game("4k3/8/8/8/8/8/5p2/4K3 w - - 0 1", VariantKind::Atomic)?.board;
+    let touching = game("5r2/8/8/8/8/8/4kK2/8 w - - 0 1", VariantKind::Atomic)?.board;
+    // action
+    let king_steps: Vec<String> = capture
+        .compute_steps()
+        .iter()
+        .map(|step| step.encode_uci())
+        .collect();
+    // assert
+    assert!(!king_steps.contains(&"e1f2".to_string()));
+    assert!(!touching.has_check(None));
+    Ok(())
+}
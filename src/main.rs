@@ -1,37 +1,467 @@
-use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
-    execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
-};
+use clap::{Parser, Subcommand};
 use log::{info, LevelFilter};
-use ratatui::{prelude::CrosstermBackend, Terminal};
-use rustychess::gui::app::App;
+use rustychess::{
+    driver::{
+        bench::bench,
+        cli_driver::CliDriver,
+        engine_match::{EngineConfig, EngineMatch, MatchOptions},
+        game::Game,
+        inspect::{analyze_position, book_moves, perft, validate_pgn},
+        puzzle::{Puzzle, PuzzleRating, RATING_FILE},
+        replay::Replay,
+        review::GameReview,
+        selfplay::{SelfPlay, SelfPlayOptions},
+        tune::tune,
+        uci::UciDriver,
+    },
+    gui::{
+        app::App,
+        theme::{GlyphSet, Theme},
+    },
+    logic::{
+        analysis::AnalysisOptions, book::OpeningBook, evaluation::EvalParams, nnue::Network,
+        tablebase::Tablebase,
+    },
+    models::{
+        board::Board, clock::TimeControl, piece_definition::PieceDefinition,
+        variant_kind::VariantKind,
+    },
+    utils::fen::Fen,
+};
 use simplelog::{CombinedLogger, Config, WriteLogger};
 use std::{
     fs::File,
-    io::{self},
+    io,
+    path::PathBuf,
+    process::ExitCode,
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
-fn main() -> io::Result<()> {
+#[derive(Parser)]
+#[command(version, about = "Chess in the terminal")]
+struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+    /// file the log is written to
+    #[arg(long, global = true, default_value = "app.log")]
+    log_file: PathBuf,
+    /// one of off, error, warn, info, debug, trace
+    #[arg(long, global = true, default_value = "debug")]
+    log_level: LevelFilter,
+    /// position new games start from
+    #[arg(long, global = true)]
+    fen: Option<String>,
+    /// start from a chess960 position, numbered 0 to 959 or 'random'
+    #[arg(long, global = true, conflicts_with = "fen")]
+    chess960: Option<String>,
+    /// one of standard, king-of-the-hill, three-check, antichess, horde,
+    /// capablanca, los-alamos
+    #[arg(long, global = true, value_parser = VariantKind::decode)]
+    variant: Option<VariantKind>,
+    /// definition file of fairy pieces, e.g. data/fairy_pieces.conf
+    #[arg(long, global = true)]
+    pieces: Option<PathBuf>,
+    /// directory of Syzygy endgame tablebases (.rtbw and .rtbz files)
+    #[arg(long, global = true)]
+    syzygy: Option<PathBuf>,
+    /// network file to evaluate with instead of counting material
+    #[arg(long, global = true)]
+    nnue: Option<PathBuf>,
+    /// evaluation parameters, e.g. written by the tune subcommand
+    #[arg(long, global = true)]
+    params: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// play in the terminal user interface (default)
+    Tui {
+        /// continue a game saved as PGN
+        #[arg(long)]
+        resume: Option<PathBuf>,
+        /// e.g. 180+2, 300d3 or 40/5400:1800
+        #[arg(long)]
+        time_control: Option<String>,
+        /// theme file, defaults to rustychess.conf if it exists
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// one of classic, wood, high-contrast, monochrome
+        #[arg(long, value_parser = Theme::preset)]
+        theme: Option<Theme>,
+        /// one of nerd-font, unicode, ascii
+        #[arg(long, value_parser = GlyphSet::decode)]
+        glyphs: Option<GlyphSet>,
+        /// puzzles in the CSV format of the Lichess puzzle database
+        #[arg(long)]
+        puzzles: Option<PathBuf>,
+        /// step through the first game of a PGN
+        #[arg(long)]
+        replay: Option<PathBuf>,
+    },
+    /// play by typing moves
+    Cli {
+        /// continue a game saved as PGN
+        #[arg(long)]
+        resume: Option<PathBuf>,
+        /// e.g. 180+2, 300d3 or 40/5400:1800
+        #[arg(long)]
+        time_control: Option<String>,
+    },
+    /// run as an engine speaking the universal chess interface
+    Uci {
+        /// Polyglot opening book to play from, e.g. data/test_book.bin
+        #[arg(long)]
+        book: Option<PathBuf>,
+    },
+    /// list the moves a Polyglot opening book has for a position
+    Book { file: PathBuf, fen: Option<String> },
+    /// count the leaf nodes of the move tree to verify move generation
+    Perft { depth: usize, fen: Option<String> },
+    /// search a position and print its best lines at every depth
+    Analyze {
+        /// the position, the start position if neither it nor a PGN is given
+        fen: Option<String>,
+        /// a game to analyse a position of instead
+        #[arg(long, conflicts_with = "fen")]
+        pgn: Option<PathBuf>,
+        /// plies played from the start of the game, all if not given
+        #[arg(long, requires = "pgn")]
+        ply: Option<usize>,
+        #[arg(long, default_value_t = 4)]
+        depth: usize,
+        /// how many of the best moves get a line
+        #[arg(long = "multipv", default_value_t = 3)]
+        multi_pv: usize,
+        #[arg(long, default_value_t = 1)]
+        threads: usize,
+    },
+    /// search every position of a game and point out inaccuracies,
+    /// mistakes and blunders
+    Review {
+        /// the game, the first one if the file has several
+        pgn: PathBuf,
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+        /// the annotated game is written here
+        #[arg(long, default_value = "review.pgn")]
+        out: PathBuf,
+    },
+    /// play the engine against itself and write the positions for training
+    /// a network, one '<fen> | <score> | <result>' line each
+    Selfplay {
+        /// file the positions are appended to
+        out: PathBuf,
+        #[arg(long, default_value_t = 10)]
+        games: usize,
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+        /// moves played at random at the start of every game
+        #[arg(long, default_value_t = 8)]
+        random_plies: usize,
+    },
+    /// play two engine configurations against each other and rate them
+    Match {
+        /// e.g. 'depth=4' or 'depth=3,params=eval.params,name=new'
+        #[arg(long, value_parser = EngineConfig::decode)]
+        first: EngineConfig,
+        /// e.g. 'cmd=/usr/bin/stockfish,movetime=100' or 'cmd=rustychess uci'
+        #[arg(long, value_parser = EngineConfig::decode)]
+        second: EngineConfig,
+        #[arg(long, default_value_t = 10)]
+        games: usize,
+        /// file with one FEN per line, each is played with both colors
+        #[arg(long)]
+        openings: Option<PathBuf>,
+        /// file all games are appended to
+        #[arg(long, default_value = "match.pgn")]
+        pgn: PathBuf,
+        /// Elo difference of the null hypothesis of the SPRT
+        #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+        elo0: f64,
+        /// Elo difference of the alternative hypothesis of the SPRT
+        #[arg(long, default_value_t = 10.0, allow_negative_numbers = true)]
+        elo1: f64,
+    },
+    /// tune the evaluation parameters to the results of games (Texel
+    /// tuning), resuming from the output file if it exists
+    Tune {
+        /// lines of a FEN and the game result, e.g. written by selfplay
+        positions: PathBuf,
+        /// parameters are written here after every iteration
+        #[arg(long, default_value = "eval.params")]
+        out: PathBuf,
+        #[arg(long, default_value_t = 10)]
+        iterations: usize,
+    },
+    /// search fixed positions with 1, 2, 4 and 8 threads and compare the speed
+    Bench {
+        #[arg(long, default_value_t = 3)]
+        depth: usize,
+    },
+    /// check that every game in a PGN file can be replayed
+    PgnValidate { file: PathBuf },
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: Args) -> io::Result<ExitCode> {
     let _ = CombinedLogger::init(vec![WriteLogger::new(
-        LevelFilter::Debug,
+        args.log_level,
         Config::default(),
-        File::create("app.log")?,
+        File::create(&args.log_file)?,
     )]);
 
     info!("initialized logging!");
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    let app_result = App::default().run(&mut terminal);
-    disable_raw_mode()?;
-    execute!(
-        terminal.backend_mut(),
-        LeaveAlternateScreen,
-        DisableMouseCapture
-    )?;
-    terminal.show_cursor()?;
-    app_result
+    let variant = args.variant.unwrap_or_default();
+    // the fairy pieces of the variant, and the ones of the user
+    let mut pieces = variant.rules().pieces();
+    if let Some(path) = &args.pieces {
+        let definitions = PieceDefinition::load(path).map_err(io::Error::other)?;
+        info!(
+            "loaded {} fairy pieces from {}",
+            definitions.len(),
+            path.display()
+        );
+        pieces = pieces.extend(definitions).map_err(io::Error::other)?;
+    }
+    let mut start_board = match (&args.fen, &args.chess960) {
+        (Some(fen), _) => {
+            Fen::decode_valid_with(fen, variant, pieces.clone()).map_err(io::Error::other)?
+        }
+        (None, Some(index)) => {
+            let index = match index.as_str() {
+                "random" => random_index(960),
+                index => index
+                    .parse()
+                    .map_err(|_| io::Error::other(format!("invalid chess960 index {index}")))?,
+            };
+            info!("starting from chess960 position {index}");
+            Board::chess960_board(index).map_err(io::Error::other)?
+        }
+        (None, None) => variant.rules().start_position(),
+    };
+    start_board.set_variant(variant);
+    start_board.set_piece_set(pieces.clone());
+    let tablebase = args
+        .syzygy
+        .map(|path| Tablebase::open(path).map_err(io::Error::other))
+        .transpose()?;
+    let params = args
+        .params
+        .map(|path| EvalParams::load(path).map_err(io::Error::other))
+        .transpose()?
+        .unwrap_or_default();
+    let network = args
+        .nnue
+        .map(|path| Network::load(path).map_err(io::Error::other))
+        .transpose()?;
+
+    let command = args.command.unwrap_or(Command::Tui {
+        resume: None,
+        time_control: None,
+        config: None,
+        theme: None,
+        glyphs: None,
+        puzzles: None,
+        replay: None,
+    });
+    match command {
+        Command::Tui {
+            resume,
+            time_control,
+            config,
+            theme,
+            glyphs,
+            puzzles,
+            replay,
+        } => {
+            let time_control = parse_time_control(time_control)?;
+            let mut app = App::new(start_board, time_control);
+            app.set_theme(
+                Theme::resolve(config.as_deref(), theme, glyphs).map_err(io::Error::other)?,
+            );
+            app.set_analysis_options(AnalysisOptions {
+                params,
+                network: network.map(Arc::new),
+                ..Default::default()
+            });
+            if let Some(tablebase) = tablebase {
+                app.set_tablebase(tablebase);
+            }
+            if let Some(path) = puzzles {
+                let puzzles = Puzzle::load_all(path).map_err(io::Error::other)?;
+                info!("loaded {} puzzles", puzzles.len());
+                let rating = PuzzleRating::load(RATING_FILE).map_err(io::Error::other)?;
+                app.set_puzzles(puzzles, rating);
+            }
+            if let Some(path) = resume {
+                app.resume(Game::load(path).map_err(io::Error::other)?);
+            } else if let Some(path) = replay {
+                app.replay(Replay::load(path).map_err(io::Error::other)?);
+            }
+            app.run_in_terminal()?;
+        }
+        Command::Cli {
+            resume,
+            time_control,
+        } => {
+            let mut game = match resume {
+                Some(path) => Game::load(path).map_err(io::Error::other)?,
+                None => {
+                    let mut game = Game::from_board(start_board);
+                    if let Some(time_control) = parse_time_control(time_control)? {
+                        game.set_time_control(time_control);
+                    }
+                    game
+                }
+            };
+            game.tablebase = tablebase.map(Arc::new);
+            CliDriver::run(game);
+        }
+        Command::Uci { book } => {
+            let book = book
+                .map(|path| OpeningBook::load(path).map_err(io::Error::other))
+                .transpose()?;
+            UciDriver::run(book, tablebase, network, params)?
+        }
+        Command::Book { file, fen } => {
+            let mut board = match fen {
+                Some(fen) => Fen::decode_valid_with(&fen, variant, pieces.clone())
+                    .map_err(io::Error::other)?,
+                None => start_board,
+            };
+            board.set_variant(variant);
+            let book = OpeningBook::load(file).map_err(io::Error::other)?;
+            book_moves(&book, &board);
+        }
+        Command::Perft { depth, fen } => {
+            let mut board = match fen {
+                Some(fen) => Fen::decode_valid_with(&fen, variant, pieces.clone())
+                    .map_err(io::Error::other)?,
+                None => start_board,
+            };
+            board.set_variant(variant);
+            perft(&board, depth);
+        }
+        Command::Analyze {
+            fen,
+            pgn,
+            ply,
+            depth,
+            multi_pv,
+            threads,
+        } => {
+            let board = match (fen, pgn) {
+                (_, Some(path)) => {
+                    let game = Game::load(path).map_err(io::Error::other)?;
+                    game.board_at(ply.unwrap_or(game.history.len()))
+                        .map_err(io::Error::other)?
+                }
+                (Some(fen), None) => Fen::decode_valid_with(&fen, variant, pieces.clone())
+                    .map_err(io::Error::other)?,
+                (None, None) => start_board,
+            };
+            let options = AnalysisOptions {
+                depth,
+                lines: multi_pv,
+                threads,
+                params,
+                network: network.map(Arc::new),
+                tablebase: tablebase.map(Arc::new),
+            };
+            analyze_position(&board, &options);
+        }
+        Command::Review { pgn, depth, out } => {
+            let options = AnalysisOptions {
+                depth,
+                params,
+                network: network.map(Arc::new),
+                tablebase: tablebase.map(Arc::new),
+                ..Default::default()
+            };
+            GameReview::review_file(&pgn, &options, &out).map_err(io::Error::other)?;
+        }
+        Command::Selfplay {
+            out,
+            games,
+            depth,
+            random_plies,
+        } => {
+            let self_play = SelfPlay {
+                options: SelfPlayOptions {
+                    depth,
+                    random_plies,
+                    ..Default::default()
+                },
+                network: network.as_ref(),
+            };
+            self_play
+                .play_games(&start_board, games, &out)
+                .map_err(io::Error::other)?;
+        }
+        Command::Match {
+            first,
+            second,
+            games,
+            openings,
+            pgn,
+            elo0,
+            elo1,
+        } => {
+            let openings = match openings {
+                Some(path) => {
+                    EngineMatch::load_openings(&path, variant).map_err(io::Error::other)?
+                }
+                None => vec![start_board],
+            };
+            let options = MatchOptions {
+                openings,
+                ..Default::default()
+            };
+            let first = first.player().map_err(io::Error::other)?;
+            let second = second.player().map_err(io::Error::other)?;
+            let mut engine_match = EngineMatch::new(first, second, options);
+            engine_match
+                .run(games, &pgn, elo0, elo1)
+                .map_err(io::Error::other)?;
+        }
+        Command::Tune {
+            positions,
+            out,
+            iterations,
+        } => {
+            tune(&positions, &out, iterations).map_err(io::Error::other)?;
+        }
+        Command::Bench { depth } => bench(depth, network.as_ref()),
+        Command::PgnValidate { file } => {
+            if validate_pgn(&file).map_err(io::Error::other)? > 0 {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// good enough to pick a start position, no need for a random number crate
+fn random_index(count: usize) -> usize {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.subsec_nanos());
+    nanos as usize % count
+}
+
+fn parse_time_control(time_control: Option<String>) -> io::Result<Option<TimeControl>> {
+    time_control
+        .map(|tc| TimeControl::decode(&tc).map_err(io::Error::other))
+        .transpose()
 }
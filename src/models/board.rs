@@ -1,8 +1,11 @@
 use super::{
+    error::{BoardError, IllegalReason, MoveError},
     field_content::FieldContent,
     location::Location,
     piece::{ColoredPiece, PieceColor, PieceKind},
+    piece_definition::PieceSet,
     step::{Direction, Step, StepKind},
+    variant_kind::VariantKind,
 };
 
 #[derive(Clone, Debug)]
@@ -11,6 +14,23 @@ pub struct Board {
     pub height: usize,
     fields: Vec<FieldContent>,
     turn: usize,
+    /// plies since the last capture or pawn move, for the fifty-move rule
+    halfmove_clock: usize,
+    /// the square a pawn skipped with its last double step
+    en_passant: Option<Location>,
+    /// Fischer random chess: castling moves are written as the king taking
+    /// its own rook
+    chess960: bool,
+    variant: VariantKind,
+    /// the fairy pieces that may stand on the board
+    pieces: PieceSet,
+    /// checks given by white and black, counted for three-check
+    checks: [usize; 2],
+    /// pieces captured by white and black that may be dropped, in crazyhouse
+    pockets: [Vec<PieceKind>; 2],
+    /// squares of pieces that were promoted, they turn back into pawns when
+    /// they are captured into a pocket
+    promoted: Vec<Location>,
 }
 
 impl Default for Board {
@@ -28,6 +48,14 @@ impl Board {
             width,
             fields,
             turn: 0,
+            halfmove_clock: 0,
+            en_passant: None,
+            chess960: false,
+            variant: VariantKind::Standard,
+            pieces: PieceSet::default(),
+            checks: [0, 0],
+            pockets: [vec![], vec![]],
+            promoted: vec![],
         }
     }
 
@@ -81,8 +109,27 @@ impl Board {
         })
     }
 
+    /// whether the given color still has enough material to deliver mate.
+    /// Follows the common over-the-board convention: a lone king or a king with
+    /// a single bishop or knight cannot win.
+    pub fn has_mating_material(&self, target_color: PieceColor) -> bool {
+        let pieces: Vec<PieceKind> = self
+            .fields
+            .iter()
+            .filter_map(|field| field.get_content())
+            .filter(|(piece, _)| piece.color == target_color && piece.kind != PieceKind::King)
+            .map(|(piece, _)| piece.kind)
+            // pieces in the pocket can still be dropped
+            .chain(self.pocket(target_color).iter().copied())
+            .collect();
+        !matches!(
+            pieces.as_slice(),
+            [] | [PieceKind::Bishop] | [PieceKind::Knight]
+        )
+    }
+
     pub fn locations(&self) -> Vec<Location> {
-        (0..(self.height * self.height))
+        (0..(self.width * self.height))
             .map(|idx| self.revert_compute_location(idx))
             .collect()
     }
@@ -119,6 +166,136 @@ impl Board {
         (x, y)
     }
 
+    /// number of half moves played so far
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    pub fn set_turn(&mut self, turn: usize) {
+        self.turn = turn;
+    }
+
+    pub fn halfmove_clock(&self) -> usize {
+        self.halfmove_clock
+    }
+
+    pub fn set_halfmove_clock(&mut self, halfmove_clock: usize) {
+        self.halfmove_clock = halfmove_clock;
+    }
+
+    pub fn en_passant(&self) -> Option<Location> {
+        self.en_passant
+    }
+
+    pub fn set_en_passant(&mut self, en_passant: Option<Location>) {
+        self.en_passant = en_passant;
+    }
+
+    pub fn is_chess960(&self) -> bool {
+        self.chess960
+    }
+
+    pub fn set_chess960(&mut self, chess960: bool) {
+        self.chess960 = chess960;
+    }
+
+    pub fn variant(&self) -> VariantKind {
+        self.variant
+    }
+
+    pub fn set_variant(&mut self, variant: VariantKind) {
+        self.variant = variant;
+    }
+
+    pub fn piece_set(&self) -> &PieceSet {
+        &self.pieces
+    }
+
+    pub fn set_piece_set(&mut self, pieces: PieceSet) {
+        self.pieces = pieces;
+    }
+
+    pub fn checks_given(&self, color: PieceColor) -> usize {
+        self.checks[color as usize]
+    }
+
+    pub fn set_checks_given(&mut self, color: PieceColor, checks: usize) {
+        self.checks[color as usize] = checks;
+    }
+
+    /// the pieces the given color may drop, strongest first
+    pub fn pocket(&self, color: PieceColor) -> &[PieceKind] {
+        &self.pockets[color as usize]
+    }
+
+    pub fn add_to_pocket(&mut self, color: PieceColor, kind: PieceKind) {
+        let pocket = &mut self.pockets[color as usize];
+        pocket.push(kind);
+        // a fixed order, so that equal pockets are encoded the same
+        pocket.sort_by_key(|kind| match kind {
+            PieceKind::Queen => 0,
+            PieceKind::Rook => 1,
+            PieceKind::Bishop => 2,
+            PieceKind::Knight => 3,
+            PieceKind::Pawn => 4,
+            PieceKind::King => 5,
+            PieceKind::Fairy(symbol) => 6 + *symbol as usize,
+        });
+    }
+
+    pub fn remove_from_pocket(
+        &mut self,
+        color: PieceColor,
+        kind: PieceKind,
+    ) -> Result<(), IllegalReason> {
+        let pocket = &mut self.pockets[color as usize];
+        let index = pocket
+            .iter()
+            .position(|pocket_kind| *pocket_kind == kind)
+            .ok_or(IllegalReason::NotInPocket(kind))?;
+        pocket.remove(index);
+        Ok(())
+    }
+
+    pub fn is_promoted(&self, location: &Location) -> bool {
+        self.promoted.contains(location)
+    }
+
+    pub fn set_promoted(&mut self, location: &Location, promoted: bool) {
+        self.promoted.retain(|other| other != location);
+        if promoted {
+            self.promoted.push(*location);
+        }
+    }
+
+    /// the rank the pieces of the given color start on
+    pub fn back_rank(&self, color: PieceColor) -> usize {
+        match color {
+            PieceColor::White => 0,
+            PieceColor::Black => self.height - 1,
+        }
+    }
+
+    /// The files of the rooks the given color may still castle with. Castling
+    /// rights are kept by the pieces themselves: a king and rooks on the back
+    /// rank that have never moved.
+    pub fn castling_rights(&self, color: PieceColor) -> Vec<usize> {
+        let rank = self.back_rank(color);
+        let king_unmoved = self
+            .find_king(color)
+            .is_some_and(|king| king.y == rank && self.location_piece_unmoved(&king));
+        if !king_unmoved {
+            return vec![];
+        }
+        (0..self.width)
+            .filter(|x| {
+                let location = Location::new(*x, rank);
+                self.location_contains_piece(&location, vec![PieceKind::Rook], color)
+                    && self.location_piece_unmoved(&location)
+            })
+            .collect()
+    }
+
     pub fn turn_color(&self) -> PieceColor {
         if self.turn.is_multiple_of(2) {
             PieceColor::White
@@ -155,6 +332,12 @@ impl Board {
         *field = FieldContent::Empty;
     }
 
+    /// empties the field, e.g. when a piece is blown up in atomic
+    pub fn remove_piece(&mut self, location: &Location) {
+        self.set_field_empty(location);
+        self.set_promoted(location, false);
+    }
+
     pub fn put_piece_on_field_xy(
         &mut self,
         colored_piece: ColoredPiece,
@@ -181,15 +364,64 @@ impl Board {
         self.put_piece_on_field_xy(colored_piece, (location.x, location.y), turn);
     }
 
-    pub fn apply_step_kind(&mut self, step_kind: &StepKind) -> Result<(), String> {
+    pub fn apply_step_kind(&mut self, step_kind: &StepKind) -> Result<(), MoveError> {
+        let mut en_passant = None;
+        let pawn_moves = match step_kind {
+            StepKind::GoTo(step) => {
+                self.location_contains_piece(&step.from, vec![PieceKind::Pawn], self.turn_color())
+            }
+            StepKind::Promote { .. } => true,
+            _ => false,
+        };
+        let captures = self.capture_into_pocket(step_kind);
         match step_kind {
-            StepKind::GoTo(step) => self.apply_step(step)?,
+            StepKind::GoTo(step) => {
+                if self.location_contains_piece(
+                    &step.from,
+                    vec![PieceKind::Pawn],
+                    self.turn_color(),
+                ) {
+                    if Some(step.to) == self.en_passant {
+                        // the captured pawn is next to the moving one
+                        self.set_field_empty(&Location::new(step.to.x, step.from.y));
+                    }
+                    let rules = self.variant.rules();
+                    if step.from.y.abs_diff(step.to.y) == 2
+                        && rules.double_step_en_passant(self.turn_color(), step.from.y)
+                    {
+                        en_passant = Some(Location::new(step.to.x, (step.from.y + step.to.y) / 2));
+                    }
+                }
+                if self.is_promoted(&step.from) {
+                    self.set_promoted(&step.from, false);
+                    self.set_promoted(&step.to, true);
+                }
+                self.apply_step(step)?
+            }
             StepKind::Castle {
                 king_step,
                 rook_step,
             } => {
-                self.apply_step(king_step)?;
-                self.apply_step(rook_step)?;
+                // in chess960 the king may land on the rook's square or stay
+                // where it is, so lift both pieces before placing them
+                let color = self.turn_color();
+                for (step, kind) in [(king_step, PieceKind::King), (rook_step, PieceKind::Rook)] {
+                    if !self.location_contains_piece(&step.from, vec![kind], color) {
+                        return Err(MoveError::Illegal {
+                            step: step_kind.clone(),
+                            reason: IllegalReason::NoPiece(step.from),
+                        });
+                    }
+                }
+                self.set_field_empty(&king_step.from);
+                self.set_field_empty(&rook_step.from);
+                for (step, kind) in [(king_step, PieceKind::King), (rook_step, PieceKind::Rook)] {
+                    self.put_piece_on_location(
+                        ColoredPiece { kind, color },
+                        &step.to,
+                        self.turn + 1,
+                    );
+                }
             }
             StepKind::Promote {
                 step: Step { from, to },
@@ -201,23 +433,101 @@ impl Board {
                         color: self.turn_color(),
                     },
                     to,
-                    self.turn,
+                    self.turn + 1,
                 );
                 self.set_field_empty(from);
+                self.set_promoted(from, false);
+                self.set_promoted(to, true);
+            }
+            StepKind::Drop { piece, to } => {
+                let illegal = |reason| MoveError::Illegal {
+                    step: step_kind.clone(),
+                    reason,
+                };
+                if !self.location_is_emtpy(to) {
+                    return Err(illegal(IllegalReason::Occupied(*to)));
+                }
+                let color = self.turn_color();
+                self.remove_from_pocket(color, *piece).map_err(illegal)?;
+                // dropped pieces count as moved, a dropped rook can't castle
+                self.put_piece_on_location(
+                    ColoredPiece {
+                        kind: *piece,
+                        color,
+                    },
+                    to,
+                    self.turn + 1,
+                );
             }
         }
+        self.en_passant = en_passant;
+        if let Some(target) = step_kind.target().filter(|_| captures) {
+            self.variant.rules().after_capture(self, target);
+        }
         self.turn += 1;
+        self.halfmove_clock = if pawn_moves || captures {
+            0
+        } else {
+            self.halfmove_clock + 1
+        };
+        self.variant.rules().after_step(self);
         Ok(())
     }
 
-    pub fn apply_step(&mut self, step: &Step) -> Result<(), String> {
+    /// Returns whether the step captures a piece. In variants with drops,
+    /// the captured piece goes to the pocket of the capturing side, a promoted
+    /// piece as a pawn
+    fn capture_into_pocket(&mut self, step_kind: &StepKind) -> bool {
+        let step = match step_kind {
+            StepKind::GoTo(step) | StepKind::Promote { step, .. } => step,
+            _ => return false,
+        };
+        let color = self.turn_color();
+        let captured = if !self.location_is_emtpy(&step.to) {
+            step.to
+        } else if Some(step.to) == self.en_passant
+            && self.location_contains_piece(&step.from, vec![PieceKind::Pawn], color)
+        {
+            Location::new(step.to.x, step.from.y)
+        } else {
+            return false;
+        };
+        let Some((piece, _)) = self
+            .field_at_location(&captured)
+            .and_then(FieldContent::get_content)
+        else {
+            return false;
+        };
+        if self.variant.rules().has_drops() && piece.color != color {
+            let kind = if self.is_promoted(&captured) {
+                PieceKind::Pawn
+            } else {
+                piece.kind
+            };
+            self.add_to_pocket(color, kind);
+        }
+        self.set_promoted(&captured, false);
+        true
+    }
+
+    pub fn apply_step(&mut self, step: &Step) -> Result<(), MoveError> {
+        if let Some(outside) = [step.from, step.to]
+            .into_iter()
+            .find(|l| !self.in_bounds(l))
+        {
+            return Err(MoveError::OutOfBounds(outside));
+        }
         let moved_field = self.field_at_location(&step.from);
         if let Some(FieldContent::Occupied { piece, .. }) = moved_field {
-            self.put_piece_on_location(*piece, &step.to, self.turn);
+            // pieces remember the turn they last moved in, 0 means never
+            self.put_piece_on_location(*piece, &step.to, self.turn + 1);
             self.set_field_empty(&step.from);
             Ok(())
         } else {
-            Err(format!("Empty or non-existent field: {:?}", step.from))
+            Err(MoveError::Illegal {
+                step: StepKind::GoTo(*step),
+                reason: IllegalReason::NoPiece(step.from),
+            })
         }
     }
 
@@ -321,4 +631,68 @@ impl Board {
 
         board
     }
+
+    /// One of the 960 start positions of Fischer random chess, numbered as
+    /// proposed by Scharnagl. Position 518 is the standard setup.
+    pub fn chess960_board(index: usize) -> Result<Self, BoardError> {
+        if index >= 960 {
+            return Err(BoardError::Chess960Index(index));
+        }
+        let mut back_rank: [Option<PieceKind>; 8] = [None; 8];
+        let mut rest = index;
+        // bishops on opposite colors
+        back_rank[(rest % 4) * 2 + 1] = Some(PieceKind::Bishop);
+        rest /= 4;
+        back_rank[(rest % 4) * 2] = Some(PieceKind::Bishop);
+        rest /= 4;
+        // the remaining pieces fill the empty squares from left to right
+        let mut place = |nth_empty: usize, kind: PieceKind| {
+            let x = (0..8)
+                .filter(|x| back_rank[*x].is_none())
+                .nth(nth_empty)
+                .expect("enough squares are left");
+            back_rank[x] = Some(kind);
+        };
+        place(rest % 6, PieceKind::Queen);
+        rest /= 6;
+        const KNIGHTS: [(usize, usize); 10] = [
+            (0, 1),
+            (0, 2),
+            (0, 3),
+            (0, 4),
+            (1, 2),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (3, 4),
+        ];
+        let (first, second) = KNIGHTS[rest];
+        // the second knight first, placing the first one shifts the empty squares
+        place(second, PieceKind::Knight);
+        place(first, PieceKind::Knight);
+        place(0, PieceKind::Rook);
+        place(0, PieceKind::King);
+        place(0, PieceKind::Rook);
+
+        let mut board = Self::new(8, 8);
+        for (x, kind) in back_rank.into_iter().enumerate() {
+            let kind = kind.expect("all squares of the back rank are filled");
+            for (color, pawn_rank, piece_rank) in
+                [(PieceColor::White, 1, 0), (PieceColor::Black, 6, 7)]
+            {
+                board.put_piece_on_field_xy(ColoredPiece { kind, color }, (x, piece_rank), 0);
+                board.put_piece_on_field_xy(
+                    ColoredPiece {
+                        kind: PieceKind::Pawn,
+                        color,
+                    },
+                    (x, pawn_rank),
+                    0,
+                );
+            }
+        }
+        board.chess960 = true;
+        Ok(board)
+    }
 }
@@ -0,0 +1,273 @@
+use std::time::Duration;
+
+use super::{
+    error::{ParseError, TimeControlError},
+    piece::PieceColor,
+};
+
+/// Time that is handed back to a player after completing a move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeBonus {
+    None,
+    /// a fixed amount is added after every move
+    Fischer(Duration),
+    /// the time spent on a move is given back, up to the given amount
+    Bronstein(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimePeriod {
+    /// number of moves to be played in this period, `None` if the period
+    /// lasts until the end of the game
+    pub moves: Option<usize>,
+    pub time: Duration,
+    pub bonus: TimeBonus,
+}
+
+/// A sequence of time periods, e.g. 40 moves in 90 minutes, then 30 minutes
+/// for the rest of the game. A last period with a move count repeats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeControl {
+    periods: Vec<TimePeriod>,
+}
+
+#[derive(Debug, Clone)]
+struct SideClock {
+    remaining: Duration,
+    period: usize,
+    moves_in_period: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct Clock {
+    control: TimeControl,
+    white: SideClock,
+    black: SideClock,
+    active: PieceColor,
+    // time spent on the current move, needed for the Bronstein delay
+    move_time: Duration,
+}
+
+impl TimeControl {
+    /// fails without periods, or if a period before the last one has no move
+    /// count and would never end
+    pub fn new(periods: Vec<TimePeriod>) -> Result<Self, TimeControlError> {
+        let Some((_, earlier)) = periods.split_last() else {
+            return Err(TimeControlError::NoPeriods);
+        };
+        match earlier.iter().position(|period| period.moves.is_none()) {
+            Some(index) => Err(TimeControlError::EndlessPeriod(index)),
+            None => Ok(Self { periods }),
+        }
+    }
+
+    pub fn periods(&self) -> &[TimePeriod] {
+        &self.periods
+    }
+
+    pub fn fischer(base: Duration, increment: Duration) -> Self {
+        let bonus = if increment.is_zero() {
+            TimeBonus::None
+        } else {
+            TimeBonus::Fischer(increment)
+        };
+        Self {
+            periods: vec![TimePeriod {
+                moves: None,
+                time: base,
+                bonus,
+            }],
+        }
+    }
+
+    pub fn bronstein(base: Duration, delay: Duration) -> Self {
+        Self {
+            periods: vec![TimePeriod {
+                moves: None,
+                time: base,
+                bonus: TimeBonus::Bronstein(delay),
+            }],
+        }
+    }
+
+    /// Encodes the time control like the PGN `TimeControl` tag, using seconds.
+    /// A Bronstein delay is written with a `d`, e.g. `300d3`.
+    pub fn encode(&self) -> String {
+        self.periods
+            .iter()
+            .map(|period| {
+                let mut res = String::new();
+                if let Some(moves) = period.moves {
+                    res += &format!("{moves}/");
+                }
+                res += &encode_seconds(period.time);
+                match period.bonus {
+                    TimeBonus::None => {}
+                    TimeBonus::Fischer(inc) => res += &format!("+{}", encode_seconds(inc)),
+                    TimeBonus::Bronstein(delay) => res += &format!("d{}", encode_seconds(delay)),
+                }
+                res
+            })
+            .collect::<Vec<String>>()
+            .join(":")
+    }
+
+    // given an encoding such as 40/5400+30:1800+30, compute the time control
+    pub fn decode(encoded: &str) -> Result<Self, TimeControlError> {
+        let mut periods = vec![];
+        let mut offset = 0;
+        for descr in encoded.split(':') {
+            // the offset of a part of the period within the whole encoding
+            let at = |part: &str| offset + (part.as_ptr() as usize - descr.as_ptr() as usize);
+            let (moves, rest) = match descr.split_once('/') {
+                Some((moves, rest)) => (
+                    Some(
+                        moves
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|_| ParseError::new(encoded, at(moves), "a move count"))?,
+                    ),
+                    rest,
+                ),
+                None => (None, descr),
+            };
+            let seconds = |part: &str| {
+                decode_seconds(part).ok_or_else(|| ParseError::new(encoded, at(part), "seconds"))
+            };
+            let (time, bonus) = if let Some((time, inc)) = rest.split_once('+') {
+                (time, TimeBonus::Fischer(seconds(inc)?))
+            } else if let Some((time, delay)) = rest.split_once('d') {
+                (time, TimeBonus::Bronstein(seconds(delay)?))
+            } else {
+                (rest, TimeBonus::None)
+            };
+            periods.push(TimePeriod {
+                moves,
+                time: seconds(time)?,
+                bonus,
+            });
+            offset += descr.len() + 1;
+        }
+        Self::new(periods)
+    }
+}
+
+fn encode_seconds(duration: Duration) -> String {
+    if duration.subsec_millis() == 0 {
+        duration.as_secs().to_string()
+    } else {
+        format!("{}", duration.as_secs_f64())
+    }
+}
+
+fn decode_seconds(input: &str) -> Option<Duration> {
+    input
+        .trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+/// formats as h:mm:ss, m:ss or, when running low, as seconds with tenths
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else if secs >= 10 {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    } else {
+        format!("{}.{}", secs, duration.subsec_millis() / 100)
+    }
+}
+
+impl Clock {
+    /// the clock of the given color, the side to move, runs first
+    pub fn new(control: TimeControl, active: PieceColor) -> Self {
+        let side = SideClock {
+            remaining: control.periods[0].time,
+            period: 0,
+            moves_in_period: 0,
+        };
+        Self {
+            control,
+            white: side.clone(),
+            black: side,
+            active,
+            move_time: Duration::ZERO,
+        }
+    }
+
+    pub fn control(&self) -> &TimeControl {
+        &self.control
+    }
+
+    pub fn active(&self) -> PieceColor {
+        self.active
+    }
+
+    pub fn remaining(&self, color: PieceColor) -> Duration {
+        self.side(color).remaining
+    }
+
+    /// overwrite the remaining time of a player, e.g. when resuming a game
+    pub fn set_remaining(&mut self, color: PieceColor, remaining: Duration) {
+        self.side_mut(color).remaining = remaining;
+    }
+
+    /// the side whose flag has fallen, if any
+    pub fn flagged(&self) -> Option<PieceColor> {
+        [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .find(|color| self.remaining(*color).is_zero())
+    }
+
+    /// let time pass for the player to move
+    pub fn tick(&mut self, elapsed: Duration) {
+        if self.flagged().is_some() {
+            return;
+        }
+        self.move_time += elapsed;
+        let side = self.side_mut(self.active);
+        side.remaining = side.remaining.saturating_sub(elapsed);
+    }
+
+    /// the player to move completed their move: apply the bonus, advance to the
+    /// next period if needed and hand the clock over to the opponent
+    pub fn press(&mut self) {
+        if self.flagged().is_some() {
+            return;
+        }
+        let move_time = self.move_time;
+        let periods = self.control.periods.clone();
+        let side = self.side_mut(self.active);
+        let period = &periods[side.period];
+        match period.bonus {
+            TimeBonus::None => {}
+            TimeBonus::Fischer(inc) => side.remaining += inc,
+            TimeBonus::Bronstein(delay) => side.remaining += move_time.min(delay),
+        }
+        side.moves_in_period += 1;
+        if period.moves == Some(side.moves_in_period) {
+            side.period = (side.period + 1).min(periods.len() - 1);
+            side.moves_in_period = 0;
+            side.remaining += periods[side.period].time;
+        }
+        self.active = self.active.invert();
+        self.move_time = Duration::ZERO;
+    }
+
+    fn side(&self, color: PieceColor) -> &SideClock {
+        match color {
+            PieceColor::White => &self.white,
+            PieceColor::Black => &self.black,
+        }
+    }
+
+    fn side_mut(&mut self, color: PieceColor) -> &mut SideClock {
+        match color {
+            PieceColor::White => &mut self.white,
+            PieceColor::Black => &mut self.black,
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::{error::Error, time::Duration};
+
+use super::{
+    clock::{Clock, TimeBonus, TimeControl, TimePeriod},
+    error::{MoveError, ParseError, TimeControlError},
+    piece::PieceColor,
+};
+use crate::{
+    driver::game::Game,
+    models::game_state::GameState,
+    utils::{board_creation::BoardCreation, fen::Fen},
+};
+
+#[test]
+fn fischer_increment() {
+    // arrange
+    let mut clock = Clock::new(
+        TimeControl::fischer(Duration::from_secs(180), Duration::from_secs(2)),
+        PieceColor::White,
+    );
+    // action
+    clock.tick(Duration::from_secs(10));
+    clock.press();
+    // assert
+    assert_eq!(clock.remaining(PieceColor::White), Duration::from_secs(172));
+    assert_eq!(clock.remaining(PieceColor::Black), Duration::from_secs(180));
+    assert_eq!(clock.active(), PieceColor::Black);
+}
+
+#[test]
+fn bronstein_delay() {
+    // arrange
+    let mut clock = Clock::new(
+        TimeControl::bronstein(Duration::from_secs(60), Duration::from_secs(5)),
+        PieceColor::White,
+    );
+    // action
+    clock.tick(Duration::from_secs(3));
+    clock.press();
+    clock.tick(Duration::from_secs(8));
+    clock.press();
+    // assert
+    assert_eq!(clock.remaining(PieceColor::White), Duration::from_secs(60));
+    assert_eq!(clock.remaining(PieceColor::Black), Duration::from_secs(57));
+}
+
+#[test]
+fn multi_period() {
+    // arrange
+    let control = TimeControl::decode("2/60:30+1").unwrap();
+    let mut clock = Clock::new(control, PieceColor::White);
+    // action
+    for _ in 0..4 {
+        clock.tick(Duration::from_secs(10));
+        clock.press();
+    }
+    // assert
+    assert_eq!(clock.remaining(PieceColor::White), Duration::from_secs(70));
+    assert_eq!(clock.remaining(PieceColor::Black), Duration::from_secs(70));
+}
+
+#[test]
+fn decode_encode_roundtrip() {
+    // arrange
+    let input = "40/5400+30:1800d5";
+    // action
+    let control = TimeControl::decode(input).unwrap();
+    // assert
+    assert_eq!(
+        control.periods()[0],
+        TimePeriod {
+            moves: Some(40),
+            time: Duration::from_secs(5400),
+            bonus: TimeBonus::Fischer(Duration::from_secs(30)),
+        }
+    );
+    assert_eq!(control.encode(), input);
+}
+
+#[test]
+fn time_control_needs_periods() {
+    // arrange
+    let period = |moves| TimePeriod {
+        moves,
+        time: Duration::from_secs(60),
+        bonus: TimeBonus::None,
+    };
+    // action
+    let empty = TimeControl::new(vec![]);
+    let endless_first = TimeControl::new(vec![period(None), period(Some(40))]);
+    let repeating = TimeControl::new(vec![period(Some(40))]);
+    // assert
+    assert_eq!(empty, Err(TimeControlError::NoPeriods));
+    assert_eq!(endless_first, Err(TimeControlError::EndlessPeriod(0)));
+    assert!(repeating.is_ok());
+    assert_eq!(
+        TimeControl::decode(""),
+        Err(TimeControlError::Parse(ParseError::new("", 0, "seconds")))
+    );
+    assert_eq!(
+        TimeControl::decode("300:40/60"),
+        Err(TimeControlError::EndlessPeriod(0))
+    );
+    assert_eq!(
+        TimeControl::decode("40/300:x/60"),
+        Err(TimeControlError::Parse(ParseError::new(
+            "40/300:x/60",
+            7,
+            "a move count"
+        )))
+    );
+}
+
+#[test]
+fn black_to_move_starts_the_black_clock() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1")?);
+    game.set_time_control(TimeControl::fischer(
+        Duration::from_secs(60),
+        Duration::ZERO,
+    ));
+    // action
+    game.tick(Duration::from_secs(10));
+    // assert
+    assert_eq!(
+        game.remaining_time(PieceColor::White),
+        Some(Duration::from_secs(60))
+    );
+    assert_eq!(
+        game.remaining_time(PieceColor::Black),
+        Some(Duration::from_secs(50))
+    );
+    game.apply_input("e8d8")?;
+    game.tick(Duration::from_secs(5));
+    assert_eq!(
+        game.remaining_time(PieceColor::White),
+        Some(Duration::from_secs(55))
+    );
+    Ok(())
+}
+
+#[test]
+fn flag_fall() {
+    // arrange
+    let mut game =
+        Game::with_time_control(TimeControl::fischer(Duration::from_secs(1), Duration::ZERO));
+    // action
+    let state = game.tick(Duration::from_secs(2));
+    // assert
+    assert_eq!(state, GameState::WonOnTime(PieceColor::Black));
+    assert_eq!(
+        game.apply_input("e2e4"),
+        Err(MoveError::GameOver(GameState::WonOnTime(PieceColor::Black)))
+    );
+}
+
+#[test]
+fn flag_fall_insufficient_material() {
+    // arrange
+    let mut game =
+        Game::with_time_control(TimeControl::fischer(Duration::from_secs(1), Duration::ZERO));
+    game.board = BoardCreation::from_description("a1kw\nh8kb\nh7nb".to_string()).unwrap();
+    // action
+    let state = game.tick(Duration::from_secs(2));
+    // assert
+    assert_eq!(state, GameState::TimeoutDraw);
+}
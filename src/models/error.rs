@@ -0,0 +1,753 @@
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    game_state::GameState,
+    location::Location,
+    piece::{PieceColor, PieceKind},
+    step::StepKind,
+};
+
+/// Input that could not be parsed, e.g. a square, a piece or a move
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub input: String,
+    /// the index of the char where parsing failed
+    pub position: usize,
+    /// what was expected there
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(input: &str, position: usize, message: &str) -> Self {
+        Self {
+            input: input.to_string(),
+            position,
+            message: message.to_string(),
+        }
+    }
+
+    /// the error for a part of a longer input, starting at the offset
+    pub fn within(self, input: &str, offset: usize) -> Self {
+        Self {
+            input: input.to_string(),
+            position: self.position + offset,
+            message: self.message,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at position {} of '{}'",
+            self.message, self.position, self.input
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A file that could not be read or written
+#[derive(Debug)]
+pub struct FileError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl FileError {
+    pub fn new(path: &Path, error: io::Error) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+
+    pub fn read(path: &Path) -> Result<Vec<u8>, Self> {
+        fs::read(path).map_err(|e| Self::new(path, e))
+    }
+
+    pub fn read_to_string(path: &Path) -> Result<String, Self> {
+        fs::read_to_string(path).map_err(|e| Self::new(path, e))
+    }
+
+    pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<(), Self> {
+        fs::write(path, contents).map_err(|e| Self::new(path, e))
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl std::error::Error for FileError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Why the side to move may not play a step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IllegalReason {
+    /// there is no piece on the square the step starts from
+    NoPiece(Location),
+    /// the piece on the square belongs to the opponent
+    OpponentsPiece(Location),
+    /// the piece doesn't move like that
+    Unreachable,
+    /// the own king would be in check afterwards
+    LeavesKingInCheck,
+    /// the variant forbids it, e.g. not capturing in antichess
+    ForbiddenByVariant,
+    /// a piece to drop that is not in the pocket
+    NotInPocket(PieceKind),
+    /// a drop on a square that is not empty
+    Occupied(Location),
+}
+
+impl fmt::Display for IllegalReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPiece(location) => write!(f, "there is no piece on {}", location.encode()),
+            Self::OpponentsPiece(location) => write!(
+                f,
+                "the piece on {} belongs to the opponent",
+                location.encode()
+            ),
+            Self::Unreachable => write!(f, "the piece can't move there"),
+            Self::LeavesKingInCheck => write!(f, "the king would be in check"),
+            Self::ForbiddenByVariant => write!(f, "the rules of the variant forbid it"),
+            Self::NotInPocket(kind) => write!(f, "there is no {kind:?} in the pocket"),
+            Self::Occupied(location) => write!(f, "{} is occupied", location.encode()),
+        }
+    }
+}
+
+/// Why a move could not be played
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveError {
+    Parse(ParseError),
+    /// a square that is not on the board
+    OutOfBounds(Location),
+    Illegal {
+        step: StepKind,
+        reason: IllegalReason,
+    },
+    /// no legal move is written like the input
+    NoMatch(String),
+    /// more than one legal move is written like the input
+    Ambiguous(String),
+    /// the game ended, no more moves can be played
+    GameOver(GameState),
+}
+
+impl fmt::Display for MoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid move: {e}"),
+            Self::OutOfBounds(location) => {
+                write!(f, "{} is not on the board", location.encode())
+            }
+            Self::Illegal { step, reason } => {
+                write!(f, "illegal move {}: {reason}", step.encode_uci())
+            }
+            Self::NoMatch(input) => write!(f, "no legal move matches {input}"),
+            Self::Ambiguous(input) => write!(f, "ambiguous move {input}"),
+            Self::GameOver(state) => write!(f, "the game is already over: {}", state.message()),
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+impl From<ParseError> for MoveError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Why a position can't come up in a game
+#[derive(Debug, Clone, PartialEq)]
+pub enum PositionError {
+    /// a side has more or less kings than the variant allows
+    KingCount { color: PieceColor, count: usize },
+    /// a pawn stands on the first or last rank
+    PawnOnBackRank(Location),
+    /// the side that just moved left its king in check
+    OpponentInCheck(PieceColor),
+    /// the side to move is checked by more pieces than a single move can
+    /// give check with
+    TooManyCheckers { color: PieceColor, count: usize },
+    /// the king may castle with more than one rook to the same side
+    CastlingRights(PieceColor),
+    /// outside of chess960, castling needs the king and rooks on the squares
+    /// they start from
+    CastlingSquares(PieceColor),
+    /// two pieces check the king from opposite sides, no single move can
+    /// give both checks
+    ImpossibleCheck(PieceColor),
+    /// no pawn can have skipped the en passant square with its last move
+    EnPassant(Location),
+    /// a fairy piece that is not in the piece set of the board
+    UnknownPiece(char),
+}
+
+impl fmt::Display for PositionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KingCount { color, count } => write!(f, "{color:?} has {count} kings"),
+            Self::PawnOnBackRank(location) => {
+                write!(f, "pawn on the first or last rank at {}", location.encode())
+            }
+            Self::OpponentInCheck(color) => {
+                write!(f, "{color:?} is in check but it is not their move")
+            }
+            Self::TooManyCheckers { color, count } => {
+                write!(f, "{color:?} is checked by {count} pieces at once")
+            }
+            Self::CastlingRights(color) => write!(
+                f,
+                "{color:?} may castle with more than one rook to the same side"
+            ),
+            Self::CastlingSquares(color) => write!(
+                f,
+                "{color:?} may castle with a king or rook off its starting square"
+            ),
+            Self::ImpossibleCheck(color) => {
+                write!(f, "{color:?} is checked from opposite sides at once")
+            }
+            Self::EnPassant(location) => write!(
+                f,
+                "no pawn can have skipped the en passant square {}",
+                location.encode()
+            ),
+            Self::UnknownPiece(symbol) => write!(f, "unknown piece {symbol}"),
+        }
+    }
+}
+
+impl std::error::Error for PositionError {}
+
+/// Why a board could not be set up from a description
+#[derive(Debug, Clone, PartialEq)]
+pub enum BoardError {
+    Parse(ParseError),
+    /// a piece placed outside of the board
+    OutOfBounds(Location),
+    Invalid(PositionError),
+    /// chess960 positions are numbered 0 to 959
+    Chess960Index(usize),
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::OutOfBounds(location) => {
+                write!(f, "{} is not on the board", location.encode())
+            }
+            Self::Invalid(e) => write!(f, "invalid position: {e}"),
+            Self::Chess960Index(index) => {
+                write!(f, "chess960 positions are numbered 0 to 959, got {index}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+impl From<ParseError> for BoardError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<PositionError> for BoardError {
+    fn from(e: PositionError) -> Self {
+        Self::Invalid(e)
+    }
+}
+
+/// Why a game could not be read from PGN
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgnError {
+    /// a malformed tag or movetext, e.g. an unbalanced variation
+    Parse(ParseError),
+    /// a tag with a value that is not understood
+    InvalidTag { name: String, value: String },
+    /// the position of the `FEN` tag
+    Board(BoardError),
+    /// the move after the given number of plies can't be played
+    Move { ply: usize, error: MoveError },
+    /// the input holds no game at all
+    NoGame,
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid PGN: {e}"),
+            Self::InvalidTag { name, value } => write!(f, "invalid {name} tag: {value}"),
+            Self::Board(e) => write!(f, "invalid FEN tag: {e}"),
+            Self::Move { ply, error } => write!(f, "ply {} of the movetext: {error}", ply + 1),
+            Self::NoGame => write!(f, "there is no game in the PGN"),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            Self::Board(e) => Some(e),
+            Self::Move { error, .. } => Some(error),
+            Self::InvalidTag { .. } | Self::NoGame => None,
+        }
+    }
+}
+
+impl From<ParseError> for PgnError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<BoardError> for PgnError {
+    fn from(e: BoardError) -> Self {
+        Self::Board(e)
+    }
+}
+
+/// Why an action of a player or on the game file failed
+#[derive(Debug)]
+pub enum GameError {
+    /// includes moves after the game is over
+    Move(MoveError),
+    /// the color offered a draw already
+    AlreadyOffered(PieceColor),
+    /// the opponent of the color has not offered a draw
+    NoDrawOffer(PieceColor),
+    /// both players have moved already
+    CannotAbort,
+    /// the game has fewer plies than asked for
+    PlyOutOfRange {
+        ply: usize,
+        plies: usize,
+    },
+    /// a new game can't start before the current one ended
+    InProgress,
+    File(FileError),
+    Pgn(PgnError),
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Move(e) => write!(f, "{e}"),
+            Self::AlreadyOffered(color) => write!(f, "{color:?} already offered a draw"),
+            Self::NoDrawOffer(color) => write!(f, "{color:?} has no draw offer to answer"),
+            Self::CannotAbort => write!(f, "the game can't be aborted after both players moved"),
+            Self::PlyOutOfRange { ply, plies } => {
+                write!(f, "the game has only {plies} plies, not {ply}")
+            }
+            Self::InProgress => write!(f, "end the current game first"),
+            Self::File(e) => write!(f, "{e}"),
+            Self::Pgn(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for GameError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Move(e) => Some(e),
+            Self::File(e) => Some(e),
+            Self::Pgn(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<MoveError> for GameError {
+    fn from(e: MoveError) -> Self {
+        Self::Move(e)
+    }
+}
+
+impl From<FileError> for GameError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+impl From<PgnError> for GameError {
+    fn from(e: PgnError) -> Self {
+        Self::Pgn(e)
+    }
+}
+
+/// Why a time control could not be set up
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimeControlError {
+    Parse(ParseError),
+    NoPeriods,
+    /// a period before the last one without a move count would never end
+    EndlessPeriod(usize),
+}
+
+impl fmt::Display for TimeControlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(e) => write!(f, "invalid time control: {e}"),
+            Self::NoPeriods => write!(f, "a time control needs at least one period"),
+            Self::EndlessPeriod(index) => write!(
+                f,
+                "period {} has no move count, only the last period may last until the end of the game",
+                index + 1
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TimeControlError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ParseError> for TimeControlError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Why fairy pieces could not be defined
+#[derive(Debug)]
+pub enum PieceError {
+    File(FileError),
+    /// a malformed line, value or move of a piece file
+    Parse(ParseError),
+    MissingSetting {
+        piece: String,
+        key: String,
+    },
+    UnknownSetting {
+        piece: String,
+        key: String,
+    },
+    /// not an upper case letter, or the letter of a standard piece
+    InvalidSymbol {
+        piece: String,
+        symbol: char,
+    },
+    /// another piece of the set has the symbol already
+    SymbolTaken {
+        piece: String,
+        symbol: char,
+        taken_by: String,
+    },
+}
+
+impl fmt::Display for PieceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "invalid piece definition: {e}"),
+            Self::MissingSetting { piece, key } => write!(f, "{piece} is missing '{key}'"),
+            Self::UnknownSetting { piece, key } => {
+                write!(f, "unknown piece setting {key} for {piece}")
+            }
+            Self::InvalidSymbol { piece, symbol } => write!(
+                f,
+                "invalid symbol {symbol} for {piece}, expected an upper case letter other than PNBRQK"
+            ),
+            Self::SymbolTaken {
+                piece,
+                symbol,
+                taken_by,
+            } => write!(
+                f,
+                "{piece} can't use the symbol {symbol}, it is taken by {taken_by}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PieceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FileError> for PieceError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+impl From<ParseError> for PieceError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+/// Why a text data file, e.g. a theme, evaluation parameters, puzzles or
+/// openings, could not be read or written
+#[derive(Debug)]
+pub enum DataError {
+    File(FileError),
+    /// a malformed line or value, e.g. no `key = value` pair
+    Parse(ParseError),
+    UnknownKey(String),
+    MissingKey(String),
+    /// a position of the file
+    Board(BoardError),
+}
+
+impl fmt::Display for DataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(e) => write!(f, "{e}"),
+            Self::Parse(e) => write!(f, "{e}"),
+            Self::UnknownKey(key) => write!(f, "unknown setting {key}"),
+            Self::MissingKey(key) => write!(f, "missing setting {key}"),
+            Self::Board(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for DataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(e) => Some(e),
+            Self::Parse(e) => Some(e),
+            Self::Board(e) => Some(e),
+            Self::UnknownKey(_) | Self::MissingKey(_) => None,
+        }
+    }
+}
+
+impl From<FileError> for DataError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+impl From<ParseError> for DataError {
+    fn from(e: ParseError) -> Self {
+        Self::Parse(e)
+    }
+}
+
+impl From<BoardError> for DataError {
+    fn from(e: BoardError) -> Self {
+        Self::Board(e)
+    }
+}
+
+/// Why an opening book could not be read
+#[derive(Debug)]
+pub enum BookError {
+    File(FileError),
+    /// the size in bytes is no multiple of the entry size
+    Size(usize),
+}
+
+impl fmt::Display for BookError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(e) => write!(f, "{e}"),
+            Self::Size(size) => write!(f, "a book consists of 16 byte entries, got {size} bytes"),
+        }
+    }
+}
+
+impl std::error::Error for BookError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(e) => Some(e),
+            Self::Size(_) => None,
+        }
+    }
+}
+
+impl From<FileError> for BookError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+/// Why a network could not be read or built
+#[derive(Debug)]
+pub enum NetworkError {
+    File(FileError),
+    /// the magic bytes are missing
+    NotANetwork,
+    UnsupportedVersion(u32),
+    /// the file is too short or too long for its hidden layer
+    Size {
+        expected: usize,
+        found: usize,
+    },
+    /// the hidden layer size is no positive multiple of the SIMD width
+    HiddenSize(usize),
+    /// the number of weights doesn't match the hidden layer
+    Weights {
+        hidden: usize,
+    },
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::File(e) => write!(f, "{e}"),
+            Self::NotANetwork => write!(f, "not a network file"),
+            Self::UnsupportedVersion(version) => write!(f, "unsupported network version {version}"),
+            Self::Size { expected, found } => {
+                write!(f, "expected a network of {expected} bytes, found {found}")
+            }
+            Self::HiddenSize(size) => write!(
+                f,
+                "hidden layer size {size} is not a positive multiple of the SIMD width"
+            ),
+            Self::Weights { hidden } => {
+                write!(f, "weights don't match a hidden layer of {hidden} neurons")
+            }
+        }
+    }
+}
+
+impl std::error::Error for NetworkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::File(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<FileError> for NetworkError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
+
+/// Why a move of a puzzle could not be played
+#[derive(Debug, Clone, PartialEq)]
+pub enum PuzzleError {
+    /// a move of the user or of the solution
+    Move(MoveError),
+    /// the puzzle was solved or failed already
+    Over,
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Move(e) => write!(f, "{e}"),
+            Self::Over => write!(f, "the puzzle is over"),
+        }
+    }
+}
+
+impl std::error::Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Move(e) => Some(e),
+            Self::Over => None,
+        }
+    }
+}
+
+impl From<MoveError> for PuzzleError {
+    fn from(e: MoveError) -> Self {
+        Self::Move(e)
+    }
+}
+
+/// Why an engine of a match could not start or play
+#[derive(Debug)]
+pub enum EngineError {
+    /// the settings of the engine, its parameters or openings
+    Data(DataError),
+    Network(NetworkError),
+    /// starting or talking to the engine process failed
+    Io {
+        engine: String,
+        error: io::Error,
+    },
+    /// the engine process ended before it answered
+    Quit(String),
+    /// the engine answered without a move
+    NoMove(String),
+    /// the engine chose a move that can't be played
+    Move(MoveError),
+    /// the match has no openings to play
+    NoOpenings,
+    /// the games could not be written
+    File(FileError),
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Data(e) => write!(f, "{e}"),
+            Self::Network(e) => write!(f, "{e}"),
+            Self::Io { engine, error } => write!(f, "could not talk to {engine}: {error}"),
+            Self::Quit(engine) => write!(f, "{engine} quit unexpectedly"),
+            Self::NoMove(engine) => write!(f, "{engine} sent no move"),
+            Self::Move(e) => write!(f, "{e}"),
+            Self::NoOpenings => write!(f, "no openings to play"),
+            Self::File(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Data(e) => Some(e),
+            Self::Network(e) => Some(e),
+            Self::Io { error, .. } => Some(error),
+            Self::Move(e) => Some(e),
+            Self::File(e) => Some(e),
+            Self::Quit(_) | Self::NoMove(_) | Self::NoOpenings => None,
+        }
+    }
+}
+
+impl From<DataError> for EngineError {
+    fn from(e: DataError) -> Self {
+        Self::Data(e)
+    }
+}
+
+impl From<NetworkError> for EngineError {
+    fn from(e: NetworkError) -> Self {
+        Self::Network(e)
+    }
+}
+
+impl From<MoveError> for EngineError {
+    fn from(e: MoveError) -> Self {
+        Self::Move(e)
+    }
+}
+
+impl From<FileError> for EngineError {
+    fn from(e: FileError) -> Self {
+        Self::File(e)
+    }
+}
@@ -1,10 +1,34 @@
 use super::piece::PieceColor;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone, Copy)]
 pub enum GameState {
     Ongoing,
     Stalemate,
+    /// the same position occurred for the third time
+    Repetition,
+    /// fifty moves of each side passed without a capture or pawn move
+    FiftyMoves,
+    /// neither side has the material left to win
+    InsufficientMaterial,
     Won(PieceColor),
+    /// the opponent of the given color ran out of time
+    WonOnTime(PieceColor),
+    /// a flag fell, but the opponent has no mating material left
+    TimeoutDraw,
+    /// the given color resigned
+    Resigned(PieceColor),
+    /// the given color lost by breaking the rules, e.g. an engine that
+    /// played an illegal move
+    Forfeit(PieceColor),
+    DrawAgreed,
+    /// the game was called off before both players moved
+    Aborted,
+    /// the endgame tablebases show that the given color wins
+    TablebaseWin(PieceColor),
+    /// the endgame tablebases show that neither side can win
+    TablebaseDraw,
+    /// an engine match called the game a draw, because it went on too long
+    AdjudicatedDraw,
 }
 
 impl GameState {
@@ -12,7 +36,89 @@ impl GameState {
         match self {
             GameState::Ongoing => "Game is still ongoing".to_string(),
             GameState::Stalemate => "Game ended in a draw because of stalemate".to_string(),
+            GameState::Repetition => {
+                "Game ended in a draw because of threefold repetition".to_string()
+            }
+            GameState::FiftyMoves => {
+                "Game ended in a draw because of the fifty-move rule".to_string()
+            }
+            GameState::InsufficientMaterial => {
+                "Game ended in a draw because of insufficient material".to_string()
+            }
             GameState::Won(piece_color) => format!("{:?} has won the game", piece_color),
+            GameState::WonOnTime(piece_color) => {
+                format!("{:?} has won the game on time", piece_color)
+            }
+            GameState::TimeoutDraw => {
+                "Game ended in a draw: time ran out, but there is no mating material left"
+                    .to_string()
+            }
+            GameState::Resigned(piece_color) => format!(
+                "{:?} resigned, {:?} has won the game",
+                piece_color,
+                piece_color.invert()
+            ),
+            GameState::Forfeit(piece_color) => format!(
+                "{:?} broke the rules, {:?} has won the game",
+                piece_color,
+                piece_color.invert()
+            ),
+            GameState::DrawAgreed => "Game ended in a draw by agreement".to_string(),
+            GameState::Aborted => "Game was aborted".to_string(),
+            GameState::TablebaseWin(piece_color) => {
+                format!(
+                    "{:?} has won the game, the tablebase shows a forced win",
+                    piece_color
+                )
+            }
+            GameState::TablebaseDraw => {
+                "Game ended in a draw, the tablebase shows neither side can win".to_string()
+            }
+            GameState::AdjudicatedDraw => "Game was adjudicated a draw".to_string(),
         }
     }
+
+    /// the result as written in PGN
+    pub fn result(&self) -> &'static str {
+        match self.winner() {
+            Some(PieceColor::White) => "1-0",
+            Some(PieceColor::Black) => "0-1",
+            None if self.is_over() && *self != GameState::Aborted => "1/2-1/2",
+            None => "*",
+        }
+    }
+
+    /// how the game ended as written in the PGN `Termination` tag, `None`
+    /// while it goes on
+    pub fn termination(&self) -> Option<&'static str> {
+        match self {
+            GameState::Ongoing => None,
+            GameState::Stalemate
+            | GameState::Repetition
+            | GameState::FiftyMoves
+            | GameState::InsufficientMaterial
+            | GameState::Won(_)
+            | GameState::Resigned(_)
+            | GameState::DrawAgreed => Some("normal"),
+            GameState::WonOnTime(_) | GameState::TimeoutDraw => Some("time forfeit"),
+            GameState::Forfeit(_) => Some("rules infraction"),
+            GameState::Aborted => Some("abandoned"),
+            GameState::TablebaseWin(_) | GameState::TablebaseDraw => Some("tablebase adjudication"),
+            GameState::AdjudicatedDraw => Some("adjudication"),
+        }
+    }
+
+    pub fn winner(&self) -> Option<PieceColor> {
+        match self {
+            GameState::Won(color)
+            | GameState::WonOnTime(color)
+            | GameState::TablebaseWin(color) => Some(*color),
+            GameState::Resigned(color) | GameState::Forfeit(color) => Some(color.invert()),
+            _ => None,
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        *self != GameState::Ongoing
+    }
 }
@@ -1,3 +1,5 @@
+use super::error::ParseError;
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 pub struct Location {
     // location along width of board
@@ -20,17 +22,36 @@ impl Location {
         format!("{}{}", char_value, self.y + 1)
     }
 
-    // given the encoding of a position, e.g. e4, compute a location
-    pub fn decode(encoded: &str) -> Result<Self, String> {
+    // given the encoding of a position, e.g. e4 or b10, compute a location
+    pub fn decode(encoded: &str) -> Result<Self, ParseError> {
         let encoded_lower = encoded.to_lowercase();
-        let base_int = 'a' as usize;
-        let char_int = encoded_lower.chars().next().unwrap() as usize;
-        let x = char_int - base_int;
-        let y = encoded[1..]
+        let mut chars = encoded_lower.chars();
+        let x = match chars.next() {
+            Some(file @ 'a'..='z') => file as usize - 'a' as usize,
+            _ => return Err(ParseError::new(encoded, 0, "expected a file letter")),
+        };
+        let y = chars
+            .as_str()
             .parse::<usize>()
-            .map_err(|_| "failed parsing second part of location encoding as an integer")?
-            - 1;
+            .ok()
+            .and_then(|rank| rank.checked_sub(1))
+            .ok_or(ParseError::new(encoded, 1, "expected a rank number"))?;
 
         Ok(Location { x, y })
     }
+
+    /// the length of the location at the start of the input, a file letter
+    /// followed by the digits of the rank, e.g. 3 for `b10c10`
+    pub fn encoded_len(input: &str) -> usize {
+        let digits = input
+            .chars()
+            .skip(1)
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+        if input.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            1 + digits
+        } else {
+            0
+        }
+    }
 }
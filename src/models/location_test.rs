@@ -1,3 +1,5 @@
+use std::error::Error;
+
 use super::location::Location;
 
 #[test]
@@ -30,3 +32,40 @@ fn decode_success() {
     // assert
     assert_eq!(decoded, Ok(expected_location));
 }
+
+#[test]
+fn two_digit_ranks() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let location = Location::new(9, 9);
+    // action
+    let encoded = location.encode();
+    let decoded = Location::decode("j10")?;
+    // assert
+    assert_eq!(encoded, "j10");
+    assert_eq!(decoded, location);
+    Ok(())
+}
+
+#[test]
+fn decode_failure() {
+    // action
+    let decoded: Vec<bool> = ["", "a0", "4e", "e"]
+        .iter()
+        .map(|input| Location::decode(input).is_err())
+        .collect();
+    // assert
+    assert_eq!(decoded, vec![true; 4]);
+}
+
+#[test]
+fn decode_failure_position() {
+    // action
+    let no_file = Location::decode("4e");
+    let no_rank = Location::decode("e");
+    // assert
+    assert_eq!(no_file.map_err(|e| e.position), Err(0));
+    assert_eq!(
+        no_rank.map_err(|e| e.to_string()),
+        Err("expected a rank number at position 1 of 'e'".to_string())
+    );
+}
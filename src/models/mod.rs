@@ -1,9 +1,17 @@
 pub mod board;
+pub mod clock;
+pub mod error;
 pub mod field_content;
 pub mod game_state;
 pub mod location;
 pub mod piece;
+pub mod piece_definition;
 pub mod step;
+pub mod variant_kind;
 
+#[cfg(test)]
+mod clock_test;
 #[cfg(test)]
 mod location_test;
+#[cfg(test)]
+mod piece_definition_test;
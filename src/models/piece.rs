@@ -1,6 +1,6 @@
 use ansi_term::Color::{self, Red, Yellow};
 
-use super::step::Direction;
+use super::{error::ParseError, step::Direction};
 
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum PieceKind {
@@ -10,6 +10,8 @@ pub enum PieceKind {
     Bishop,
     Queen,
     King,
+    /// a piece of the board's `PieceSet` with the given upper case letter
+    Fairy(char),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
@@ -24,6 +26,7 @@ pub struct ColoredPiece {
     pub color: PieceColor,
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct MoveCapability {
     pub direction: Direction,
     pub distance: i16,
@@ -40,6 +43,8 @@ impl PieceKind {
             PieceKind::Rook => "\u{f085b}".to_string(),
             PieceKind::Queen => "\u{f085a}".to_string(),
             PieceKind::King => "\u{f0857}".to_string(),
+            // there are no glyphs for fairy pieces, they are shown by their letter
+            PieceKind::Fairy(_) => self.encode(),
         }
     }
 
@@ -51,19 +56,27 @@ impl PieceKind {
             PieceKind::Rook => "R".to_string(),
             PieceKind::Queen => "Q".to_string(),
             PieceKind::King => "K".to_string(),
+            PieceKind::Fairy(symbol) => symbol.to_string(),
         }
     }
 
-    pub fn decode(input: char) -> Result<PieceKind, String> {
-        let input: char = input.to_ascii_lowercase();
-        match input {
+    /// Other letters than the ones of the standard pieces are fairy pieces,
+    /// whether the board has them is up to `Board::validate`.
+    pub fn decode(input: char) -> Result<PieceKind, ParseError> {
+        let lower: char = input.to_ascii_lowercase();
+        match lower {
             'p' => Ok(PieceKind::Pawn),
             'b' => Ok(PieceKind::Bishop),
             'n' => Ok(PieceKind::Knight),
             'r' => Ok(PieceKind::Rook),
             'q' => Ok(PieceKind::Queen),
             'k' => Ok(PieceKind::King),
-            c => Err(format!("failed decoding piece char {}", c)),
+            c if c.is_ascii_lowercase() => Ok(PieceKind::Fairy(c.to_ascii_uppercase())),
+            _ => Err(ParseError::new(
+                &input.to_string(),
+                0,
+                "expected a piece letter",
+            )),
         }
     }
 }
@@ -83,12 +96,11 @@ impl PieceColor {
         }
     }
 
-    pub fn decode(c: char) -> Result<Self, String> {
-        let c = c.to_ascii_lowercase();
-        match c {
+    pub fn decode(c: char) -> Result<Self, ParseError> {
+        match c.to_ascii_lowercase() {
             'w' => Ok(Self::White),
             'b' => Ok(Self::Black),
-            _ => Err(format!("Invalid color! {}", c)),
+            _ => Err(ParseError::new(&c.to_string(), 0, "expected 'w' or 'b'")),
         }
     }
 }
@@ -100,8 +112,11 @@ impl ColoredPiece {
         color.paint(symbol).to_string()
     }
 
+    /// how the standard pieces move, fairy pieces move as the piece set of
+    /// the board defines them, see `PieceSet::capabilities`
     pub fn piece_move_capabilities(&self) -> Vec<MoveCapability> {
         match self.kind {
+            PieceKind::Fairy(_) => vec![],
             PieceKind::Pawn => {
                 let direction_y = if self.color == PieceColor::White {
                     1
@@ -119,13 +134,13 @@ impl ColoredPiece {
                         direction: Direction(1, direction_y),
                         distance: 1,
                         must_take: true,
-                        can_take: false,
+                        can_take: true,
                     },
                     MoveCapability {
                         direction: Direction(-1, direction_y),
                         distance: 1,
                         must_take: true,
-                        can_take: false,
+                        can_take: true,
                     },
                 ]
             }
@@ -0,0 +1,212 @@
+use std::{path::Path, sync::Arc};
+
+use super::{
+    error::{FileError, ParseError, PieceError},
+    piece::{MoveCapability, PieceKind},
+    step::Direction,
+};
+
+// letters of the standard pieces, a fairy piece has to use another one
+const STANDARD_SYMBOLS: &str = "PNBRQK";
+
+/// A piece that is described by data instead of being built in, e.g. the
+/// archbishop moving like a bishop and a knight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PieceDefinition {
+    pub name: String,
+    /// upper case letter used in FEN and SAN, lower case for black
+    pub symbol: char,
+    pub capabilities: Vec<MoveCapability>,
+    /// in centipawns
+    pub value: i32,
+}
+
+/// The fairy pieces a board is played with, shared by all copies of it.
+/// `PieceKind::Fairy` names a piece of the set by its letter.
+#[derive(Debug, Clone, Default)]
+pub struct PieceSet(Arc<[PieceDefinition]>);
+
+impl PieceSet {
+    /// fails for letters of the standard pieces and for letters used twice
+    pub fn new(definitions: Vec<PieceDefinition>) -> Result<Self, PieceError> {
+        Self::default().extend(definitions)
+    }
+
+    /// A set with the given pieces added. The pieces of the set can't be
+    /// redefined, e.g. the archbishop of capablanca chess by a file of the
+    /// user.
+    pub fn extend(&self, definitions: Vec<PieceDefinition>) -> Result<Self, PieceError> {
+        let mut res = self.0.to_vec();
+        for definition in definitions {
+            if !definition.symbol.is_ascii_uppercase()
+                || STANDARD_SYMBOLS.contains(definition.symbol)
+            {
+                return Err(PieceError::InvalidSymbol {
+                    piece: definition.name,
+                    symbol: definition.symbol,
+                });
+            }
+            if let Some(defined) = res.iter().find(|def| def.symbol == definition.symbol) {
+                return Err(PieceError::SymbolTaken {
+                    piece: definition.name,
+                    symbol: definition.symbol,
+                    taken_by: defined.name.clone(),
+                });
+            }
+            res.push(definition);
+        }
+        Ok(Self(res.into()))
+    }
+
+    pub fn get(&self, kind: PieceKind) -> Option<&PieceDefinition> {
+        let PieceKind::Fairy(symbol) = kind else {
+            return None;
+        };
+        self.0.iter().find(|def| def.symbol == symbol)
+    }
+
+    /// the piece with the given letter, in either case
+    pub fn find(&self, symbol: char) -> Option<PieceKind> {
+        let kind = PieceKind::Fairy(symbol.to_ascii_uppercase());
+        self.get(kind).map(|_| kind)
+    }
+
+    pub fn kinds(&self) -> Vec<PieceKind> {
+        self.0
+            .iter()
+            .map(|def| PieceKind::Fairy(def.symbol))
+            .collect()
+    }
+
+    /// how the piece moves, nothing for pieces outside of the set
+    pub fn capabilities(&self, kind: PieceKind) -> &[MoveCapability] {
+        self.get(kind).map_or(&[], |def| &def.capabilities)
+    }
+}
+
+impl PieceDefinition {
+    /// reads all pieces of a definition file
+    pub fn load(path: impl AsRef<Path>) -> Result<Vec<PieceDefinition>, PieceError> {
+        let content = FileError::read_to_string(path.as_ref())?;
+        Self::decode(&content)
+    }
+
+    /// Reads piece definitions, each starting with its name in brackets and
+    /// followed by `key = value` lines. Lines starting with `#` are comments.
+    /// Moves are joined by `+`, a `leap x,y` jumps to every square x files
+    /// and y ranks away in all orientations, a `ride x,y` repeats that leap
+    /// until it is blocked:
+    ///
+    /// ```text
+    /// [Archbishop]
+    /// symbol = A
+    /// value = 825
+    /// moves = ride 1,1 + leap 1,2
+    /// ```
+    pub fn decode(input: &str) -> Result<Vec<PieceDefinition>, PieceError> {
+        let mut res = vec![];
+        let mut current: Option<(String, Vec<(&str, &str)>)> = None;
+        for line in input.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some((name, entries)) = current.take() {
+                    res.push(Self::from_entries(name, &entries)?);
+                }
+                current = Some((name.trim().to_string(), vec![]));
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(line, 0, "a 'key = value' line"))?;
+            let (_, entries) = current
+                .as_mut()
+                .ok_or_else(|| ParseError::new(line, 0, "a [name] line first"))?;
+            entries.push((key.trim(), value.trim()));
+        }
+        if let Some((name, entries)) = current {
+            res.push(Self::from_entries(name, &entries)?);
+        }
+        Ok(res)
+    }
+
+    fn from_entries(name: String, entries: &[(&str, &str)]) -> Result<PieceDefinition, PieceError> {
+        let value_of = |key: &str| {
+            entries
+                .iter()
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| *value)
+                .ok_or_else(|| PieceError::MissingSetting {
+                    piece: name.clone(),
+                    key: key.to_string(),
+                })
+        };
+        if let Some((key, _)) = entries
+            .iter()
+            .find(|(key, _)| !["symbol", "value", "moves"].contains(key))
+        {
+            return Err(PieceError::UnknownSetting {
+                piece: name,
+                key: key.to_string(),
+            });
+        }
+        let symbol = value_of("symbol")?;
+        let mut chars = symbol.chars();
+        let symbol = match (chars.next(), chars.next()) {
+            (Some(symbol), None) => symbol.to_ascii_uppercase(),
+            _ => return Err(ParseError::new(symbol, 0, "a single letter").into()),
+        };
+        let value = value_of("value")?;
+        let value = value
+            .parse()
+            .map_err(|_| ParseError::new(value, 0, "a value in centipawns"))?;
+        let mut capabilities = vec![];
+        for movement in value_of("moves")?.split('+') {
+            capabilities.extend(Self::decode_movement(movement.trim())?);
+        }
+        Ok(PieceDefinition {
+            name,
+            symbol,
+            capabilities,
+            value,
+        })
+    }
+
+    /// `leap x,y` or `ride x,y`
+    fn decode_movement(input: &str) -> Result<Vec<MoveCapability>, ParseError> {
+        let invalid = || ParseError::new(input, 0, "a move like 'leap 1,2' or 'ride 1,1'");
+        let (kind, offset) = input.split_once(' ').ok_or_else(invalid)?;
+        let distance = match kind {
+            "leap" => 1,
+            "ride" => -1,
+            _ => return Err(invalid()),
+        };
+        let (x, y) = offset.trim().split_once(',').ok_or_else(invalid)?;
+        let x: isize = x.trim().parse().map_err(|_| invalid())?;
+        let y: isize = y.trim().parse().map_err(|_| invalid())?;
+        if x == 0 && y == 0 {
+            return Err(invalid());
+        }
+
+        let mut directions: Vec<(isize, isize)> = vec![];
+        for (a, b) in [(x, y), (y, x)] {
+            for (sign_a, sign_b) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+                let direction = (a.abs() * sign_a, b.abs() * sign_b);
+                if !directions.contains(&direction) {
+                    directions.push(direction);
+                }
+            }
+        }
+        Ok(directions
+            .into_iter()
+            .map(|(x, y)| MoveCapability {
+                direction: Direction(x, y),
+                distance,
+                must_take: false,
+                can_take: true,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,195 @@
+use std::error::Error;
+
+use super::{
+    board::Board,
+    error::{PieceError, PositionError},
+    location::Location,
+    piece::{ColoredPiece, PieceColor, PieceKind},
+    piece_definition::{PieceDefinition, PieceSet},
+    variant_kind::VariantKind,
+};
+use crate::{
+    logic::{evaluation::piece_value, move_computer::StepComputer},
+    utils::{fen::Fen, san::San},
+};
+
+const FAIRY_PIECES: &str = include_str!("../../data/fairy_pieces.conf");
+
+fn fairy_pieces() -> Result<PieceSet, PieceError> {
+    PieceSet::new(PieceDefinition::decode(FAIRY_PIECES)?)
+}
+
+/// a standard position with the shipped fairy pieces
+fn fairy_board(fen: &str) -> Result<Board, Box<dyn Error>> {
+    Ok(Fen::decode_valid_with(
+        fen,
+        VariantKind::Standard,
+        fairy_pieces()?,
+    )?)
+}
+
+#[test]
+fn decode_shipped_definitions() -> Result<(), Box<dyn Error>> {
+    // action
+    let definitions = PieceDefinition::decode(FAIRY_PIECES)?;
+    // assert
+    let summary: Vec<(&str, char, usize, i32)> = definitions
+        .iter()
+        .map(|def| {
+            (
+                def.name.as_str(),
+                def.symbol,
+                def.capabilities.len(),
+                def.value,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("Archbishop", 'A', 12, 825),
+            ("Chancellor", 'C', 12, 875),
+            ("Amazon", 'M', 16, 1250),
+            ("Camel", 'L', 8, 250),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn pieces_of_the_set_are_known_by_symbol() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = fairy_board("4k3/8/8/8/8/8/8/4K3 w - - 0 1")?;
+    // action
+    let kinds = board.piece_set().kinds();
+    // assert
+    assert_eq!(PieceKind::decode('a')?, kinds[0]);
+    assert_eq!(board.piece_set().find('c'), Some(kinds[1]));
+    assert_eq!(board.piece_set().find('x'), None);
+    assert_eq!(kinds[1].encode(), "C");
+    assert_eq!(piece_value(&board, kinds[2]), 1250);
+    assert_eq!(board.piece_set().capabilities(PieceKind::Fairy('X')), &[]);
+    Ok(())
+}
+
+#[test]
+fn boards_only_hold_pieces_of_their_set() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let fen = "4k3/8/8/8/3A4/8/8/4K3 w - - 0 1";
+    // action
+    let standard = Fen::decode_valid(fen, VariantKind::Standard);
+    let without_set = Fen::decode(fen)?;
+    // assert
+    assert!(standard.is_err());
+    assert_eq!(
+        without_set.validate(),
+        Err(PositionError::UnknownPiece('A'))
+    );
+    assert!(fairy_board(fen).is_ok());
+    Ok(())
+}
+
+#[test]
+fn pieces_of_the_variant_cant_be_redefined() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let capablanca = VariantKind::Capablanca.rules().pieces();
+    let archbishop = PieceDefinition::decode(
+        "[Princess]\nsymbol = A\nvalue = 800\nmoves = ride 1,1 + leap 1,2",
+    )?;
+    let camel = PieceDefinition::decode("[Camel]\nsymbol = L\nvalue = 250\nmoves = leap 1,3")?;
+    // action
+    let redefined = capablanca.extend(archbishop);
+    let extended = capablanca.extend(camel)?;
+    // assert
+    assert!(matches!(
+        redefined,
+        Err(PieceError::SymbolTaken { symbol: 'A', .. })
+    ));
+    assert_eq!(
+        extended.kinds(),
+        vec![
+            PieceKind::Fairy('A'),
+            PieceKind::Fairy('C'),
+            PieceKind::Fairy('L')
+        ]
+    );
+    assert_eq!(capablanca.kinds().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn fairy_pieces_move_by_their_definition() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let archbishop = fairy_board("4k3/8/8/8/3A4/8/8/4K3 w - - 0 1")?;
+    let camel = fairy_board("4k3/8/8/8/3L4/8/8/4K3 w - - 0 1")?;
+    // action
+    let archbishop_steps = archbishop.compute_steps().len();
+    let camel_steps = camel.compute_steps().len();
+    // assert
+    // 13 diagonal steps and 8 knight jumps, besides 5 king steps
+    assert_eq!(archbishop_steps, 13 + 8 + 5);
+    // d4 reaches a3, a5, c1, c7, e1 (own king), e7, g3 and g5
+    assert_eq!(camel_steps, 7 + 5);
+    Ok(())
+}
+
+#[test]
+fn fairy_pieces_in_notation() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let fen = "4k2c/8/4p3/8/3A4/8/8/4K3 w - - 0 1";
+    let board = fairy_board(fen)?;
+    // action
+    let step = San::decode(&board, "Axe6")?;
+    let san = San::encode(&board, &step);
+    // assert
+    assert_eq!(step.encode_uci(), "d4e6");
+    assert_eq!(san, "Axe6");
+    assert_eq!(Fen::encode(&board), fen);
+    let chancellor = board
+        .field_at_location(&Location::new(7, 7))
+        .and_then(|field| field.get_content())
+        .map(|(piece, _)| piece);
+    assert_eq!(
+        chancellor,
+        Some(ColoredPiece {
+            kind: PieceKind::Fairy('C'),
+            color: PieceColor::Black,
+        })
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_definitions() {
+    // arrange
+    let inputs = [
+        "symbol = A",
+        "[Queen]\nsymbol = Q\nvalue = 900\nmoves = ride 1,1 + ride 1,0",
+        "[Archbishop]\nsymbol = A\nvalue = 825",
+        "[Archbishop]\nsymbol = A\nvalue = 825\nmoves = fly 1,1",
+        "[Archbishop]\nsymbol = A\nvalue = lots\nmoves = leap 1,2",
+        "[Archbishop]\nsymbol = A\nvalue = 825\nmoves = leap 1,2\n[Amazon]\nsymbol = A\nvalue = 1250\nmoves = leap 1,2",
+    ];
+    // action
+    let results: Vec<_> = inputs
+        .iter()
+        .map(|input| PieceDefinition::decode(input).and_then(PieceSet::new))
+        .collect();
+    // assert
+    assert!(matches!(results[0], Err(PieceError::Parse(_))));
+    assert!(matches!(
+        results[1],
+        Err(PieceError::InvalidSymbol { symbol: 'Q', .. })
+    ));
+    assert!(matches!(
+        &results[2],
+        Err(PieceError::MissingSetting { key, .. }) if key == "moves"
+    ));
+    assert!(matches!(results[3], Err(PieceError::Parse(_))));
+    assert!(matches!(results[4], Err(PieceError::Parse(_))));
+    assert!(matches!(
+        &results[5],
+        Err(PieceError::SymbolTaken { piece, taken_by, .. })
+            if piece == "Amazon" && taken_by == "Archbishop"
+    ));
+}
@@ -1,7 +1,4 @@
-use super::{
-    location::Location,
-    piece::{PieceColor, PieceKind},
-};
+use super::{error::ParseError, location::Location, piece::PieceKind};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Step {
@@ -9,14 +6,25 @@ pub struct Step {
     pub to: Location,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Direction(pub isize, pub isize);
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum StepKind {
     GoTo(Step),
-    Promote { step: Step, piece: PieceKind },
-    Castle { king_step: Step, rook_step: Step },
+    Promote {
+        step: Step,
+        piece: PieceKind,
+    },
+    Castle {
+        king_step: Step,
+        rook_step: Step,
+    },
+    /// putting a piece from the pocket on an empty field, in crazyhouse
+    Drop {
+        piece: PieceKind,
+        to: Location,
+    },
 }
 
 impl Step {
@@ -42,40 +50,37 @@ impl Direction {
 }
 
 impl StepKind {
-    pub fn possible_promotions(step: Step) -> Vec<StepKind> {
-        vec![
-            StepKind::Promote {
-                step,
-                piece: PieceKind::Pawn,
-            },
-            StepKind::Promote {
-                step,
-                piece: PieceKind::Rook,
-            },
-            StepKind::Promote {
-                step,
-                piece: PieceKind::Knight,
-            },
-            StepKind::Promote {
-                step,
-                piece: PieceKind::Bishop,
-            },
-            StepKind::Promote {
-                step,
-                piece: PieceKind::Queen,
-            },
-            StepKind::Promote {
-                step,
-                piece: PieceKind::King,
-            },
-        ]
-    }
-
     pub fn target(&self) -> Option<Location> {
         match self {
             StepKind::GoTo(step) => Some(step.to),
             StepKind::Promote { step, .. } => Some(step.to),
-            _ => None,
+            StepKind::Drop { to, .. } => Some(*to),
+            StepKind::Castle { .. } => None,
+        }
+    }
+
+    /// the long algebraic notation used by the UCI protocol, e.g. `e1g1`,
+    /// `e7e8q` or `P@e4`
+    pub fn encode_uci(&self) -> String {
+        match self {
+            StepKind::GoTo(step) => step.encode(),
+            StepKind::Promote { step, piece } => {
+                format!("{}{}", step.encode(), piece.encode().to_lowercase())
+            }
+            StepKind::Castle { king_step, .. } => king_step.encode(),
+            StepKind::Drop { .. } => self.encode(),
+        }
+    }
+
+    /// like `encode_uci`, but castling is written as the king taking its own
+    /// rook, e.g. `e1h1`, which is how chess960 moves are sent over UCI
+    pub fn encode_uci_chess960(&self) -> String {
+        match self {
+            StepKind::Castle {
+                king_step,
+                rook_step,
+            } => format!("{}{}", king_step.from.encode(), rook_step.from.encode()),
+            _ => self.encode_uci(),
         }
     }
 
@@ -84,57 +89,77 @@ impl StepKind {
         match self {
             StepKind::GoTo(step) => step.encode(),
             StepKind::Promote { step, piece } => format!("{}={}", step.encode(), piece.encode()),
-            StepKind::Castle { rook_step, .. } => {
-                if rook_step.from.x == 0 {
+            StepKind::Castle {
+                king_step,
+                rook_step,
+            } => {
+                if rook_step.from.x < king_step.from.x {
                     // long castle:
                     "0-0-0".to_string()
                 } else {
                     "0-0".to_string()
                 }
             }
+            StepKind::Drop { piece, to } => format!("{}@{}", piece.encode(), to.encode()),
         }
     }
 
-    pub fn decode(input: &str, current_color: PieceColor) -> Result<Self, String> {
-        let rank = if current_color == PieceColor::White {
-            0
-        } else {
-            7
+    /// decodes coordinate notation like `e2e4`, `e7e8=Q` or `a9a10` on larger
+    /// boards, and drops like `N@f3`. Where the king and rook start from
+    /// depends on the position, so castling is resolved against the board by
+    /// `San::decode`
+    pub fn decode(input: &str) -> Result<Self, ParseError> {
+        if let Some((piece, to)) = input.split_once('@') {
+            let mut chars = piece.chars();
+            let piece = match (chars.next(), chars.next()) {
+                // a pawn drop may leave out the piece
+                (None, _) => PieceKind::Pawn,
+                (Some(piece), None) => PieceKind::decode(piece).map_err(|e| e.within(input, 0))?,
+                _ => return Err(ParseError::new(input, 1, "expected '@' after the piece")),
+            };
+            let offset = input.len() - to.len();
+            return Ok(StepKind::Drop {
+                piece,
+                to: Location::decode(to).map_err(|e| e.within(input, offset))?,
+            });
+        }
+        let from_len = Location::encoded_len(input);
+        let (start, rest) = input.split_at(from_len);
+        let to_len = Location::encoded_len(rest);
+        let (end, promotion) = rest.split_at(to_len);
+        if from_len < 2 {
+            return Err(ParseError::new(input, 0, "expected the square moved from"));
+        }
+        if to_len < 2 {
+            return Err(ParseError::new(
+                input,
+                from_len,
+                "expected the square moved to",
+            ));
+        }
+        let step = Step {
+            from: Location::decode(start).map_err(|e| e.within(input, 0))?,
+            to: Location::decode(end).map_err(|e| e.within(input, from_len))?,
         };
-        match input {
-            "O-O" => {
-                let rook_step = Step::new((7, rank), (5, rank));
-                let king_step = Step::new((4, rank), (6, rank));
-                Ok(StepKind::Castle {
-                    king_step,
-                    rook_step,
-                })
-            }
-            //"O-O-O" => Ok(Move::CastlingQueenside),
-            _ if input.len() == 4 => {
-                let (start, end) = input.split_at(2);
-                Ok(StepKind::GoTo(Step {
-                    from: Location::decode(start)?,
-                    to: Location::decode(end)?,
-                }))
-            }
-            _ if input.contains('=') => {
-                if let Some(eq_pos) = input.find('=') {
-                    let promotion = input.chars().nth(eq_pos + 1).ok_or("Invalid promotion")?;
-                    let piece = PieceKind::decode(promotion)?;
-                    let (start, end) = input.split_at(eq_pos);
-                    Ok(StepKind::Promote {
-                        step: Step {
-                            from: Location::decode(start)?,
-                            to: Location::decode(end)?,
-                        },
-                        piece,
-                    })
-                } else {
-                    Err("Invalid promotion format".to_string())
+        let offset = from_len + to_len;
+        match promotion {
+            "" => Ok(StepKind::GoTo(step)),
+            _ => {
+                let piece = promotion.strip_prefix('=').unwrap_or(promotion);
+                let offset = offset + promotion.len() - piece.len();
+                let mut chars = piece.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(promotion), None) => Ok(StepKind::Promote {
+                        step,
+                        piece: PieceKind::decode(promotion).map_err(|e| e.within(input, offset))?,
+                    }),
+                    _ => Err(ParseError::new(
+                        input,
+                        offset,
+                        "expected a single piece to promote to",
+                    )),
                 }
             }
-            _ => Err("Invalid move format".to_string()),
         }
     }
 }
@@ -0,0 +1,100 @@
+use super::error::ParseError;
+
+/// The rule sets a game can be played with. The rules themselves are
+/// implemented by the `Variant` trait in `logic::variants`.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum VariantKind {
+    #[default]
+    Standard,
+    /// a king reaching one of the four center squares wins
+    KingOfTheHill,
+    /// giving the third check wins
+    ThreeCheck,
+    /// captures are compulsory, losing all pieces wins
+    Antichess,
+    /// white has a horde of pawns and no king, black has to capture them all
+    Horde,
+    /// 10x8 board with archbishops and chancellors
+    Capablanca,
+    /// 6x6 board without bishops
+    LosAlamos,
+    /// captured pieces may be dropped back on the board
+    Crazyhouse,
+    /// captures explode the pieces around them
+    Atomic,
+}
+
+impl VariantKind {
+    pub const ALL: [VariantKind; 9] = [
+        VariantKind::Standard,
+        VariantKind::KingOfTheHill,
+        VariantKind::ThreeCheck,
+        VariantKind::Antichess,
+        VariantKind::Horde,
+        VariantKind::Capablanca,
+        VariantKind::LosAlamos,
+        VariantKind::Crazyhouse,
+        VariantKind::Atomic,
+    ];
+
+    /// the name used in the `Variant` tag of PGN files
+    pub fn encode(&self) -> &'static str {
+        match self {
+            VariantKind::Standard => "Standard",
+            VariantKind::KingOfTheHill => "King of the Hill",
+            VariantKind::ThreeCheck => "Three-check",
+            VariantKind::Antichess => "Antichess",
+            VariantKind::Horde => "Horde",
+            VariantKind::Capablanca => "Capablanca",
+            VariantKind::LosAlamos => "Los Alamos",
+            VariantKind::Crazyhouse => "Crazyhouse",
+            VariantKind::Atomic => "Atomic",
+        }
+    }
+
+    /// accepts the PGN names as well as the short UCI names like `3check`,
+    /// ignoring case, spaces and dashes
+    pub fn decode(input: &str) -> Result<VariantKind, ParseError> {
+        let normalized: String = input
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase();
+        match normalized.as_str() {
+            "standard" | "chess" => Ok(VariantKind::Standard),
+            "kingofthehill" | "koth" => Ok(VariantKind::KingOfTheHill),
+            "threecheck" | "3check" => Ok(VariantKind::ThreeCheck),
+            "antichess" => Ok(VariantKind::Antichess),
+            "horde" => Ok(VariantKind::Horde),
+            "capablanca" => Ok(VariantKind::Capablanca),
+            "losalamos" => Ok(VariantKind::LosAlamos),
+            "crazyhouse" | "zh" => Ok(VariantKind::Crazyhouse),
+            "atomic" => Ok(VariantKind::Atomic),
+            _ => Err(ParseError::new(input, 0, "a known variant")),
+        }
+    }
+
+    /// the name used by the `UCI_Variant` option
+    pub fn encode_uci(&self) -> &'static str {
+        match self {
+            VariantKind::Standard => "chess",
+            VariantKind::KingOfTheHill => "kingofthehill",
+            VariantKind::ThreeCheck => "3check",
+            VariantKind::Antichess => "antichess",
+            VariantKind::Horde => "horde",
+            VariantKind::Capablanca => "capablanca",
+            VariantKind::LosAlamos => "losalamos",
+            VariantKind::Crazyhouse => "crazyhouse",
+            VariantKind::Atomic => "atomic",
+        }
+    }
+
+    /// the variant after this one, for cycling through them in the TUI
+    pub fn next(&self) -> VariantKind {
+        let index = Self::ALL
+            .iter()
+            .position(|variant| variant == self)
+            .unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
@@ -1,23 +1,48 @@
 use crate::models::{
     board::Board,
+    error::{BoardError, ParseError},
     location::Location,
     piece::{ColoredPiece, PieceColor, PieceKind},
 };
 
 pub struct BoardCreation;
 impl BoardCreation {
-    pub fn from_description(descr: String) -> Result<Board, String> {
-        let mut board = Board::new(8, 8);
+    /// places pieces on an empty 8x8 board, one per line like `e1kw` for the
+    /// white king on e1. The position has to be legal.
+    pub fn from_description(descr: String) -> Result<Board, BoardError> {
+        Self::from_description_sized(8, 8, descr)
+    }
+
+    /// like `from_description`, on an empty board of the given size
+    pub fn from_description_sized(
+        width: usize,
+        height: usize,
+        descr: String,
+    ) -> Result<Board, BoardError> {
+        let mut board = Board::new(width, height);
         let lines = descr.lines();
         for line in lines {
             let line = line.trim();
             if line.is_empty() {
                 continue;
             }
-            let chars: Vec<char> = line.chars().collect();
-            let location = Location::decode(&line[0..2])?;
-            let piece_kind = PieceKind::decode(chars[2])?;
-            let piece_color = PieceColor::decode(chars[3])?;
+            let location_len = Location::encoded_len(line);
+            let (location, piece) = line.split_at(location_len);
+            let location = Location::decode(location).map_err(|e| e.within(line, 0))?;
+            if !board.in_bounds(&location) {
+                return Err(BoardError::OutOfBounds(location));
+            }
+            let chars: Vec<char> = piece.chars().collect();
+            let [kind, color] = chars[..] else {
+                return Err(BoardError::Parse(ParseError::new(
+                    line,
+                    location_len,
+                    "expected a piece and a color after the square",
+                )));
+            };
+            let piece_kind = PieceKind::decode(kind).map_err(|e| e.within(line, location_len))?;
+            let piece_color =
+                PieceColor::decode(color).map_err(|e| e.within(line, location_len + 1))?;
 
             let piece = ColoredPiece {
                 kind: piece_kind,
@@ -25,6 +50,7 @@ impl BoardCreation {
             };
             board.put_piece_on_location(piece, &location, 0);
         }
+        board.validate()?;
         Ok(board)
     }
 }
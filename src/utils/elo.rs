@@ -0,0 +1,113 @@
+/// z-value of a 95% confidence interval
+const Z_95: f64 = 1.959964;
+
+/// Wins, draws and losses of a player, e.g. the first engine of a match
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MatchScore {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+}
+
+/// Which hypothesis a sequential probability ratio test accepted, if any
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SprtVerdict {
+    /// the difference is at most elo0
+    H0,
+    /// the difference is at least elo1
+    H1,
+    /// more games are needed to tell
+    Continue,
+}
+
+/// A sequential probability ratio test of elo0 against elo1: the log
+/// likelihood ratio of the games so far and the bounds it is compared to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    pub llr: f64,
+    pub lower: f64,
+    pub upper: f64,
+    pub verdict: SprtVerdict,
+}
+
+impl MatchScore {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// points per game, a draw counts half
+    pub fn ratio(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    /// The Elo difference the score suggests with the half width of its 95%
+    /// confidence interval, which is infinite if it reaches a perfect score.
+    pub fn elo(&self) -> (f64, f64) {
+        let ratio = self.ratio();
+        let deviation = (self.variance() / self.games().max(1) as f64).sqrt();
+        let low = elo_difference((ratio - Z_95 * deviation).max(0.0));
+        let high = elo_difference((ratio + Z_95 * deviation).min(1.0));
+        let error = if low.is_finite() && high.is_finite() {
+            (high - low) / 2.0
+        } else {
+            f64::INFINITY
+        };
+        (elo_difference(ratio), error)
+    }
+
+    /// Tests whether the Elo difference is elo0 or elo1, with the chance
+    /// alpha of wrongly accepting elo1 and beta of wrongly accepting elo0.
+    /// Uses the usual normal approximation of the log likelihood ratio.
+    pub fn sprt(&self, elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Sprt {
+        let lower = (beta / (1.0 - alpha)).ln();
+        let upper = ((1.0 - beta) / alpha).ln();
+        let variance = self.variance();
+        let llr = if variance > 0.0 {
+            let (score0, score1) = (expected_score(elo0), expected_score(elo1));
+            self.games() as f64 * (score1 - score0) * (2.0 * self.ratio() - score0 - score1)
+                / (2.0 * variance)
+        } else {
+            0.0
+        };
+        let verdict = if llr >= upper {
+            SprtVerdict::H1
+        } else if llr <= lower {
+            SprtVerdict::H0
+        } else {
+            SprtVerdict::Continue
+        };
+        Sprt {
+            llr,
+            lower,
+            upper,
+            verdict,
+        }
+    }
+
+    /// variance of the points of a single game
+    fn variance(&self) -> f64 {
+        let games = self.games();
+        if games == 0 {
+            return 0.0;
+        }
+        let ratio = self.ratio();
+        let squared = |points: f64| (points - ratio).powi(2);
+        (self.wins as f64 * squared(1.0)
+            + self.draws as f64 * squared(0.5)
+            + self.losses as f64 * squared(0.0))
+            / games as f64
+    }
+}
+
+/// points per game expected against an opponent rated lower by the difference
+pub fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// the inverse of `expected_score`
+pub fn elo_difference(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
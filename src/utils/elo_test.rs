@@ -0,0 +1,72 @@
+use super::elo::{elo_difference, expected_score, MatchScore, SprtVerdict};
+
+#[test]
+fn elo_of_an_even_score_is_zero() {
+    // arrange
+    let score = MatchScore {
+        wins: 30,
+        draws: 40,
+        losses: 30,
+    };
+    // action
+    let (elo, error) = score.elo();
+    // assert
+    assert!(elo.abs() < 1e-9);
+    // a standard deviation of 0.387 points per game over 100 games
+    assert!((error - 53.2).abs() < 0.1, "{error}");
+}
+
+#[test]
+fn elo_of_a_perfect_score_is_infinite() {
+    // arrange
+    let score = MatchScore {
+        wins: 2,
+        draws: 0,
+        losses: 0,
+    };
+    // action
+    let (elo, error) = score.elo();
+    // assert
+    assert_eq!(elo, f64::INFINITY);
+    assert_eq!(error, f64::INFINITY);
+}
+
+#[test]
+fn elo_and_expected_score_are_inverse() {
+    // arrange
+    let elo = 120.0;
+    // action
+    let score = expected_score(elo);
+    // assert
+    assert!((score - 0.666).abs() < 0.001);
+    assert!((elo_difference(score) - elo).abs() < 1e-9);
+}
+
+#[test]
+fn sprt_decides_clear_results() {
+    // arrange
+    let strong = MatchScore {
+        wins: 300,
+        draws: 400,
+        losses: 200,
+    };
+    let even = MatchScore {
+        wins: 1500,
+        draws: 2000,
+        losses: 1500,
+    };
+    let few = MatchScore {
+        wins: 3,
+        draws: 4,
+        losses: 2,
+    };
+    // action
+    let strong_test = strong.sprt(0.0, 10.0, 0.05, 0.05);
+    let even_test = even.sprt(0.0, 10.0, 0.05, 0.05);
+    let few_test = few.sprt(0.0, 10.0, 0.05, 0.05);
+    // assert
+    assert!((strong_test.upper - 2.944).abs() < 0.001);
+    assert_eq!(strong_test.verdict, SprtVerdict::H1);
+    assert_eq!(even_test.verdict, SprtVerdict::H0);
+    assert_eq!(few_test.verdict, SprtVerdict::Continue);
+}
@@ -0,0 +1,429 @@
+use crate::{
+    logic::variants::CHECKS_TO_WIN,
+    models::{
+        board::Board,
+        error::{BoardError, ParseError},
+        field_content::FieldContent,
+        location::Location,
+        piece::{ColoredPiece, PieceColor, PieceKind},
+        piece_definition::PieceSet,
+        variant_kind::VariantKind,
+    },
+};
+
+pub const STANDARD_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+/// Forsyth-Edwards notation for board positions
+pub struct Fen;
+impl Fen {
+    /// Encodes the position. Castling rights are written as in X-FEN, which is
+    /// plain FEN for standard positions: `K` and `Q` stand for the outermost
+    /// rook of a side, only other rooks are named by their file.
+    pub fn encode(board: &Board) -> String {
+        Self::encode_with(board, false)
+    }
+
+    /// Encodes the position with castling rights as in Shredder-FEN, always
+    /// naming the file of the rook, e.g. `HAha`
+    pub fn encode_shredder(board: &Board) -> String {
+        Self::encode_with(board, true)
+    }
+
+    fn encode_with(board: &Board, shredder: bool) -> String {
+        let drops = board.variant().rules().has_drops();
+        let mut rows = vec![];
+        for y in (0..board.height).rev() {
+            let mut row = String::new();
+            let mut empty = 0;
+            for x in 0..board.width {
+                match board.field_at_xy(x, y) {
+                    Some(FieldContent::Occupied { piece, .. }) => {
+                        if empty > 0 {
+                            row += &empty.to_string();
+                            empty = 0;
+                        }
+                        row += &Self::encode_piece(piece);
+                        // crazyhouse marks promoted pieces, they are pawns in the pocket
+                        if drops && board.is_promoted(&Location::new(x, y)) {
+                            row.push('~');
+                        }
+                    }
+                    _ => empty += 1,
+                }
+            }
+            if empty > 0 {
+                row += &empty.to_string();
+            }
+            rows.push(row);
+        }
+
+        let pocket = if drops {
+            let pieces: String = [PieceColor::White, PieceColor::Black]
+                .into_iter()
+                .flat_map(|color| {
+                    board
+                        .pocket(color)
+                        .iter()
+                        .map(move |kind| Self::encode_piece(&ColoredPiece { kind: *kind, color }))
+                })
+                .collect();
+            format!("[{pieces}]")
+        } else {
+            String::new()
+        };
+
+        let side = match board.turn_color() {
+            PieceColor::White => "w",
+            PieceColor::Black => "b",
+        };
+        let castling = Self::encode_castling(board, shredder);
+        let en_passant = board
+            .en_passant()
+            .map_or("-".to_string(), |location| location.encode());
+        let fullmove = board.turn() / 2 + 1;
+        // three-check adds the checks each side has left, as lichess does
+        let checks = if board.variant() == VariantKind::ThreeCheck {
+            format!(
+                " {}+{}",
+                CHECKS_TO_WIN.saturating_sub(board.checks_given(PieceColor::White)),
+                CHECKS_TO_WIN.saturating_sub(board.checks_given(PieceColor::Black))
+            )
+        } else {
+            String::new()
+        };
+
+        format!(
+            "{}{pocket} {side} {castling} {en_passant}{checks} {} {fullmove}",
+            rows.join("/"),
+            board.halfmove_clock()
+        )
+    }
+
+    /// Parses a FEN string. Only the piece placement is required, the other
+    /// fields default to white to move without castling rights. The checks of
+    /// three-check may follow the en passant square, either as checks left
+    /// (`3+2`) or as checks given (`+0+1`). The pockets of crazyhouse follow
+    /// the placement in brackets, e.g. `[QPp]`, promoted pieces are marked by
+    /// a `~`.
+    pub fn decode(input: &str) -> Result<Board, BoardError> {
+        let at = |part: &str| Self::offset(input, part);
+        let error = |part: &str, message: &str| ParseError::new(input, at(part), message);
+        let mut parts = input.split_whitespace().peekable();
+        let placement =
+            parts
+                .next()
+                .ok_or(ParseError::new(input, 0, "expected a piece placement"))?;
+        let side = parts.next().unwrap_or("w");
+        let castling = parts.next().unwrap_or("-");
+        let en_passant = parts.next().unwrap_or("-");
+        let checks = parts.next_if(|part| part.contains('+'));
+        let halfmove = parts
+            .next()
+            .map(|halfmove| {
+                halfmove
+                    .parse::<usize>()
+                    .map_err(|_| error(halfmove, "expected a halfmove clock"))
+            })
+            .transpose()?
+            .unwrap_or(0);
+        let fullmove = parts
+            .next()
+            .map(|fullmove| {
+                fullmove
+                    .parse::<usize>()
+                    .map_err(|_| error(fullmove, "expected a fullmove number"))
+            })
+            .transpose()?
+            .unwrap_or(1);
+
+        let (placement, pocket) = match placement.split_once('[') {
+            Some((placement, pocket)) => (
+                placement,
+                pocket.strip_suffix(']').ok_or(ParseError::new(
+                    input,
+                    at(pocket) + pocket.len(),
+                    "expected ']' after the pocket",
+                ))?,
+            ),
+            None => (placement, ""),
+        };
+        let rows: Vec<Vec<Option<(ColoredPiece, bool)>>> = placement
+            .split('/')
+            .map(|row| Self::decode_row(row).map_err(|e| e.within(input, at(row))))
+            .collect::<Result<_, ParseError>>()?;
+        let height = rows.len();
+        let width = rows[0].len();
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(error(placement, "expected rows of the same length").into());
+        }
+
+        let mut board = Board::new(width, height);
+        for (row_index, row) in rows.into_iter().enumerate() {
+            let y = height - 1 - row_index;
+            for (x, piece) in row.into_iter().enumerate() {
+                if let Some((piece, promoted)) = piece {
+                    board.put_piece_on_field_xy(piece, (x, y), 0);
+                    board.set_promoted(&Location::new(x, y), promoted);
+                }
+            }
+        }
+        for (i, c) in pocket.char_indices() {
+            let color = if c.is_ascii_uppercase() {
+                PieceColor::White
+            } else {
+                PieceColor::Black
+            };
+            let kind = PieceKind::decode(c).map_err(|e| e.within(input, at(pocket) + i))?;
+            board.add_to_pocket(color, kind);
+        }
+
+        // kings and rooks without castling rights count as moved
+        let rights =
+            Self::decode_castling(&board, castling).map_err(|e| e.within(input, at(castling)))?;
+        for location in board.locations() {
+            let Some((piece, _)) = board
+                .field_at_location(&location)
+                .and_then(|f| f.get_content())
+            else {
+                continue;
+            };
+            let color_rights = rights.iter().filter(|(color, _)| *color == piece.color);
+            let keeps_right = match piece.kind {
+                PieceKind::King => {
+                    location.y == board.back_rank(piece.color) && color_rights.count() > 0
+                }
+                PieceKind::Rook => {
+                    location.y == board.back_rank(piece.color)
+                        && color_rights.into_iter().any(|(_, x)| *x == location.x)
+                }
+                _ => true,
+            };
+            if !keeps_right {
+                board.put_piece_on_location(piece, &location, 1);
+            }
+        }
+        // castling with a rook or king off their usual squares, or written
+        // with files, needs the chess960 rules
+        let standard_files = [0, board.width / 2, board.width - 1];
+        let unusual_castling = [PieceColor::White, PieceColor::Black]
+            .into_iter()
+            .filter(|color| !board.castling_rights(*color).is_empty())
+            .any(|color| {
+                let king = board.find_king(color).map(|king| king.x);
+                king != Some(board.width / 2)
+                    || board
+                        .castling_rights(color)
+                        .iter()
+                        .any(|x| !standard_files.contains(x))
+            });
+        let file_letters = castling
+            .chars()
+            .any(|c| c.is_ascii_alphabetic() && !"KQkq".contains(c));
+        board.set_chess960(unusual_castling || file_letters);
+
+        let side = PieceColor::decode(side.chars().next().unwrap_or('w'))
+            .map_err(|e| e.within(input, at(side)))?;
+        let turn = (fullmove.max(1) - 1) * 2 + usize::from(side == PieceColor::Black);
+        board.set_turn(turn);
+        board.set_halfmove_clock(halfmove);
+        if en_passant != "-" {
+            let location =
+                Location::decode(en_passant).map_err(|e| e.within(input, at(en_passant)))?;
+            board.set_en_passant(Some(location));
+        }
+        if let Some(checks) = checks {
+            let invalid = || error(checks, "expected checks like 3+3 or +0+0");
+            let (counts_given, counts) = match checks.strip_prefix('+') {
+                Some(given) => (true, given),
+                None => (false, checks),
+            };
+            let (white, black) = counts.split_once('+').ok_or_else(invalid)?;
+            for (color, count) in [(PieceColor::White, white), (PieceColor::Black, black)] {
+                let count = count.parse::<usize>().map_err(|_| invalid())?;
+                let given = if counts_given {
+                    count
+                } else {
+                    CHECKS_TO_WIN.saturating_sub(count)
+                };
+                board.set_checks_given(color, given);
+            }
+        }
+        Ok(board)
+    }
+
+    /// Parses a position of the variant, e.g. one given by the user, and
+    /// makes sure it is legal. `decode` alone accepts any arrangement.
+    pub fn decode_valid(input: &str, variant: VariantKind) -> Result<Board, BoardError> {
+        Self::decode_valid_with(input, variant, variant.rules().pieces())
+    }
+
+    /// like `decode_valid`, with the given fairy pieces instead of the ones
+    /// of the variant
+    pub fn decode_valid_with(
+        input: &str,
+        variant: VariantKind,
+        pieces: PieceSet,
+    ) -> Result<Board, BoardError> {
+        let mut board = Self::decode(input)?;
+        board.set_variant(variant);
+        board.set_piece_set(pieces);
+        board.validate()?;
+        Ok(board)
+    }
+
+    /// where a part of the input starts, the end for parts that are defaults
+    fn offset(input: &str, part: &str) -> usize {
+        (part.as_ptr() as usize)
+            .checked_sub(input.as_ptr() as usize)
+            .filter(|offset| *offset <= input.len())
+            .unwrap_or(input.len())
+    }
+
+    /// the pieces of a row, and whether they were promoted
+    fn decode_row(row: &str) -> Result<Vec<Option<(ColoredPiece, bool)>>, ParseError> {
+        let mut res = vec![];
+        let mut empty = String::new();
+        for (i, c) in row.char_indices() {
+            if c.is_ascii_digit() {
+                empty.push(c);
+                continue;
+            }
+            if c == '~' {
+                match res.last_mut() {
+                    Some(Some((_, promoted))) => *promoted = true,
+                    _ => return Err(ParseError::new(row, i, "expected a piece before '~'")),
+                }
+                continue;
+            }
+            if !empty.is_empty() {
+                let count = empty
+                    .parse::<usize>()
+                    .map_err(|_| ParseError::new(row, i - empty.len(), "too many empty squares"))?;
+                res.extend((0..count).map(|_| None));
+                empty.clear();
+            }
+            let color = if c.is_ascii_uppercase() {
+                PieceColor::White
+            } else {
+                PieceColor::Black
+            };
+            res.push(Some((
+                ColoredPiece {
+                    kind: PieceKind::decode(c).map_err(|e| e.within(row, i))?,
+                    color,
+                },
+                false,
+            )));
+        }
+        if !empty.is_empty() {
+            let count = empty.parse::<usize>().map_err(|_| {
+                ParseError::new(row, row.len() - empty.len(), "too many empty squares")
+            })?;
+            res.extend((0..count).map(|_| None));
+        }
+        Ok(res)
+    }
+
+    fn encode_piece(piece: &ColoredPiece) -> String {
+        let symbol = piece.kind.encode();
+        match piece.color {
+            PieceColor::White => symbol,
+            PieceColor::Black => symbol.to_lowercase(),
+        }
+    }
+
+    fn encode_castling(board: &Board, shredder: bool) -> String {
+        if !board.variant().rules().allows_castling() {
+            return "-".to_string();
+        }
+        let mut res = String::new();
+        for color in [PieceColor::White, PieceColor::Black] {
+            let Some(king) = board.find_king(color) else {
+                continue;
+            };
+            let rank = board.back_rank(color);
+            let rooks: Vec<usize> = (0..board.width)
+                .filter(|x| {
+                    board.location_contains_piece(
+                        &Location::new(*x, rank),
+                        vec![PieceKind::Rook],
+                        color,
+                    )
+                })
+                .collect();
+            let rights = board.castling_rights(color);
+            // kingside first, as in KQkq
+            let kingside = rights.iter().rev().filter(|x| **x > king.x);
+            let queenside = rights.iter().filter(|x| **x < king.x);
+            for x in kingside.chain(queenside) {
+                let outermost = if *x > king.x {
+                    rooks.last() == Some(x)
+                } else {
+                    rooks.first() == Some(x)
+                };
+                let symbol = match (shredder || !outermost, *x > king.x) {
+                    (true, _) => (b'A' + *x as u8) as char,
+                    (false, true) => 'K',
+                    (false, false) => 'Q',
+                };
+                res.push(match color {
+                    PieceColor::White => symbol,
+                    PieceColor::Black => symbol.to_ascii_lowercase(),
+                });
+            }
+        }
+        if res.is_empty() {
+            res.push('-');
+        }
+        res
+    }
+
+    /// the colors and rook files that may castle. `K`/`Q` refer to the
+    /// outermost rook on that side of the king, letters of files to the rook
+    /// on that file.
+    fn decode_castling(
+        board: &Board,
+        castling: &str,
+    ) -> Result<Vec<(PieceColor, usize)>, ParseError> {
+        let mut res = vec![];
+        for (i, c) in castling.char_indices().filter(|(_, c)| *c != '-') {
+            let color = if c.is_ascii_uppercase() {
+                PieceColor::White
+            } else {
+                PieceColor::Black
+            };
+            let rank = board.back_rank(color);
+            let king =
+                board
+                    .find_king(color)
+                    .filter(|king| king.y == rank)
+                    .ok_or(ParseError::new(
+                        castling,
+                        i,
+                        "expected a king on the back rank to castle",
+                    ))?;
+            let is_rook = |x: &usize| {
+                board.location_contains_piece(
+                    &Location::new(*x, rank),
+                    vec![PieceKind::Rook],
+                    color,
+                )
+            };
+            let file = match c.to_ascii_uppercase() {
+                'K' => (king.x + 1..board.width).rev().find(is_rook),
+                'Q' => (0..king.x).find(is_rook),
+                file @ 'A'..='Z' => Some((file as u8 - b'A') as usize).filter(is_rook),
+                _ => return Err(ParseError::new(castling, i, "expected a castling right")),
+            };
+            res.push((
+                color,
+                file.ok_or(ParseError::new(
+                    castling,
+                    i,
+                    "expected a rook to castle with",
+                ))?,
+            ));
+        }
+        Ok(res)
+    }
+}
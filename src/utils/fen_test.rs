@@ -0,0 +1,143 @@
+use std::error::Error;
+
+use super::fen::{Fen, STANDARD_FEN};
+use crate::{
+    driver::game::Game,
+    models::{board::Board, error::BoardError},
+};
+
+#[test]
+fn encode_standard_board() {
+    // arrange
+    let board = Board::standard_board();
+    // action
+    let encoded = Fen::encode(&board);
+    // assert
+    assert_eq!(encoded, STANDARD_FEN);
+}
+
+#[test]
+fn encode_after_moves() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e2e4")?;
+    game.apply_input("e7e5")?;
+    game.apply_input("e1e2")?;
+    // action
+    let encoded = Fen::encode(&game.board);
+    // assert
+    assert_eq!(
+        encoded,
+        "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 1 2"
+    );
+    Ok(())
+}
+
+#[test]
+fn halfmove_clock_resets_on_captures_and_pawn_moves() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::from_board(Fen::decode("4k3/8/8/3p4/8/8/4P3/4K1N1 w - - 37 60")?);
+    // action
+    game.apply_input("Nf3")?;
+    let after_knight = game.board.halfmove_clock();
+    game.apply_input("Ke7")?;
+    game.apply_input("e4")?;
+    let after_pawn = game.board.halfmove_clock();
+    game.apply_input("Ke6")?;
+    game.apply_input("Nd4")?;
+    game.apply_input("Kd6")?;
+    let before_capture = Fen::encode(&game.board);
+    game.apply_input("exd5")?;
+    // assert
+    assert_eq!(after_knight, 38);
+    assert_eq!(after_pawn, 0);
+    assert_eq!(before_capture, "8/8/3k4/3p4/3NP3/8/8/4K3 w - - 3 63");
+    assert_eq!(game.board.halfmove_clock(), 0);
+    Ok(())
+}
+
+#[test]
+fn decode_roundtrip() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let input = "r3k2r/8/8/8/8/8/8/R3K2R b Kq - 0 12";
+    // action
+    let board = Fen::decode(input)?;
+    // assert
+    assert_eq!(Fen::encode(&board), input);
+    Ok(())
+}
+
+#[test]
+fn decode_errors_point_at_the_field() {
+    // action
+    let piece = Fen::decode("4k3/8/8/8/8/8/8/4K2? w - - 0 1");
+    let castling = Fen::decode("4k3/8/8/8/8/8/8/4K3 w K - 0 1");
+    let side = Fen::decode("4k3/8/8/8/8/8/8/4K3 x - - 0 1");
+    let halfmove = Fen::decode("4k3/8/8/8/8/8/8/4K3 w - - many 1");
+    // assert
+    let position = |res: Result<Board, BoardError>| match res {
+        Err(BoardError::Parse(e)) => Some(e.position),
+        _ => None,
+    };
+    assert_eq!(position(piece), Some(19));
+    assert_eq!(position(castling), Some(22));
+    assert_eq!(position(side), Some(20));
+    assert_eq!(position(halfmove), Some(26));
+}
+
+#[test]
+fn chess960_start_positions() -> Result<(), Box<dyn Error>> {
+    // action
+    let standard = Board::chess960_board(518)?;
+    let first = Board::chess960_board(0)?;
+    let last = Board::chess960_board(959)?;
+    // assert
+    assert_eq!(Fen::encode(&standard), STANDARD_FEN);
+    assert_eq!(
+        Fen::encode(&first),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+    assert_eq!(
+        Fen::encode_shredder(&first),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w HFhf - 0 1"
+    );
+    assert_eq!(
+        Fen::encode_shredder(&last),
+        "rkrnnqbb/pppppppp/8/8/8/8/PPPPPPPP/RKRNNQBB w CAca - 0 1"
+    );
+    assert_eq!(
+        Board::chess960_board(960).map(|_| ()),
+        Err(BoardError::Chess960Index(960))
+    );
+    Ok(())
+}
+
+#[test]
+fn decode_shredder_and_x_fen() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let shredder = "r3k1rr/8/8/8/8/8/8/RR2K2R w HBga - 0 1";
+    let x_fen = "r3k1rr/8/8/8/8/8/8/RR2K2R w KBgq - 0 1";
+    // action
+    let from_shredder = Fen::decode(shredder)?;
+    let from_x_fen = Fen::decode(x_fen)?;
+    // assert
+    assert!(from_x_fen.is_chess960());
+    assert_eq!(Fen::encode(&from_shredder), x_fen);
+    assert_eq!(Fen::encode_shredder(&from_x_fen), shredder);
+    Ok(())
+}
+
+#[test]
+fn chess960_castling_notation() -> Result<(), Box<dyn Error>> {
+    // arrange: king on b1 with rooks on a1 and h1
+    let mut game = Game::from_board(Fen::decode("5k1r/8/8/8/8/8/8/RK5R w HAh - 0 1")?);
+    // action: king takes rook for castling, queenside the king moves right
+    game.apply_input("b1a1")?;
+    game.apply_input("O-O")?;
+    // assert
+    assert_eq!(
+        Fen::encode_shredder(&game.board),
+        "5rk1/8/8/8/8/8/8/2KR3R w - - 2 2"
+    );
+    Ok(())
+}
@@ -1 +1,19 @@
 pub mod board_creation;
+pub mod elo;
+pub mod fen;
+pub mod pgn;
+pub mod polyglot;
+mod polyglot_random;
+pub mod san;
+pub mod zobrist;
+
+#[cfg(test)]
+mod elo_test;
+#[cfg(test)]
+mod fen_test;
+#[cfg(test)]
+mod pgn_test;
+#[cfg(test)]
+mod polyglot_test;
+#[cfg(test)]
+mod zobrist_test;
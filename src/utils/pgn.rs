@@ -0,0 +1,438 @@
+use std::time::Duration;
+
+use crate::{
+    driver::game::Game,
+    models::{
+        board::Board,
+        clock::TimeControl,
+        error::{ParseError, PgnError},
+        game_state::GameState,
+        piece::PieceColor,
+        variant_kind::VariantKind,
+    },
+};
+
+use super::{fen::Fen, san::San};
+
+/// The raw content of a PGN game: its tags, the moves of the main line and
+/// what is written between them
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub moves: Vec<String>,
+    /// by the number of moves played before, one more than there are moves
+    pub notes: Vec<MoveNotes>,
+}
+
+/// Comments and variations that follow a move of the main line
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct MoveNotes {
+    pub comments: Vec<String>,
+    /// alternatives to the move as movetext, e.g. `1... c5 2. Nf3`
+    pub variations: Vec<String>,
+}
+
+/// What a review says about a move of the main line
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MoveAnnotation {
+    /// appended to the move, e.g. `?!`, `?` or `??`
+    pub glyph: Option<&'static str>,
+    /// written in braces after the move, e.g. `[%eval 0.35] Mistake.`
+    pub comment: Option<String>,
+}
+
+/// Portable game notation. Besides the standard tags, the remaining time of
+/// both players is stored in the `WhiteClock` and `BlackClock` tags (seconds),
+/// and a pending draw offer in the `DrawOffer` tag, so that games can be
+/// resumed.
+pub struct Pgn;
+impl Pgn {
+    pub fn encode(game: &Game) -> String {
+        Self::encode_with_tags(game, &[])
+    }
+
+    /// like `encode`, the given tags replace the standard tags of the same
+    /// name or are added after them
+    pub fn encode_with_tags(game: &Game, extra_tags: &[(&str, String)]) -> String {
+        Self::encode_annotated(game, extra_tags, &[])
+    }
+
+    /// like `encode_with_tags`, with the annotations of the moves by ply
+    pub fn encode_annotated(
+        game: &Game,
+        extra_tags: &[(&str, String)],
+        annotations: &[MoveAnnotation],
+    ) -> String {
+        let result = game.state.result();
+        let mut tags = vec![
+            ("Event", "rustychess game".to_string()),
+            ("Site", "?".to_string()),
+            ("Date", "????.??.??".to_string()),
+            ("Round", "-".to_string()),
+            ("White", "?".to_string()),
+            ("Black", "?".to_string()),
+            ("Result", result.to_string()),
+        ];
+        if let Some(termination) = game.state.termination() {
+            tags.push(("Termination", termination.to_string()));
+        }
+        tags.extend([
+            ("SetUp", "1".to_string()),
+            ("FEN", Fen::encode(&game.start)),
+        ]);
+        if game.start.variant() != VariantKind::Standard {
+            tags.push(("Variant", game.start.variant().encode().to_string()));
+        } else if game.start.is_chess960() {
+            tags.push(("Variant", "Chess960".to_string()));
+        }
+        if let Some(clock) = &game.clock {
+            tags.push(("TimeControl", clock.control().encode()));
+            for (tag, color) in [
+                ("WhiteClock", PieceColor::White),
+                ("BlackClock", PieceColor::Black),
+            ] {
+                tags.push((tag, format!("{:.1}", clock.remaining(color).as_secs_f64())));
+            }
+        }
+        if let Some(color) = game.draw_offer {
+            tags.push(("DrawOffer", format!("{color:?}")));
+        }
+        for (tag, value) in extra_tags {
+            match tags.iter_mut().find(|(name, _)| name == tag) {
+                Some((_, existing)) => *existing = value.clone(),
+                None => tags.push((tag, value.clone())),
+            }
+        }
+
+        let mut res: String = tags
+            .iter()
+            .map(|(tag, value)| format!("[{tag} \"{}\"]\n", value.replace('"', "\\\"")))
+            .collect();
+        res.push('\n');
+
+        let mut tokens = vec![];
+        let mut board = game.start.clone();
+        // Black's moves need their number again after a comment
+        let mut commented = false;
+        for (index, step) in game.history.iter().enumerate() {
+            let move_number = board.turn() / 2 + 1;
+            if board.turn_color() == PieceColor::White {
+                tokens.push(format!("{move_number}."));
+            } else if index == 0 || commented {
+                tokens.push(format!("{move_number}..."));
+            }
+            let annotation = annotations.get(index).cloned().unwrap_or_default();
+            tokens.push(San::encode(&board, step) + annotation.glyph.unwrap_or_default());
+            commented = annotation.comment.is_some();
+            if let Some(comment) = annotation.comment {
+                tokens.push(format!("{{ {comment} }}"));
+            }
+            board
+                .apply_step_kind(step)
+                .expect("steps in the history have been applied before");
+        }
+        if let (GameState::Forfeit(color), Some(reason)) = (game.state, &game.forfeit_reason) {
+            tokens.push(format!("{{ {color:?} forfeits: {reason} }}"));
+        }
+        tokens.push(result.to_string());
+        res += &Self::wrap(&tokens);
+        res
+    }
+
+    /// reads a game and replays its moves
+    pub fn decode(input: &str) -> Result<Game, PgnError> {
+        let pgn_game = Self::parse(input)?;
+        let tag = |name: &str| {
+            pgn_game
+                .tags
+                .iter()
+                .find(|(tag, _)| tag == name)
+                .map(|(_, value)| value.as_str())
+        };
+
+        let chess960 = tag("Variant").is_some_and(|variant| {
+            ["chess960", "chess 960", "fischerandom"].contains(&variant.to_lowercase().as_str())
+        });
+        let variant = match tag("Variant") {
+            Some(name) if !chess960 => {
+                Some(VariantKind::decode(name).map_err(|_| Self::invalid_tag("Variant", name))?)
+            }
+            _ => None,
+        };
+        let mut board = match (tag("FEN"), variant) {
+            (Some(fen), variant) => Fen::decode_valid(fen, variant.unwrap_or_default())?,
+            (None, Some(variant)) => variant.rules().start_position(),
+            (None, None) => Board::standard_board(),
+        };
+        if chess960 {
+            board.set_chess960(true);
+        }
+        if let Some(variant) = variant {
+            board.set_variant(variant);
+        }
+        let mut game = Game::from_board(board);
+        if let Some(time_control) = tag("TimeControl").filter(|tc| *tc != "-") {
+            let time_control = TimeControl::decode(time_control)
+                .map_err(|_| Self::invalid_tag("TimeControl", time_control))?;
+            game.set_time_control(time_control);
+        }
+        for (ply, san) in pgn_game.moves.iter().enumerate() {
+            San::decode(&game.board, san)
+                .and_then(|step| game.apply_stepkind(step))
+                .map_err(|error| PgnError::Move { ply, error })?;
+        }
+        if let Some(clock) = &mut game.clock {
+            for (name, color) in [
+                ("WhiteClock", PieceColor::White),
+                ("BlackClock", PieceColor::Black),
+            ] {
+                if let Some(remaining) = tag(name) {
+                    let secs = remaining
+                        .parse::<f64>()
+                        .map_err(|_| Self::invalid_tag(name, remaining))?;
+                    clock.set_remaining(color, Duration::from_secs_f64(secs.max(0.0)));
+                }
+            }
+        }
+
+        // the result is only needed if the board doesn't show it, e.g. mate
+        if game.state == GameState::Ongoing {
+            game.state = Self::decode_state(tag("Result"), tag("Termination"))?;
+        }
+        if let GameState::Forfeit(color) = game.state {
+            let prefix = format!("{color:?} forfeits: ");
+            game.forfeit_reason = pgn_game
+                .notes
+                .last()
+                .into_iter()
+                .flat_map(|notes| &notes.comments)
+                .find_map(|comment| comment.strip_prefix(&prefix))
+                .map(str::to_string);
+        }
+        game.draw_offer = match tag("DrawOffer") {
+            _ if game.state.is_over() => None,
+            Some("White") => Some(PieceColor::White),
+            Some("Black") => Some(PieceColor::Black),
+            Some(offer) => return Err(Self::invalid_tag("DrawOffer", offer)),
+            None => None,
+        };
+        Ok(game)
+    }
+
+    /// how a game ended that the board doesn't tell, by the `Result` and the
+    /// `Termination` tag, see `GameState::termination`. Without a special
+    /// termination a decisive result means the game was given up.
+    fn decode_state(
+        result: Option<&str>,
+        termination: Option<&str>,
+    ) -> Result<GameState, PgnError> {
+        let winner = match result.unwrap_or("*") {
+            "1-0" => Some(PieceColor::White),
+            "0-1" => Some(PieceColor::Black),
+            "1/2-1/2" => None,
+            "*" if termination.is_some_and(|t| t.eq_ignore_ascii_case("abandoned")) => {
+                return Ok(GameState::Aborted)
+            }
+            "*" => return Ok(GameState::Ongoing),
+            other => return Err(Self::invalid_tag("Result", other)),
+        };
+        let termination = termination.unwrap_or("normal").to_lowercase();
+        Ok(match (termination.as_str(), winner) {
+            ("time forfeit", Some(color)) => GameState::WonOnTime(color),
+            ("time forfeit", None) => GameState::TimeoutDraw,
+            ("tablebase adjudication" | "adjudication", Some(color)) => {
+                GameState::TablebaseWin(color)
+            }
+            ("tablebase adjudication", None) => GameState::TablebaseDraw,
+            ("adjudication", None) => GameState::AdjudicatedDraw,
+            ("rules infraction", Some(color)) => GameState::Forfeit(color.invert()),
+            ("abandoned", _) => GameState::Aborted,
+            (_, Some(color)) => GameState::Resigned(color.invert()),
+            (_, None) => GameState::DrawAgreed,
+        })
+    }
+
+    /// splits a file with several games into the single games
+    pub fn split_games(input: &str) -> Vec<String> {
+        let mut games = vec![];
+        let mut current = String::new();
+        let mut in_movetext = false;
+        for line in input.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with('[') && in_movetext {
+                games.push(std::mem::take(&mut current));
+                in_movetext = false;
+            } else if !trimmed.is_empty() && !trimmed.starts_with('[') {
+                in_movetext = true;
+            }
+            current += line;
+            current.push('\n');
+        }
+        if !current.trim().is_empty() {
+            games.push(current);
+        }
+        games
+    }
+
+    /// splits a single game into tags, main line moves and the comments and
+    /// variations after them. Move numbers and annotation glyphs are skipped.
+    pub fn parse(input: &str) -> Result<PgnGame, PgnError> {
+        let mut res = PgnGame::default();
+        let mut lines = input.lines().peekable();
+        while let Some(line) = lines.peek() {
+            let line = line.trim();
+            if line.is_empty() {
+                lines.next();
+                continue;
+            }
+            if !line.starts_with('[') {
+                break;
+            }
+            res.tags.push(Self::parse_tag(line)?);
+            lines.next();
+        }
+
+        let movetext: Vec<&str> = lines.collect();
+        let movetext = movetext.join("\n");
+        let mut chars = movetext.char_indices().peekable();
+        let mut depth = 0;
+        let mut token = String::new();
+        // the tokens of the variation being read, nested ones included
+        let mut variation: Vec<String> = vec![];
+        res.notes.push(MoveNotes::default());
+        while let Some((position, c)) = chars.next() {
+            match c {
+                '{' | ';' => {
+                    let end = if c == '{' { '}' } else { '\n' };
+                    let comment: String = chars
+                        .by_ref()
+                        .map(|(_, c)| c)
+                        .take_while(|c| *c != end)
+                        .collect();
+                    let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if comment.is_empty() {
+                        continue;
+                    }
+                    if depth == 0 {
+                        Self::last_notes(&mut res).comments.push(comment);
+                    } else {
+                        variation.push(format!("{{{comment}}}"));
+                    }
+                }
+                '(' => {
+                    if depth > 0 {
+                        variation.push("(".to_string());
+                    }
+                    depth += 1;
+                }
+                ')' => {
+                    if depth == 0 {
+                        let e = ParseError::new(&movetext, position, "unbalanced ')'");
+                        return Err(e.into());
+                    }
+                    depth -= 1;
+                    if depth > 0 {
+                        variation.push(")".to_string());
+                    } else if !variation.is_empty() {
+                        let text = variation.join(" ").replace("( ", "(").replace(" )", ")");
+                        Self::last_notes(&mut res).variations.push(text);
+                        variation.clear();
+                    }
+                }
+                c if c.is_whitespace() => {}
+                _ => {
+                    token.push(c);
+                    while let Some((_, next)) = chars.peek() {
+                        if next.is_whitespace() || "{}();".contains(*next) {
+                            break;
+                        }
+                        token.push(*next);
+                        chars.next();
+                    }
+                    if depth > 0 {
+                        variation.push(std::mem::take(&mut token));
+                    } else if let Some(san) = Self::move_token(&token) {
+                        res.moves.push(san);
+                        res.notes.push(MoveNotes::default());
+                    }
+                    token.clear();
+                }
+            }
+        }
+        if depth != 0 {
+            let e = ParseError::new(&movetext, movetext.len(), "unbalanced '('");
+            return Err(e.into());
+        }
+        Ok(res)
+    }
+
+    fn invalid_tag(name: &str, value: &str) -> PgnError {
+        PgnError::InvalidTag {
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// the notes of the last move read so far
+    fn last_notes(game: &mut PgnGame) -> &mut MoveNotes {
+        game.notes
+            .last_mut()
+            .expect("there are notes before the first move")
+    }
+
+    fn parse_tag(line: &str) -> Result<(String, String), ParseError> {
+        let inner = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+            .ok_or(ParseError::new(line, 0, "expected a tag in brackets"))?;
+        let (name, value) =
+            inner
+                .split_once(' ')
+                .ok_or(ParseError::new(line, 1, "expected a name and a value"))?;
+        let value = value
+            .trim()
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .ok_or(ParseError::new(
+                line,
+                name.len() + 2,
+                "expected a quoted value",
+            ))?;
+        Ok((name.to_string(), value.replace("\\\"", "\"")))
+    }
+
+    /// strips move numbers from a movetext token, returns `None` for tokens
+    /// that are not moves
+    fn move_token(token: &str) -> Option<String> {
+        if ["1-0", "0-1", "1/2-1/2", "*"].contains(&token) || token.starts_with('$') {
+            return None;
+        }
+        let san = match token.rfind('.') {
+            Some(dot) => &token[dot + 1..],
+            None => token,
+        };
+        if san.is_empty() || san.chars().all(|c| c.is_ascii_digit()) {
+            None
+        } else {
+            Some(san.to_string())
+        }
+    }
+
+    fn wrap(tokens: &[String]) -> String {
+        let mut res = String::new();
+        let mut line_length = 0;
+        for token in tokens {
+            if line_length > 0 && line_length + token.len() + 1 > 80 {
+                res.push('\n');
+                line_length = 0;
+            } else if line_length > 0 {
+                res.push(' ');
+                line_length += 1;
+            }
+            res += token;
+            line_length += token.len();
+        }
+        res.push('\n');
+        res
+    }
+}
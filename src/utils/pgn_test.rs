@@ -0,0 +1,229 @@
+use std::{error::Error, time::Duration};
+
+use super::pgn::Pgn;
+use crate::{
+    driver::game::Game,
+    models::{
+        clock::TimeControl,
+        error::{MoveError, PgnError},
+        game_state::GameState,
+        piece::PieceColor,
+        variant_kind::VariantKind,
+    },
+    utils::fen::{Fen, STANDARD_FEN},
+};
+
+#[test]
+fn encode_moves_as_san() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    for input in ["e2e4", "a7a6", "d1h5", "a6a5", "f1c4", "a5a4", "h5f7"] {
+        game.apply_input(input)?;
+    }
+    // action
+    let pgn = Pgn::encode(&game);
+    // assert
+    assert!(pgn.contains("[Result \"1-0\"]"));
+    assert!(pgn.contains(&format!("[FEN \"{STANDARD_FEN}\"]")));
+    assert!(pgn.ends_with("1. e4 a6 2. Qh5 a5 3. Bc4 a4 4. Qxf7# 1-0\n"));
+    Ok(())
+}
+
+#[test]
+fn save_and_resume() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::with_time_control(TimeControl::fischer(
+        Duration::from_secs(180),
+        Duration::from_secs(2),
+    ));
+    game.tick(Duration::from_secs(5));
+    game.apply_input("e2e4")?;
+    game.tick(Duration::from_secs(12));
+    game.apply_input("b8c6")?;
+    // action
+    let resumed = Pgn::decode(&Pgn::encode(&game))?;
+    // assert
+    assert_eq!(resumed.history, game.history);
+    assert_eq!(Fen::encode(&resumed.board), Fen::encode(&game.board));
+    assert_eq!(resumed.state, GameState::Ongoing);
+    assert_eq!(
+        resumed.remaining_time(PieceColor::White),
+        Some(Duration::from_secs(177))
+    );
+    assert_eq!(
+        resumed.remaining_time(PieceColor::Black),
+        Some(Duration::from_secs(170))
+    );
+    Ok(())
+}
+
+#[test]
+fn resume_with_black_to_move() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1")?;
+    let mut game = Game::from_board(board);
+    game.set_time_control(TimeControl::fischer(
+        Duration::from_secs(60),
+        Duration::ZERO,
+    ));
+    game.apply_input("e8d8")?;
+    game.apply_input("e2e4")?;
+    // action
+    let mut resumed = Pgn::decode(&Pgn::encode(&game))?;
+    resumed.tick(Duration::from_secs(10));
+    // assert
+    let active = resumed.clock.as_ref().map(|clock| clock.active());
+    assert_eq!(active, Some(PieceColor::Black));
+    assert_eq!(
+        resumed.remaining_time(PieceColor::Black),
+        Some(Duration::from_secs(50))
+    );
+    assert_eq!(
+        resumed.remaining_time(PieceColor::White),
+        Some(Duration::from_secs(60))
+    );
+    Ok(())
+}
+
+#[test]
+fn every_end_of_a_game_round_trips() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut mated = Game::new();
+    for input in ["f3", "e5", "g4", "Qh4"] {
+        mated.apply_input(input)?;
+    }
+    let mut stalemate = Game::from_board(Fen::decode("7k/8/6Q1/8/8/8/8/K7 w - - 0 1")?);
+    stalemate.apply_input("Qf7")?;
+    let mut played = Game::new();
+    played.apply_input("e4")?;
+    let states = [
+        GameState::Ongoing,
+        GameState::WonOnTime(PieceColor::White),
+        GameState::TimeoutDraw,
+        GameState::Resigned(PieceColor::White),
+        GameState::Forfeit(PieceColor::Black),
+        GameState::DrawAgreed,
+        GameState::Aborted,
+        GameState::TablebaseWin(PieceColor::Black),
+        GameState::TablebaseDraw,
+        GameState::AdjudicatedDraw,
+    ];
+    let mut games: Vec<Game> = states
+        .into_iter()
+        .map(|state| Game {
+            state,
+            ..played.clone()
+        })
+        .collect();
+    games.push(mated);
+    games.push(stalemate);
+    for game in games {
+        // action
+        let resumed = Pgn::decode(&Pgn::encode(&game))?;
+        // assert
+        assert_eq!(resumed.state, game.state);
+    }
+    Ok(())
+}
+
+#[test]
+fn pending_draw_offer_round_trips() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut game = Game::new();
+    game.apply_input("e4")?;
+    game.offer_draw(PieceColor::White)?;
+    // action
+    let pgn = Pgn::encode(&game);
+    let mut resumed = Pgn::decode(&pgn)?;
+    // assert
+    assert!(pgn.contains("[DrawOffer \"White\"]"));
+    assert_eq!(resumed.draw_offer, Some(PieceColor::White));
+    assert_eq!(
+        resumed.accept_draw(PieceColor::Black)?,
+        GameState::DrawAgreed
+    );
+    Ok(())
+}
+
+#[test]
+fn decode_skips_comments_and_variations() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let input = r#"[Event "test"]
+[Result "0-1"]
+
+1. e4 {best by test} e5 (1... c5 2. Nf3) 2. Nf3 $1 Nc6 ; a comment
+3. Bb5 0-1
+"#;
+    // action
+    let game = Pgn::decode(input)?;
+    // assert
+    assert_eq!(game.history.len(), 5);
+    assert_eq!(game.state, GameState::Resigned(PieceColor::White));
+    Ok(())
+}
+
+#[test]
+fn parse_keeps_comments_and_variations() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let input = r#"[Event "test"]
+
+{Opening} 1. e4 {best by test} e5 (1... c5 {Sicilian} 2. Nf3 (2. c3)) (1... e6)
+2. Nf3 ; a comment
+Nc6 *
+"#;
+    // action
+    let pgn_game = Pgn::parse(input)?;
+    // assert
+    assert_eq!(pgn_game.moves, vec!["e4", "e5", "Nf3", "Nc6"]);
+    assert_eq!(pgn_game.notes.len(), 5);
+    assert_eq!(pgn_game.notes[0].comments, vec!["Opening"]);
+    assert_eq!(pgn_game.notes[1].comments, vec!["best by test"]);
+    assert_eq!(
+        pgn_game.notes[2].variations,
+        vec!["1... c5 {Sicilian} 2. Nf3 (2. c3)", "1... e6"]
+    );
+    assert_eq!(pgn_game.notes[3].comments, vec!["a comment"]);
+    assert!(pgn_game.notes[4].comments.is_empty());
+    Ok(())
+}
+
+#[test]
+fn decode_errors_tell_what_is_wrong() {
+    // action
+    let unbalanced = Pgn::parse("1. e4 e5 (1... c5))");
+    let tag = Pgn::decode("[Result \"2-0\"]\n\n1. e4 *");
+    let illegal = Pgn::decode("1. e4 e5 2. Ke3 *");
+    // assert
+    assert!(matches!(unbalanced, Err(PgnError::Parse(e)) if e.position == 18));
+    assert_eq!(
+        tag.map(|_| ()),
+        Err(PgnError::InvalidTag {
+            name: "Result".to_string(),
+            value: "2-0".to_string()
+        })
+    );
+    assert_eq!(
+        illegal.map(|_| ()),
+        Err(PgnError::Move {
+            ply: 2,
+            error: MoveError::NoMatch("Ke3".to_string())
+        })
+    );
+}
+
+#[test]
+fn variant_tag_picks_the_rules_and_start_position() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let input = r#"[Variant "Horde"]
+
+1. d5 e6 *
+"#;
+    // action
+    let game = Pgn::decode(input)?;
+    let pgn = Pgn::encode(&game);
+    // assert
+    assert_eq!(game.board.variant(), VariantKind::Horde);
+    assert!(pgn.contains("[Variant \"Horde\"]"));
+    assert_eq!(Pgn::decode(&pgn)?.board.variant(), VariantKind::Horde);
+    Ok(())
+}
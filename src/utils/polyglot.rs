@@ -0,0 +1,141 @@
+use crate::{
+    logic::move_computer::StepComputer,
+    models::{
+        board::Board,
+        location::Location,
+        piece::{ColoredPiece, PieceColor, PieceKind},
+        step::StepKind,
+    },
+};
+
+use super::polyglot_random::RANDOM64;
+
+// offsets into the random numbers after the 768 of the pieces
+const CASTLING_OFFSET: usize = 768;
+const EN_PASSANT_OFFSET: usize = 772;
+const TURN_OFFSET: usize = 780;
+
+/// The position hash and move encoding of Polyglot opening books. Both are
+/// only defined for 8x8 boards with the standard pieces.
+pub struct Polyglot;
+
+impl Polyglot {
+    /// whether positions of the board can be looked up in a Polyglot book
+    pub fn supports(board: &Board) -> bool {
+        board.width == 8
+            && board.height == 8
+            && board.locations().iter().all(|location| {
+                board
+                    .field_at_location(location)
+                    .and_then(|field| field.get_content())
+                    .is_none_or(|(piece, _)| Self::piece_index(piece).is_some())
+            })
+    }
+
+    /// The Polyglot key of the position: the random numbers of all pieces,
+    /// castling rights, the en passant file and the side to move xored
+    /// together. The en passant file only counts if a pawn of the side to
+    /// move stands next to the pawn that can be taken.
+    pub fn hash(board: &Board) -> u64 {
+        let mut key = 0;
+        for location in board.locations() {
+            let Some((piece, _)) = board
+                .field_at_location(&location)
+                .and_then(|field| field.get_content())
+            else {
+                continue;
+            };
+            if let Some(index) = Self::piece_index(piece) {
+                key ^= RANDOM64[64 * index + 8 * location.y + location.x];
+            }
+        }
+
+        for (offset, color) in [(0, PieceColor::White), (2, PieceColor::Black)] {
+            let Some(king) = board.find_king(color) else {
+                continue;
+            };
+            for file in board.castling_rights(color) {
+                let long = usize::from(file < king.x);
+                key ^= RANDOM64[CASTLING_OFFSET + offset + long];
+            }
+        }
+
+        let color = board.turn_color();
+        if let Some(en_passant) = board.en_passant() {
+            let rank = match color {
+                PieceColor::White => en_passant.y - 1,
+                PieceColor::Black => en_passant.y + 1,
+            };
+            let pawn = ColoredPiece {
+                kind: PieceKind::Pawn,
+                color,
+            };
+            let capturable = [en_passant.x.wrapping_sub(1), en_passant.x + 1]
+                .into_iter()
+                .filter(|x| *x < board.width)
+                .any(|x| {
+                    board
+                        .field_at_xy(x, rank)
+                        .and_then(|field| field.get_content())
+                        .is_some_and(|(piece, _)| piece == pawn)
+                });
+            if capturable {
+                key ^= RANDOM64[EN_PASSANT_OFFSET + en_passant.x];
+            }
+        }
+
+        if color == PieceColor::White {
+            key ^= RANDOM64[TURN_OFFSET];
+        }
+        key
+    }
+
+    /// Moves are stored in 16 bits: the target square in the lowest 6, then
+    /// the start square and the promotion piece. Castling is written as the
+    /// king taking its own rook.
+    pub fn encode_step(step: &StepKind) -> Option<u16> {
+        let square = |location: Location| (location.y * 8 + location.x) as u16;
+        let (from, to, promotion) = match step {
+            StepKind::GoTo(step) => (step.from, step.to, 0),
+            StepKind::Promote { step, piece } => {
+                let promotion = match piece {
+                    PieceKind::Knight => 1,
+                    PieceKind::Bishop => 2,
+                    PieceKind::Rook => 3,
+                    PieceKind::Queen => 4,
+                    _ => return None,
+                };
+                (step.from, step.to, promotion)
+            }
+            StepKind::Castle {
+                king_step,
+                rook_step,
+            } => (king_step.from, rook_step.from, 0),
+            StepKind::Drop { .. } => return None,
+        };
+        Some(square(to) | square(from) << 6 | promotion << 12)
+    }
+
+    /// the legal step of the position stored as the given book move
+    pub fn decode_step(board: &Board, encoded: u16) -> Option<StepKind> {
+        board
+            .compute_steps()
+            .into_iter()
+            .find(|step| Self::encode_step(step) == Some(encoded))
+    }
+
+    /// black pawn, white pawn, black knight, white knight and so on up to
+    /// the white king
+    fn piece_index(piece: ColoredPiece) -> Option<usize> {
+        let kind = match piece.kind {
+            PieceKind::Pawn => 0,
+            PieceKind::Knight => 1,
+            PieceKind::Bishop => 2,
+            PieceKind::Rook => 3,
+            PieceKind::Queen => 4,
+            PieceKind::King => 5,
+            PieceKind::Fairy(_) => return None,
+        };
+        Some(2 * kind + usize::from(piece.color == PieceColor::White))
+    }
+}
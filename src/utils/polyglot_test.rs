@@ -0,0 +1,91 @@
+use std::error::Error;
+
+use super::{fen::Fen, polyglot::Polyglot};
+use crate::models::{
+    location::Location,
+    piece::PieceKind,
+    step::{Step, StepKind},
+};
+
+#[test]
+fn hash_matches_the_reference_keys() -> Result<(), Box<dyn Error>> {
+    // the examples of the Polyglot format description
+    for (fen, expected) in [
+        (
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            0x463b96181691fc9c,
+        ),
+        (
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1",
+            0x823c9b50fd114196,
+        ),
+        (
+            "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+            0x0756b94461c50fb0,
+        ),
+        (
+            "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 2",
+            0x662fafb965db29d4,
+        ),
+        (
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            0x22a48b5a8e47ff78,
+        ),
+        (
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR b kq - 0 3",
+            0x652a607ca3f242c1,
+        ),
+        (
+            "rnbq1bnr/ppp1pkpp/8/3pPp2/8/8/PPPPKPPP/RNBQ1BNR w - - 0 4",
+            0x00fdd303c946bdd9,
+        ),
+        (
+            "rnbqkbnr/p1pppppp/8/8/PpP4P/8/1P1PPPP1/RNBQKBNR b KQkq c3 0 3",
+            0x3c8123ea7b067637,
+        ),
+        (
+            "rnbqkbnr/p1pppppp/8/8/P6P/R1p5/1P1PPPP1/1NBQKBNR b Kkq - 0 4",
+            0x5c3f9b829b279560,
+        ),
+    ] {
+        // arrange
+        let board = Fen::decode(fen)?;
+        // action
+        let hash = Polyglot::hash(&board);
+        // assert
+        assert_eq!(hash, expected, "{fen}");
+    }
+    Ok(())
+}
+
+#[test]
+fn steps_round_trip() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let board = Fen::decode("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1")?;
+    let castle = StepKind::Castle {
+        king_step: Step::new((4, 0), (6, 0)),
+        rook_step: Step::new((7, 0), (5, 0)),
+    };
+    let capture_promotion = StepKind::Promote {
+        step: Step::new((1, 6), (0, 7)),
+        piece: PieceKind::Knight,
+    };
+    // action
+    let castle_encoded = Polyglot::encode_step(&castle).ok_or("castling")?;
+    let promotion_encoded = Polyglot::encode_step(&capture_promotion).ok_or("promotion")?;
+    // assert
+    // the king takes its own rook: e1h1
+    assert_eq!(castle_encoded, 7 | 4 << 6);
+    assert_eq!(Polyglot::decode_step(&board, castle_encoded), Some(castle));
+    assert_eq!(
+        Polyglot::decode_step(&board, promotion_encoded),
+        Some(capture_promotion)
+    );
+    // a drop has no Polyglot encoding
+    let drop = StepKind::Drop {
+        piece: PieceKind::Pawn,
+        to: Location::new(4, 3),
+    };
+    assert_eq!(Polyglot::encode_step(&drop), None);
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use crate::{
+    logic::move_computer::StepComputer,
+    models::{
+        board::Board,
+        error::MoveError,
+        field_content::FieldContent,
+        piece::PieceKind,
+        step::{Step, StepKind},
+    },
+};
+
+/// Standard algebraic notation, e.g. `Nbd7`, `exd5`, `O-O`, `e8=Q+` or `N@f3`
+pub struct San;
+impl San {
+    /// encodes a legal step in the given position
+    pub fn encode(board: &Board, step_kind: &StepKind) -> String {
+        let legal_steps = board.compute_steps();
+        let mut res = Self::encode_without_check(board, step_kind, &legal_steps);
+
+        let mut next_board = board.clone();
+        if next_board.apply_step_kind(step_kind).is_ok() {
+            let eval = next_board.evaluate_basic();
+            if eval.has_checkmate {
+                res.push('#');
+            } else if eval.has_check {
+                res.push('+');
+            }
+        }
+        res
+    }
+
+    /// finds the legal step in the given position matching the input. Check and
+    /// annotation suffixes are ignored, coordinate notation like `e2e4` is
+    /// accepted as well.
+    pub fn decode(board: &Board, input: &str) -> Result<StepKind, MoveError> {
+        let normalize = |san: &str| {
+            san.trim_end_matches(['+', '#', '!', '?'])
+                .replace('0', "O")
+                .replace('=', "")
+        };
+        let target = normalize(input.trim());
+        let legal_steps = board.compute_steps();
+        let matching: Vec<&StepKind> = legal_steps
+            .iter()
+            .filter(|step| {
+                normalize(&Self::encode_without_check(board, step, &legal_steps)) == target
+            })
+            .collect();
+        match matching.as_slice() {
+            [step] => Ok((*step).clone()),
+            [] => {
+                // coordinate notation, castling as king step or king takes rook
+                let input = input.trim();
+                let step = StepKind::decode(input).ok();
+                let legal = legal_steps.into_iter().find(|legal| {
+                    Some(legal) == step.as_ref()
+                        || legal.encode_uci() == input.to_lowercase()
+                        || legal.encode_uci_chess960() == input.to_lowercase()
+                });
+                match (legal, step) {
+                    (Some(legal), _) => Ok(legal),
+                    // tell why a move in coordinates is not possible
+                    (None, Some(step)) => Err(board.illegal_step_error(&step)),
+                    (None, None) => Err(MoveError::NoMatch(input.to_string())),
+                }
+            }
+            _ => Err(MoveError::Ambiguous(input.to_string())),
+        }
+    }
+
+    fn encode_without_check(
+        board: &Board,
+        step_kind: &StepKind,
+        legal_steps: &[StepKind],
+    ) -> String {
+        let (step, promotion) = match step_kind {
+            StepKind::Castle {
+                king_step,
+                rook_step,
+            } => {
+                return if rook_step.from.x < king_step.from.x {
+                    "O-O-O".to_string()
+                } else {
+                    "O-O".to_string()
+                };
+            }
+            StepKind::Drop { .. } => return step_kind.encode(),
+            StepKind::GoTo(step) => (step, None),
+            StepKind::Promote { step, piece } => (step, Some(piece)),
+        };
+        let Some((piece, _)) = board
+            .field_at_location(&step.from)
+            .and_then(FieldContent::get_content)
+        else {
+            return step_kind.encode();
+        };
+        let is_capture = !board.location_is_emtpy(&step.to)
+            || (piece.kind == PieceKind::Pawn && board.en_passant() == Some(step.to));
+
+        let mut res = String::new();
+        if piece.kind == PieceKind::Pawn {
+            if is_capture {
+                res += &step.from.encode()[..1];
+            }
+        } else {
+            res += &piece.kind.encode();
+            res += &Self::disambiguation(board, step, piece.kind, legal_steps);
+        }
+        if is_capture {
+            res.push('x');
+        }
+        res += &step.to.encode();
+        if let Some(promotion) = promotion {
+            res += &format!("={}", promotion.encode());
+        }
+        res
+    }
+
+    /// the part of the origin square needed to tell apart pieces of the same
+    /// kind that can reach the same target
+    fn disambiguation(
+        board: &Board,
+        step: &Step,
+        kind: PieceKind,
+        legal_steps: &[StepKind],
+    ) -> String {
+        let others: Vec<Step> = legal_steps
+            .iter()
+            .filter_map(|other| match other {
+                StepKind::GoTo(other) if other.to == step.to && other.from != step.from => {
+                    Some(*other)
+                }
+                _ => None,
+            })
+            .filter(|other| {
+                board
+                    .field_at_location(&other.from)
+                    .and_then(FieldContent::get_content)
+                    .is_some_and(|(piece, _)| piece.kind == kind)
+            })
+            .collect();
+        let encoded = step.from.encode();
+        if others.is_empty() {
+            String::new()
+        } else if others.iter().all(|other| other.from.x != step.from.x) {
+            encoded[..1].to_string()
+        } else if others.iter().all(|other| other.from.y != step.from.y) {
+            encoded[1..].to_string()
+        } else {
+            encoded
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::models::{
+    board::Board,
+    location::Location,
+    piece::{ColoredPiece, PieceColor, PieceKind},
+};
+
+// the kinds of features hashed, kept apart in the upper bits
+const PIECE: u64 = 1 << 56;
+const CASTLING: u64 = 2 << 56;
+const EN_PASSANT: u64 = 3 << 56;
+const TURN: u64 = 4 << 56;
+const POCKET: u64 = 5 << 56;
+const CHECKS: u64 = 6 << 56;
+const PROMOTED: u64 = 7 << 56;
+
+/// Position hash for boards of any size and every variant, used to find
+/// positions again during the search. Unlike `Polyglot::hash` the random
+/// numbers are computed instead of taken from a table, so that fairy pieces
+/// and large boards are covered as well.
+pub struct Zobrist;
+
+impl Zobrist {
+    pub fn hash(board: &Board) -> u64 {
+        let mut key = 0;
+        for location in board.locations() {
+            let Some((piece, _)) = board
+                .field_at_location(&location)
+                .and_then(|field| field.get_content())
+            else {
+                continue;
+            };
+            let square = Self::square(board, &location);
+            key ^= Self::random(PIECE | Self::piece_code(piece) << 16 | square);
+            if board.is_promoted(&location) {
+                key ^= Self::random(PROMOTED | square);
+            }
+        }
+
+        for color in [PieceColor::White, PieceColor::Black] {
+            let color_code = Self::color_code(color);
+            for file in board.castling_rights(color) {
+                key ^= Self::random(CASTLING | color_code << 16 | file as u64);
+            }
+            let checks = board.checks_given(color) as u64;
+            if checks > 0 {
+                key ^= Self::random(CHECKS | color_code << 16 | checks);
+            }
+            // every further piece of a kind in the pocket adds its own number
+            let mut count = 0;
+            let mut last = None;
+            for kind in board.pocket(color) {
+                count = if last == Some(*kind) { count + 1 } else { 1 };
+                last = Some(*kind);
+                let piece = Self::piece_code(ColoredPiece { kind: *kind, color });
+                key ^= Self::random(POCKET | piece << 16 | count);
+            }
+        }
+
+        if let Some(en_passant) = board.en_passant() {
+            key ^= Self::random(EN_PASSANT | Self::square(board, &en_passant));
+        }
+        if board.turn_color() == PieceColor::Black {
+            key ^= Self::random(TURN);
+        }
+        key
+    }
+
+    /// the splitmix64 finalizer, spreads every input over all 64 bits
+    fn random(input: u64) -> u64 {
+        let mut z = input.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn square(board: &Board, location: &Location) -> u64 {
+        (location.y * board.width + location.x) as u64
+    }
+
+    fn color_code(color: PieceColor) -> u64 {
+        match color {
+            PieceColor::White => 0,
+            PieceColor::Black => 1,
+        }
+    }
+
+    fn piece_code(piece: ColoredPiece) -> u64 {
+        let kind = match piece.kind {
+            PieceKind::Pawn => 0,
+            PieceKind::Knight => 1,
+            PieceKind::Bishop => 2,
+            PieceKind::Rook => 3,
+            PieceKind::Queen => 4,
+            PieceKind::King => 5,
+            PieceKind::Fairy(symbol) => 6 + symbol as u64,
+        };
+        kind << 1 | Self::color_code(piece.color)
+    }
+}
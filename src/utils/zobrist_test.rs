@@ -0,0 +1,48 @@
+use std::error::Error;
+
+use super::{fen::Fen, zobrist::Zobrist};
+use crate::{driver::game::Game, models::variant_kind::VariantKind};
+
+#[test]
+fn transpositions_hash_the_same() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let mut first = Game::new();
+    let mut second = Game::new();
+    for input in ["Nf3", "Nf6", "Nc3", "Nc6"] {
+        first.apply_input(input)?;
+    }
+    for input in ["Nc3", "Nc6", "Nf3", "Nf6"] {
+        second.apply_input(input)?;
+    }
+    // action
+    let first_hash = Zobrist::hash(&first.board);
+    let second_hash = Zobrist::hash(&second.board);
+    // assert
+    assert_eq!(first_hash, second_hash);
+    Ok(())
+}
+
+#[test]
+fn state_beyond_the_pieces_changes_the_hash() -> Result<(), Box<dyn Error>> {
+    // arrange
+    let fens = [
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq d6 0 1",
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R b KQkq d6 0 1",
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R w Kkq d6 0 1",
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R w KQkq - 0 1",
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R[Q] w KQkq - 0 1",
+        "r3k2r/8/8/3pP3/8/8/8/R3K2R[QQ] w KQkq - 0 1",
+    ];
+    // action
+    let mut hashes = vec![];
+    for fen in fens {
+        let mut board = Fen::decode(fen)?;
+        board.set_variant(VariantKind::Crazyhouse);
+        hashes.push(Zobrist::hash(&board));
+    }
+    // assert
+    for (index, hash) in hashes.iter().enumerate() {
+        assert!(!hashes[index + 1..].contains(hash), "{}", fens[index]);
+    }
+    Ok(())
+}